@@ -37,11 +37,82 @@ CREATE TABLE `sys_api` (
   `create_user` varchar(64) NOT NULL COMMENT '创建者',
   `create_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
   `update_user` varchar(64) DEFAULT NULL COMMENT '更新者',
+  `update_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  `deprecated` tinyint(4) NOT NULL DEFAULT 0 COMMENT '是否已废弃（路由已不存在）'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- --------------------------------------------------------
+
+--
+-- 表的结构 `sys_api_key`
+--
+
+CREATE TABLE `sys_api_key` (
+  `id` int(11) NOT NULL COMMENT '主键',
+  `user_id` int(11) NOT NULL COMMENT '所属用户ID',
+  `name` varchar(64) NOT NULL COMMENT 'API Key名称',
+  `key_prefix` varchar(32) NOT NULL COMMENT 'Key前缀，用于查找和展示',
+  `key_hash` char(64) NOT NULL COMMENT 'Key的SHA-256哈希',
+  `allowed_ips` varchar(1024) DEFAULT NULL COMMENT 'IP白名单，逗号分隔，支持CIDR，为空表示不限制',
+  `expire_time` timestamp NULL DEFAULT NULL COMMENT '过期时间',
+  `last_used_time` timestamp NULL DEFAULT NULL COMMENT '最后使用时间',
+  `last_used_ip` varchar(64) DEFAULT NULL COMMENT '最后使用IP',
+  `revoked` tinyint(4) NOT NULL DEFAULT 0 COMMENT '是否已吊销',
+  `create_user` varchar(64) NOT NULL COMMENT '创建者',
+  `create_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  `update_user` varchar(64) DEFAULT NULL COMMENT '更新者',
   `update_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间'
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
 
 -- --------------------------------------------------------
 
+--
+-- 表的结构 `sys_api_key_permission`
+--
+
+CREATE TABLE `sys_api_key_permission` (
+  `id` int(11) NOT NULL COMMENT '主键',
+  `api_key_id` int(11) NOT NULL COMMENT 'API Key ID',
+  `permission_id` int(11) NOT NULL COMMENT '权限ID'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- --------------------------------------------------------
+
+--
+-- 表的结构 `sys_dept`
+--
+
+CREATE TABLE `sys_dept` (
+  `id` int(11) NOT NULL COMMENT '主键',
+  `parent_id` int(11) DEFAULT NULL COMMENT '上级部门ID',
+  `dept_name` varchar(64) NOT NULL COMMENT '部门名称',
+  `leader_id` int(11) DEFAULT NULL COMMENT '负责人用户ID',
+  `order` int(11) NOT NULL DEFAULT '0' COMMENT '同级排序',
+  `create_user` varchar(64) NOT NULL COMMENT '创建者',
+  `create_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  `update_user` varchar(64) DEFAULT NULL COMMENT '更新者',
+  `update_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  `status` tinyint(1) NOT NULL DEFAULT '1' COMMENT '部门状态 1(enable)/2(disabled)'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- --------------------------------------------------------
+
+--
+-- 表的结构 `sys_login_log`
+--
+
+CREATE TABLE `sys_login_log` (
+  `id` int(11) NOT NULL COMMENT '主键',
+  `user_name` varchar(64) NOT NULL COMMENT '登录用户名',
+  `ip` varchar(64) DEFAULT NULL COMMENT '客户端IP',
+  `user_agent` varchar(512) DEFAULT NULL COMMENT '客户端User-Agent',
+  `result` enum('SUCCESS','FAILURE') NOT NULL COMMENT '登录结果',
+  `failure_reason` varchar(255) DEFAULT NULL COMMENT '失败原因',
+  `login_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '登录时间'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- --------------------------------------------------------
+
 --
 -- 表的结构 `sys_menu`
 --
@@ -49,7 +120,7 @@ CREATE TABLE `sys_api` (
 CREATE TABLE `sys_menu` (
   `id` int(11) NOT NULL COMMENT '主键',
   `parent_id` int(11) DEFAULT NULL COMMENT '父菜单ID',
-  `type` enum('DIRECTORY','MENU') NOT NULL DEFAULT 'MENU' COMMENT '菜单项类型：目录、菜单、按钮',
+  `type` enum('DIRECTORY','MENU','BUTTON') NOT NULL DEFAULT 'MENU' COMMENT '菜单项类型：目录、菜单、按钮',
   `menu_name` varchar(64) DEFAULT NULL COMMENT '菜单名称',
   `route_name` varchar(255) DEFAULT NULL COMMENT '路由名称',
   `route_path` varchar(255) DEFAULT NULL COMMENT '路由路径',
@@ -71,7 +142,8 @@ CREATE TABLE `sys_menu` (
   `create_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
   `update_user` varchar(64) DEFAULT NULL COMMENT '更新者',
   `update_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
-  `status` tinyint(1) NOT NULL DEFAULT '1' COMMENT '菜单状态 1(enable)/2(disabled)'
+  `status` tinyint(1) NOT NULL DEFAULT '1' COMMENT '菜单状态 1(enable)/2(disabled)',
+  `button_code` varchar(255) GENERATED ALWAYS AS (if(`type` = 'BUTTON',`route_name`,NULL)) STORED COMMENT '按钮编码，仅按钮有值，用于按钮编码唯一'
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
 
 --
@@ -102,16 +174,17 @@ CREATE TABLE `sys_permission` (
   `create_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
   `update_user` varchar(64) DEFAULT NULL COMMENT '更新者',
   `update_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
-  `status` tinyint(1) NOT NULL DEFAULT '1' COMMENT '权限状态 1(enable)/2(disabled)'
+  `status` tinyint(1) NOT NULL DEFAULT '1' COMMENT '权限状态 1(enable)/2(disabled)',
+  `effect` enum('ALLOW','DENY') NOT NULL DEFAULT 'ALLOW' COMMENT '权限效果，DENY 优先于 ALLOW'
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
 
 --
 -- 转存表中的数据 `sys_permission`
 --
 
-INSERT INTO `sys_permission` (`id`, `permission_name`, `permission_code`, `description`, `create_user`, `create_time`, `update_user`, `update_time`, `status`) VALUES
-(1, 'quanxian1', 'qx1', '权限1', 'admin', '2024-06-14 06:43:06', NULL, '2024-06-14 06:43:06', 1),
-(2, 'quanxian2', 'qx2', '权限2', 'admin', '2024-06-14 06:43:25', NULL, '2024-06-14 06:43:25', 1);
+INSERT INTO `sys_permission` (`id`, `permission_name`, `permission_code`, `description`, `create_user`, `create_time`, `update_user`, `update_time`, `status`, `effect`) VALUES
+(1, 'quanxian1', 'qx1', '权限1', 'admin', '2024-06-14 06:43:06', NULL, '2024-06-14 06:43:06', 1, 'ALLOW'),
+(2, 'quanxian2', 'qx2', '权限2', 'admin', '2024-06-14 06:43:25', NULL, '2024-06-14 06:43:25', 1, 'ALLOW');
 
 -- --------------------------------------------------------
 
@@ -174,6 +247,10 @@ CREATE TABLE `sys_role` (
   `role_code` varchar(64) NOT NULL COMMENT '角色code',
   `role_name` varchar(64) NOT NULL COMMENT '角色名称',
   `description` varchar(255) DEFAULT NULL COMMENT '描述',
+  `home` varchar(64) DEFAULT NULL COMMENT '角色首页路由名称',
+  `require_two_factor` tinyint(1) NOT NULL DEFAULT '0' COMMENT '是否要求两步验证 0(否)/1(是)',
+  `parent_role_id` int(11) DEFAULT NULL COMMENT '继承的父角色ID，拥有父角色及其祖先的全部权限',
+  `data_scope` enum('ALL','CUSTOM','DEPT','DEPT_AND_CHILD','SELF_ONLY') NOT NULL DEFAULT 'ALL' COMMENT '数据权限范围：全部、自定义部门、本部门、本部门及下级、仅本人',
   `create_user` varchar(64) NOT NULL COMMENT '创建者',
   `create_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
   `update_user` varchar(64) DEFAULT NULL COMMENT '更新者',
@@ -185,8 +262,20 @@ CREATE TABLE `sys_role` (
 -- 转存表中的数据 `sys_role`
 --
 
-INSERT INTO `sys_role` (`id`, `role_code`, `role_name`, `description`, `create_user`, `create_time`, `update_user`, `update_time`, `status`) VALUES
-(1, 'admin', '管理员', '系统管理员', 'admin', '2024-06-15 04:44:26', NULL, '2024-07-19 02:07:21', 1);
+INSERT INTO `sys_role` (`id`, `role_code`, `role_name`, `description`, `home`, `create_user`, `create_time`, `update_user`, `update_time`, `status`) VALUES
+(1, 'admin', '管理员', '系统管理员', 'home', 'admin', '2024-06-15 04:44:26', NULL, '2024-07-19 02:07:21', 1);
+
+-- --------------------------------------------------------
+
+--
+-- 表的结构 `sys_role_dept`
+--
+
+CREATE TABLE `sys_role_dept` (
+  `id` int(11) NOT NULL COMMENT '主键',
+  `role_id` int(11) NOT NULL COMMENT '角色ID',
+  `dept_id` int(11) NOT NULL COMMENT '部门ID'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
 
 -- --------------------------------------------------------
 
@@ -232,7 +321,11 @@ CREATE TABLE `sys_user` (
   `update_user` varchar(64) DEFAULT NULL COMMENT '更新者',
   `update_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
   `last_login` timestamp NULL DEFAULT NULL COMMENT '上次登录时间',
-  `status` tinyint(1) NOT NULL DEFAULT '1' COMMENT '用户状态 1(enable)/2(disabled)'
+  `status` tinyint(1) NOT NULL DEFAULT '1' COMMENT '用户状态 1(enable)/2(disabled)',
+  `totp_secret` varchar(64) DEFAULT NULL COMMENT 'TOTP 密钥(base32)',
+  `totp_enabled` tinyint(1) NOT NULL DEFAULT '0' COMMENT '是否启用两步验证 0(否)/1(是)',
+  `recovery_codes` varchar(1024) DEFAULT NULL COMMENT '恢复码哈希，逗号分隔',
+  `dept_id` int(11) DEFAULT NULL COMMENT '所属部门ID'
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
 
 --
@@ -244,6 +337,20 @@ INSERT INTO `sys_user` (`id`, `user_name`, `nick_name`, `password`, `email`, `ge
 
 -- --------------------------------------------------------
 
+--
+-- 表的结构 `sys_user_identity`
+--
+
+CREATE TABLE `sys_user_identity` (
+  `id` int(11) NOT NULL COMMENT '主键',
+  `user_id` int(11) NOT NULL COMMENT '用户ID',
+  `provider` varchar(64) NOT NULL COMMENT '身份提供方标识',
+  `subject` varchar(255) NOT NULL COMMENT '提供方中的用户标识(sub)',
+  `create_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- --------------------------------------------------------
+
 --
 -- 表的结构 `sys_user_role`
 --
@@ -265,6 +372,23 @@ CREATE TABLE `sys_user_role` (
 INSERT INTO `sys_user_role` (`id`, `user_id`, `role_id`, `create_user`, `create_time`, `update_user`, `update_time`) VALUES
 (7, 1, 1, 'admin', '2024-06-20 08:36:44', NULL, '2024-06-20 08:36:44');
 
+-- --------------------------------------------------------
+
+--
+-- 表的结构 `sys_user_permission`
+--
+
+CREATE TABLE `sys_user_permission` (
+  `id` int(11) NOT NULL COMMENT '主键',
+  `user_id` int(11) NOT NULL COMMENT '用户ID',
+  `permission_id` int(11) NOT NULL COMMENT '权限ID',
+  `effect` enum('ALLOW','DENY') NOT NULL DEFAULT 'ALLOW' COMMENT '授予或拒绝，DENY 优先于 ALLOW',
+  `create_user` varchar(64) NOT NULL COMMENT '创建者',
+  `create_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  `update_user` varchar(64) DEFAULT NULL COMMENT '更新者',
+  `update_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
 --
 -- 转储表的索引
 --
@@ -278,13 +402,47 @@ ALTER TABLE `sys_api`
   ADD KEY `idx_api_group` (`api_group`),
   ADD KEY `idx_api_path` (`api_path`);
 
+--
+-- 表的索引 `sys_api_key`
+--
+ALTER TABLE `sys_api_key`
+  ADD PRIMARY KEY (`id`),
+  ADD UNIQUE KEY `uk_key_prefix` (`key_prefix`),
+  ADD KEY `idx_user_id` (`user_id`);
+
+--
+-- 表的索引 `sys_api_key_permission`
+--
+ALTER TABLE `sys_api_key_permission`
+  ADD PRIMARY KEY (`id`),
+  ADD KEY `idx_api_key_id` (`api_key_id`),
+  ADD KEY `idx_permission_id` (`permission_id`);
+
+--
+-- 表的索引 `sys_dept`
+--
+ALTER TABLE `sys_dept`
+  ADD PRIMARY KEY (`id`),
+  ADD KEY `idx_parent_id` (`parent_id`),
+  ADD KEY `idx_leader_id` (`leader_id`);
+
+--
+-- 表的索引 `sys_login_log`
+--
+ALTER TABLE `sys_login_log`
+  ADD PRIMARY KEY (`id`),
+  ADD KEY `idx_user_name` (`user_name`),
+  ADD KEY `idx_ip` (`ip`),
+  ADD KEY `idx_login_time` (`login_time`);
+
 --
 -- 表的索引 `sys_menu`
 --
 ALTER TABLE `sys_menu`
   ADD PRIMARY KEY (`id`),
   ADD KEY `idx_menu_name` (`menu_name`),
-  ADD KEY `idx_parent_id` (`parent_id`);
+  ADD KEY `idx_parent_id` (`parent_id`),
+  ADD UNIQUE KEY `uk_button_code` (`button_code`);
 
 --
 -- 表的索引 `sys_permission`
@@ -317,7 +475,16 @@ ALTER TABLE `sys_permission_target`
 ALTER TABLE `sys_role`
   ADD PRIMARY KEY (`id`),
   ADD UNIQUE KEY `role_code` (`role_code`),
-  ADD UNIQUE KEY `role_name` (`role_name`);
+  ADD UNIQUE KEY `role_name` (`role_name`),
+  ADD KEY `idx_parent_role_id` (`parent_role_id`);
+
+--
+-- 表的索引 `sys_role_dept`
+--
+ALTER TABLE `sys_role_dept`
+  ADD PRIMARY KEY (`id`),
+  ADD UNIQUE KEY `uk_role_dept` (`role_id`,`dept_id`),
+  ADD KEY `idx_dept_id` (`dept_id`);
 
 --
 -- 表的索引 `sys_role_permission`
@@ -336,7 +503,16 @@ ALTER TABLE `sys_user`
   ADD UNIQUE KEY `email` (`email`),
   ADD UNIQUE KEY `mobile` (`mobile`),
   ADD KEY `idx_user_name` (`user_name`),
-  ADD KEY `idx_mobile` (`mobile`);
+  ADD KEY `idx_mobile` (`mobile`),
+  ADD KEY `idx_dept_id` (`dept_id`);
+
+--
+-- 表的索引 `sys_user_identity`
+--
+ALTER TABLE `sys_user_identity`
+  ADD PRIMARY KEY (`id`),
+  ADD UNIQUE KEY `uk_provider_subject` (`provider`,`subject`),
+  ADD KEY `idx_user_id` (`user_id`);
 
 --
 -- 表的索引 `sys_user_role`
@@ -346,6 +522,14 @@ ALTER TABLE `sys_user_role`
   ADD KEY `idx_user_id` (`user_id`),
   ADD KEY `idx_role_id` (`role_id`);
 
+--
+-- 表的索引 `sys_user_permission`
+--
+ALTER TABLE `sys_user_permission`
+  ADD PRIMARY KEY (`id`),
+  ADD UNIQUE KEY `uk_user_permission` (`user_id`,`permission_id`),
+  ADD KEY `idx_permission_id` (`permission_id`);
+
 --
 -- 在导出的表使用AUTO_INCREMENT
 --
@@ -356,6 +540,30 @@ ALTER TABLE `sys_user_role`
 ALTER TABLE `sys_api`
   MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键';
 
+--
+-- 使用表AUTO_INCREMENT `sys_api_key`
+--
+ALTER TABLE `sys_api_key`
+  MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键';
+
+--
+-- 使用表AUTO_INCREMENT `sys_api_key_permission`
+--
+ALTER TABLE `sys_api_key_permission`
+  MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键';
+
+--
+-- 使用表AUTO_INCREMENT `sys_dept`
+--
+ALTER TABLE `sys_dept`
+  MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键';
+
+--
+-- 使用表AUTO_INCREMENT `sys_login_log`
+--
+ALTER TABLE `sys_login_log`
+  MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键';
+
 --
 -- 使用表AUTO_INCREMENT `sys_menu`
 --
@@ -380,6 +588,12 @@ ALTER TABLE `sys_permission_action`
 ALTER TABLE `sys_role`
   MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键', AUTO_INCREMENT=2;
 
+--
+-- 使用表AUTO_INCREMENT `sys_role_dept`
+--
+ALTER TABLE `sys_role_dept`
+  MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键';
+
 --
 -- 使用表AUTO_INCREMENT `sys_role_permission`
 --
@@ -392,16 +606,48 @@ ALTER TABLE `sys_role_permission`
 ALTER TABLE `sys_user`
   MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键', AUTO_INCREMENT=7;
 
+--
+-- 使用表AUTO_INCREMENT `sys_user_identity`
+--
+ALTER TABLE `sys_user_identity`
+  MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键';
+
 --
 -- 使用表AUTO_INCREMENT `sys_user_role`
 --
 ALTER TABLE `sys_user_role`
   MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键', AUTO_INCREMENT=10;
 
+--
+-- 使用表AUTO_INCREMENT `sys_user_permission`
+--
+ALTER TABLE `sys_user_permission`
+  MODIFY `id` int(11) NOT NULL AUTO_INCREMENT COMMENT '主键';
+
 --
 -- 限制导出的表
 --
 
+--
+-- 限制表 `sys_api_key`
+--
+ALTER TABLE `sys_api_key`
+  ADD CONSTRAINT `sys_api_key_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE;
+
+--
+-- 限制表 `sys_api_key_permission`
+--
+ALTER TABLE `sys_api_key_permission`
+  ADD CONSTRAINT `sys_api_key_permission_ibfk_1` FOREIGN KEY (`api_key_id`) REFERENCES `sys_api_key` (`id`) ON DELETE CASCADE,
+  ADD CONSTRAINT `sys_api_key_permission_ibfk_2` FOREIGN KEY (`permission_id`) REFERENCES `sys_permission` (`id`) ON DELETE CASCADE;
+
+--
+-- 限制表 `sys_dept`
+--
+ALTER TABLE `sys_dept`
+  ADD CONSTRAINT `sys_dept_ibfk_1` FOREIGN KEY (`parent_id`) REFERENCES `sys_dept` (`id`),
+  ADD CONSTRAINT `sys_dept_ibfk_2` FOREIGN KEY (`leader_id`) REFERENCES `sys_user` (`id`) ON DELETE SET NULL;
+
 --
 -- 限制表 `sys_permission_action`
 --
@@ -414,6 +660,19 @@ ALTER TABLE `sys_permission_action`
 ALTER TABLE `sys_permission_target`
   ADD CONSTRAINT `sys_permission_target_ibfk_1` FOREIGN KEY (`permission_id`) REFERENCES `sys_permission` (`id`) ON DELETE CASCADE;
 
+--
+-- 限制表 `sys_role`
+--
+ALTER TABLE `sys_role`
+  ADD CONSTRAINT `sys_role_ibfk_1` FOREIGN KEY (`parent_role_id`) REFERENCES `sys_role` (`id`) ON DELETE SET NULL;
+
+--
+-- 限制表 `sys_role_dept`
+--
+ALTER TABLE `sys_role_dept`
+  ADD CONSTRAINT `sys_role_dept_ibfk_1` FOREIGN KEY (`role_id`) REFERENCES `sys_role` (`id`) ON DELETE CASCADE,
+  ADD CONSTRAINT `sys_role_dept_ibfk_2` FOREIGN KEY (`dept_id`) REFERENCES `sys_dept` (`id`) ON DELETE CASCADE;
+
 --
 -- 限制表 `sys_role_permission`
 --
@@ -421,12 +680,31 @@ ALTER TABLE `sys_role_permission`
   ADD CONSTRAINT `sys_role_permission_ibfk_1` FOREIGN KEY (`role_id`) REFERENCES `sys_role` (`id`) ON DELETE CASCADE,
   ADD CONSTRAINT `sys_role_permission_ibfk_2` FOREIGN KEY (`permission_id`) REFERENCES `sys_permission` (`id`) ON DELETE CASCADE;
 
+--
+-- 限制表 `sys_user`
+--
+ALTER TABLE `sys_user`
+  ADD CONSTRAINT `sys_user_ibfk_1` FOREIGN KEY (`dept_id`) REFERENCES `sys_dept` (`id`) ON DELETE SET NULL;
+
+--
+-- 限制表 `sys_user_identity`
+--
+ALTER TABLE `sys_user_identity`
+  ADD CONSTRAINT `sys_user_identity_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE;
+
 --
 -- 限制表 `sys_user_role`
 --
 ALTER TABLE `sys_user_role`
   ADD CONSTRAINT `sys_user_role_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE,
   ADD CONSTRAINT `sys_user_role_ibfk_2` FOREIGN KEY (`role_id`) REFERENCES `sys_role` (`id`) ON DELETE CASCADE;
+
+--
+-- 限制表 `sys_user_permission`
+--
+ALTER TABLE `sys_user_permission`
+  ADD CONSTRAINT `sys_user_permission_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE,
+  ADD CONSTRAINT `sys_user_permission_ibfk_2` FOREIGN KEY (`permission_id`) REFERENCES `sys_permission` (`id`) ON DELETE CASCADE;
 COMMIT;
 
 /*!40101 SET CHARACTER_SET_CLIENT=@OLD_CHARACTER_SET_CLIENT */;
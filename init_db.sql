@@ -1,3 +1,21 @@
+-- 部门表
+DROP TABLE IF EXISTS sys_dept;
+CREATE TABLE sys_dept (
+                          id INT AUTO_INCREMENT PRIMARY KEY COMMENT '主键',
+                          parent_id INT DEFAULT NULL COMMENT '上级部门ID',
+                          dept_name VARCHAR(64) NOT NULL COMMENT '部门名称',
+                          leader_id INT DEFAULT NULL COMMENT '负责人用户ID',
+                          `order` INT NOT NULL DEFAULT 0 COMMENT '同级排序',
+                          create_user VARCHAR(64) NOT NULL COMMENT '创建者',
+                          create_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+                          update_user VARCHAR(64) COMMENT '更新者',
+                          update_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+                          status TINYINT(1) NOT NULL DEFAULT 1 COMMENT '部门状态 1(enable)/2(disabled)',
+                          FOREIGN KEY (parent_id) REFERENCES sys_dept(id),
+                          INDEX idx_parent_id (parent_id),
+                          INDEX idx_leader_id (leader_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
 -- 用户表
 DROP TABLE IF EXISTS sys_user;
 CREATE TABLE sys_user (
@@ -15,10 +33,20 @@ CREATE TABLE sys_user (
                           update_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
                           last_login TIMESTAMP COMMENT '上次登录时间',
                           status TINYINT(1) NOT NULL DEFAULT 1 COMMENT '用户状态 1(enable)/2(disabled)',
+                          totp_secret VARCHAR(64) COMMENT 'TOTP 密钥(base32)',
+                          totp_enabled TINYINT(1) NOT NULL DEFAULT 0 COMMENT '是否启用两步验证 0(否)/1(是)',
+                          recovery_codes VARCHAR(1024) COMMENT '恢复码哈希，逗号分隔',
+                          dept_id INT DEFAULT NULL COMMENT '所属部门ID',
+                          FOREIGN KEY (dept_id) REFERENCES sys_dept(id) ON DELETE SET NULL,
                           INDEX idx_user_name (user_name),
-                          INDEX idx_mobile (mobile)
+                          INDEX idx_mobile (mobile),
+                          INDEX idx_dept_id (dept_id)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
 
+-- 部门负责人引用用户表，需在用户表创建后添加
+ALTER TABLE sys_dept
+    ADD FOREIGN KEY (leader_id) REFERENCES sys_user(id) ON DELETE SET NULL;
+
 -- 角色表
 DROP TABLE IF EXISTS sys_role;
 CREATE TABLE sys_role (
@@ -26,11 +54,29 @@ CREATE TABLE sys_role (
                           role_code VARCHAR(64) NOT NULL UNIQUE COMMENT '角色code',
                           role_name VARCHAR(64) NOT NULL UNIQUE COMMENT '角色名称',
                           description VARCHAR(255) COMMENT '描述',
+                          home VARCHAR(64) COMMENT '角色首页路由名称',
+                          require_two_factor TINYINT(1) NOT NULL DEFAULT 0 COMMENT '是否要求两步验证 0(否)/1(是)',
+                          parent_role_id INT DEFAULT NULL COMMENT '继承的父角色ID，拥有父角色及其祖先的全部权限',
+                          data_scope ENUM('ALL', 'CUSTOM', 'DEPT', 'DEPT_AND_CHILD', 'SELF_ONLY') NOT NULL DEFAULT 'ALL' COMMENT '数据权限范围：全部、自定义部门、本部门、本部门及下级、仅本人',
                           create_user VARCHAR(64) NOT NULL COMMENT '创建者',
                           create_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
                           update_user VARCHAR(64) COMMENT '更新者',
                           update_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
-                          status TINYINT(1) NOT NULL DEFAULT 1 COMMENT '角色状态 1(enable)/2(disabled)'
+                          status TINYINT(1) NOT NULL DEFAULT 1 COMMENT '角色状态 1(enable)/2(disabled)',
+                          FOREIGN KEY (parent_role_id) REFERENCES sys_role(id) ON DELETE SET NULL,
+                          INDEX idx_parent_role_id (parent_role_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+-- 角色自定义数据权限的部门
+DROP TABLE IF EXISTS sys_role_dept;
+CREATE TABLE sys_role_dept (
+                          id INT AUTO_INCREMENT PRIMARY KEY COMMENT '主键',
+                          role_id INT NOT NULL COMMENT '角色ID',
+                          dept_id INT NOT NULL COMMENT '部门ID',
+                          FOREIGN KEY (role_id) REFERENCES sys_role(id) ON DELETE CASCADE,
+                          FOREIGN KEY (dept_id) REFERENCES sys_dept(id) ON DELETE CASCADE,
+                          UNIQUE INDEX uk_role_dept (role_id, dept_id),
+                          INDEX idx_dept_id (dept_id)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
 
 -- 删除 sys_permission 表如果存在
@@ -47,6 +93,7 @@ DROP TABLE IF EXISTS sys_permission;
                           update_user VARCHAR(64) COMMENT '更新者',
                           update_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
                           status TINYINT(1) NOT NULL DEFAULT 1 COMMENT '权限状态 1(enable)/2(disabled)',
+                          effect ENUM('ALLOW', 'DENY') NOT NULL DEFAULT 'ALLOW' COMMENT '权限效果，DENY 优先于 ALLOW',
                           INDEX idx_permission_code (permission_code)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
 
@@ -58,7 +105,7 @@ DROP TABLE IF EXISTS sys_menu;
 CREATE TABLE sys_menu (
                         id INT AUTO_INCREMENT PRIMARY KEY COMMENT '主键',
                         parent_id INT DEFAULT NULL COMMENT '父菜单ID',
-                        type ENUM('DIRECTORY', 'MENU') NOT NULL DEFAULT 'MENU' COMMENT '菜单项类型：目录、菜单、按钮',
+                        type ENUM('DIRECTORY', 'MENU', 'BUTTON') NOT NULL DEFAULT 'MENU' COMMENT '菜单项类型：目录、菜单、按钮',
                         menu_name VARCHAR(64) COMMENT '菜单名称',
                         route_name VARCHAR(255) COMMENT '路由名称',
                         route_path VARCHAR(255) COMMENT '路由路径',
@@ -81,8 +128,10 @@ CREATE TABLE sys_menu (
                         update_user VARCHAR(64) COMMENT '更新者',
                         update_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
                         status TINYINT(1) NOT NULL DEFAULT 1 COMMENT '菜单状态 1(enable)/2(disabled)',
+                        button_code VARCHAR(255) GENERATED ALWAYS AS (IF(type = 'BUTTON', route_name, NULL)) STORED COMMENT '按钮编码，仅按钮有值，用于按钮编码唯一',
                         INDEX idx_menu_name (menu_name),
-                        INDEX idx_parent_id (parent_id)
+                        INDEX idx_parent_id (parent_id),
+                        UNIQUE INDEX uk_button_code (button_code)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
 
 
@@ -99,6 +148,7 @@ CREATE TABLE sys_api (
                          create_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
                          update_user VARCHAR(64) COMMENT '更新者',
                          update_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+                         deprecated TINYINT NOT NULL DEFAULT 0 COMMENT '是否已废弃（路由已不存在）',
                          INDEX idx_api_group (api_group),
                          INDEX idx_api_path (api_path),
                          UNIQUE INDEX idx_method_path (api_path, api_method)
@@ -147,6 +197,24 @@ CREATE TABLE sys_user_role (
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
 
 
+-- 用户直接授权表，用于给单个用户额外授予或拒绝权限
+DROP TABLE IF EXISTS sys_user_permission;
+CREATE TABLE sys_user_permission (
+                         id INT AUTO_INCREMENT PRIMARY KEY COMMENT '主键',
+                         user_id INT NOT NULL COMMENT '用户ID',
+                         permission_id INT NOT NULL COMMENT '权限ID',
+                         effect ENUM('ALLOW', 'DENY') NOT NULL DEFAULT 'ALLOW' COMMENT '授予或拒绝，DENY 优先于 ALLOW',
+                         create_user VARCHAR(64) NOT NULL COMMENT '创建者',
+                         create_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+                         update_user VARCHAR(64) COMMENT '更新者',
+                         update_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+                         FOREIGN KEY (user_id) REFERENCES sys_user(id) ON DELETE CASCADE,
+                         FOREIGN KEY (permission_id) REFERENCES sys_permission(id) ON DELETE CASCADE,
+                         UNIQUE KEY uk_user_permission (user_id, permission_id),
+                         INDEX idx_permission_id (permission_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+
 
 -- 角色权限关联表
 DROP TABLE IF EXISTS sys_role_permission;
@@ -165,4 +233,66 @@ CREATE TABLE sys_role_permission (
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
 
 
+-- 登录日志表
+DROP TABLE IF EXISTS sys_login_log;
+CREATE TABLE sys_login_log (
+                         id INT AUTO_INCREMENT PRIMARY KEY COMMENT '主键',
+                         user_name VARCHAR(64) NOT NULL COMMENT '登录用户名',
+                         ip VARCHAR(64) COMMENT '客户端IP',
+                         user_agent VARCHAR(512) COMMENT '客户端User-Agent',
+                         result ENUM('SUCCESS', 'FAILURE') NOT NULL COMMENT '登录结果',
+                         failure_reason VARCHAR(255) COMMENT '失败原因',
+                         login_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP COMMENT '登录时间',
+                         INDEX idx_user_name (user_name),
+                         INDEX idx_ip (ip),
+                         INDEX idx_login_time (login_time)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+
+-- API Key 表，只保存哈希，明文只在创建时返回一次
+DROP TABLE IF EXISTS sys_api_key_permission;
+DROP TABLE IF EXISTS sys_api_key;
+CREATE TABLE sys_api_key (
+                         id INT AUTO_INCREMENT PRIMARY KEY COMMENT '主键',
+                         user_id INT NOT NULL COMMENT '所属用户ID',
+                         name VARCHAR(64) NOT NULL COMMENT 'API Key名称',
+                         key_prefix VARCHAR(32) NOT NULL COMMENT 'Key前缀，用于查找和展示',
+                         key_hash CHAR(64) NOT NULL COMMENT 'Key的SHA-256哈希',
+                         allowed_ips VARCHAR(1024) COMMENT 'IP白名单，逗号分隔，支持CIDR，为空表示不限制',
+                         expire_time TIMESTAMP NULL COMMENT '过期时间',
+                         last_used_time TIMESTAMP NULL COMMENT '最后使用时间',
+                         last_used_ip VARCHAR(64) COMMENT '最后使用IP',
+                         revoked TINYINT NOT NULL DEFAULT 0 COMMENT '是否已吊销',
+                         create_user VARCHAR(64) NOT NULL COMMENT '创建者',
+                         create_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+                         update_user VARCHAR(64) COMMENT '更新者',
+                         update_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+                         FOREIGN KEY (user_id) REFERENCES sys_user(id) ON DELETE CASCADE,
+                         UNIQUE INDEX uk_key_prefix (key_prefix),
+                         INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
 
+-- API Key 权限关联表，只能是所属用户权限的子集
+CREATE TABLE sys_api_key_permission (
+                         id INT AUTO_INCREMENT PRIMARY KEY COMMENT '主键',
+                         api_key_id INT NOT NULL COMMENT 'API Key ID',
+                         permission_id INT NOT NULL COMMENT '权限ID',
+                         FOREIGN KEY (api_key_id) REFERENCES sys_api_key(id) ON DELETE CASCADE,
+                         FOREIGN KEY (permission_id) REFERENCES sys_permission(id) ON DELETE CASCADE,
+                         INDEX idx_api_key_id (api_key_id),
+                         INDEX idx_permission_id (permission_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
+
+-- 第三方身份绑定表，记录 OIDC 提供方的用户标识与本地用户的对应关系
+DROP TABLE IF EXISTS sys_user_identity;
+CREATE TABLE sys_user_identity (
+                         id INT AUTO_INCREMENT PRIMARY KEY COMMENT '主键',
+                         user_id INT NOT NULL COMMENT '用户ID',
+                         provider VARCHAR(64) NOT NULL COMMENT '身份提供方标识',
+                         subject VARCHAR(255) NOT NULL COMMENT '提供方中的用户标识(sub)',
+                         create_time TIMESTAMP DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+                         FOREIGN KEY (user_id) REFERENCES sys_user(id) ON DELETE CASCADE,
+                         UNIQUE KEY uk_provider_subject (provider, subject),
+                         INDEX idx_user_id (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
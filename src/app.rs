@@ -1,8 +1,12 @@
 // 引入所需的模块
+use crate::common::auth::jwt;
 use crate::common::db;
 use crate::common::log as logger;
+use crate::common::mail;
 use crate::common::redis;
 use crate::config::globals;
+use crate::handlers;
+use crate::services::admin::sys_api_services;
 use actix::Addr;
 use actix_redis::{Error, RedisActor};
 use log::{error, info};
@@ -35,6 +39,14 @@ pub async fn initialize() {
     // 初始化日志系统
     logger::config::init();
 
+    // 加载 JWT 签名 key，配置有误时启动失败而不是等到第一次登录
+    let signing_key = jwt::JWT_KEYS.signing_key();
+    info!(
+        "jwt signing key loaded, kid: {}, algorithm: {:?}",
+        signing_key.kid.as_deref().unwrap_or("-"),
+        signing_key.algorithm
+    );
+
     // 初始化 Redis
     let redis_conn = match init_redis(&globals::APP_CONFIG.redis).await {
         Ok(conn) => conn,
@@ -53,8 +65,32 @@ pub async fn initialize() {
         }
     };
 
+    // 将已注册的路由同步到 sys_api
+    if globals::APP_CONFIG.permission.sync_api_on_startup {
+        match sys_api_services::sync_api_routes(
+            &mysql_conn,
+            handlers::admin::system_manage_routes(),
+            handlers::admin::SYSTEM_MANAGE_SCOPE,
+            false,
+            "system".to_string(),
+        )
+        .await
+        {
+            Ok(diff) => info!(
+                "api routes synced, created: {}, regrouped: {}, restored: {}, deprecated: {}",
+                diff.created.len(),
+                diff.regrouped.len(),
+                diff.restored.len(),
+                diff.deprecated.len()
+            ),
+            Err(e) => error!("Api routes sync failed: {}", e),
+        }
+    }
+
     // 初始化全局 AppState
-    let app_state = globals::AppState::new(redis_conn, mysql_conn);
+    let mail_sender =
+        mail::build_mail_sender(&globals::APP_CONFIG.mail).expect("Failed to build mail sender");
+    let app_state = globals::AppState::new(redis_conn, mysql_conn, mail_sender);
 
     globals::set_app_state(app_state).expect("Failed to set global APP_STATE");
 }
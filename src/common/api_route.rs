@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+// 通过 api_routes! 注册的接口，用于同步到 sys_api
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiRoute {
+    pub api_name: String,
+    pub api_group: String, // 所在的 handler 模块，如 sys_user
+    pub api_path: String,
+    pub api_method: String,
+}
+
+impl ApiRoute {
+    pub fn new(module_path: &str, api_name: &str, api_method: &str, api_path: &str) -> Self {
+        let module = module_path.rsplit("::").next().unwrap_or(module_path);
+        ApiRoute {
+            api_name: api_name.to_string(),
+            api_group: module.trim_end_matches("_handler").to_string(),
+            api_path: api_path.to_string(),
+            api_method: api_method.to_string(),
+        }
+    }
+
+    // 加上 scope 前缀，得到完整的请求路径
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.api_path = format!("{}{}", prefix, self.api_path);
+        self
+    }
+}
+
+// 同时生成 api_config 和 api_routes，路由直接按清单注册，handler 上不再单独写路由属性，两者不会不一致
+// 路由按书写顺序注册，固定路径要写在同前缀的带参数路径之前，如 /permissions/simple 要在 /permissions/{id} 之前
+#[macro_export]
+macro_rules! api_routes {
+    ($($handler:ident => $method:ident $path:literal),* $(,)?) => {
+        pub fn api_config(cfg: &mut actix_web::web::ServiceConfig) {
+            $(cfg.service(
+                actix_web::web::resource($path)
+                    .name(stringify!($handler))
+                    .guard(actix_web::guard::Method(actix_web::http::Method::$method))
+                    .to($handler),
+            );)*
+        }
+
+        pub fn api_routes() -> Vec<$crate::common::api_route::ApiRoute> {
+            vec![$($crate::common::api_route::ApiRoute::new(
+                module_path!(),
+                stringify!($handler),
+                stringify!($method),
+                $path,
+            )),*]
+        }
+    };
+}
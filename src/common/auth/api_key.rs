@@ -0,0 +1,134 @@
+use crate::common::auth::crypto::generate_random_token;
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+
+// 通过 API Key 认证的请求，JWTAuth 将其放入请求扩展，用于限制可用的权限
+#[derive(Debug, Clone)]
+pub struct ApiKeyScope {
+    pub api_key_id: i32,
+    pub permission_ids: Vec<i32>,
+}
+
+const KEY_ID_LENGTH: usize = 8;
+const KEY_SECRET_LENGTH: usize = 40;
+
+/// 生成 API Key，返回完整的 Key 和用于查找的 Key 前缀。
+///
+/// Key 形如 `{prefix}{8位ID}_{40位密钥}`，Key 前缀为 `{prefix}{8位ID}`。
+pub fn generate_api_key(prefix: &str) -> (String, String) {
+    let key_prefix = format!("{}{}", prefix, generate_random_token(KEY_ID_LENGTH));
+    let key = format!(
+        "{}_{}",
+        key_prefix,
+        generate_random_token(KEY_SECRET_LENGTH)
+    );
+    (key, key_prefix)
+}
+
+/// 从完整的 Key 中取出 Key 前缀，格式不正确时返回 None。
+pub fn split_key_prefix<'a>(key: &'a str, prefix: &str) -> Option<&'a str> {
+    let rest = key.strip_prefix(prefix)?;
+    let (key_id, secret) = rest.split_once('_')?;
+    let is_valid = |part: &str, len: usize| {
+        part.len() == len && part.chars().all(|c| c.is_ascii_alphanumeric())
+    };
+    if !is_valid(key_id, KEY_ID_LENGTH) || !is_valid(secret, KEY_SECRET_LENGTH) {
+        return None;
+    }
+    Some(&key[..prefix.len() + KEY_ID_LENGTH])
+}
+
+/// 计算 Key 的 SHA-256 哈希（十六进制）。Key 本身是高熵随机串，不需要慢哈希。
+pub fn hash_api_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// 以固定时间比较 Key 与保存的哈希是否匹配。
+pub fn verify_api_key(key: &str, key_hash: &str) -> bool {
+    let hash = hash_api_key(key);
+    hash.len() == key_hash.len()
+        && hash
+            .bytes()
+            .zip(key_hash.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// IP 白名单中的一条规则：单个 IP 或 CIDR 网段。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRule {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpRule {
+    /// 解析 `10.0.0.1`、`10.0.0.0/8`、`2001:db8::/32` 形式的规则。
+    pub fn parse(rule: &str) -> Option<IpRule> {
+        let rule = rule.trim();
+        let (addr, prefix_len) = match rule.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len.parse::<u8>().ok()?)),
+            None => (rule, None),
+        };
+        let network = addr.parse::<IpAddr>().ok()?.to_canonical();
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len.unwrap_or(max_len);
+        if prefix_len > max_len {
+            return None;
+        }
+        Some(IpRule {
+            network,
+            prefix_len,
+        })
+    }
+
+    /// 判断 IP 是否在规则范围内，IPv4 映射的 IPv6 地址按 IPv4 处理。
+    pub fn matches(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                prefix_eq(&network.octets(), &ip.octets(), self.prefix_len)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_eq(&network.octets(), &ip.octets(), self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+// 比较两个地址的前 prefix_len 位
+fn prefix_eq(a: &[u8], b: &[u8], prefix_len: u8) -> bool {
+    let full_bytes = (prefix_len / 8) as usize;
+    let rest_bits = prefix_len % 8;
+    if a[..full_bytes] != b[..full_bytes] {
+        return false;
+    }
+    if rest_bits == 0 {
+        return true;
+    }
+    let mask = 0xffu8 << (8 - rest_bits);
+    a[full_bytes] & mask == b[full_bytes] & mask
+}
+
+/// 解析逗号分隔的 IP 白名单，返回第一条无法解析的规则作为错误。
+pub fn parse_ip_allowlist(allowed_ips: &str) -> Result<Vec<IpRule>, String> {
+    allowed_ips
+        .split(',')
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(|rule| IpRule::parse(rule).ok_or_else(|| rule.to_string()))
+        .collect()
+}
+
+/// 判断客户端 IP 是否在白名单中；白名单为空时不限制，取不到客户端 IP 时拒绝。
+pub fn is_ip_allowed(allowed_ips: Option<&str>, ip: Option<IpAddr>) -> bool {
+    let Ok(rules) = parse_ip_allowlist(allowed_ips.unwrap_or_default()) else {
+        return false;
+    };
+    if rules.is_empty() {
+        return true;
+    }
+    ip.is_some_and(|ip| rules.iter().any(|rule| rule.matches(ip)))
+}
@@ -1,13 +1,89 @@
 extern crate bcrypt;
 
-use bcrypt::{hash, verify, DEFAULT_COST};
+use crate::common::error::MyError;
+use crate::config::cfg::PasswordConfig;
+use crate::config::globals;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::distributions::Alphanumeric;
+use rand::{Rng, RngCore};
 
-/// 使用bcrypt算法哈希密码。
-pub fn hash_password(password: Option<String>) -> Result<String, bcrypt::BcryptError> {
-    hash(password.unwrap(), DEFAULT_COST)
+const ARGON2_SALT_LENGTH: usize = 16;
+const BCRYPT_PREFIXES: [&str; 3] = ["$2a$", "$2b$", "$2y$"];
+
+// 根据配置创建 Argon2id 哈希器
+fn argon2_hasher(config: &PasswordConfig) -> Result<Argon2<'static>, MyError> {
+    let params = Params::new(
+        config.argon2_memory_kib,
+        config.argon2_iterations,
+        config.argon2_parallelism,
+        None,
+    )
+    .map_err(|e| MyError::CryptoError(e.to_string()))?;
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+}
+
+fn is_bcrypt_hash(hashed_password: &str) -> bool {
+    BCRYPT_PREFIXES
+        .iter()
+        .any(|prefix| hashed_password.starts_with(prefix))
+}
+
+/// 使用 Argon2id 算法哈希密码。
+pub fn hash_password(password: Option<String>) -> Result<String, MyError> {
+    hash_password_with(
+        password.unwrap_or_default().as_str(),
+        &globals::APP_CONFIG.password,
+    )
+}
+
+/// 使用指定的配置哈希密码。
+pub fn hash_password_with(password: &str, config: &PasswordConfig) -> Result<String, MyError> {
+    let mut salt = [0u8; ARGON2_SALT_LENGTH];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let salt = SaltString::encode_b64(&salt).map_err(|e| MyError::CryptoError(e.to_string()))?;
+    argon2_hasher(config)?
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| MyError::CryptoError(e.to_string()))
+}
+
+/// 验证密码与哈希是否匹配，同时兼容 Argon2 和历史的 bcrypt 哈希。
+pub fn verify_password(password: &str, hashed_password: &str) -> Result<bool, MyError> {
+    if is_bcrypt_hash(hashed_password) {
+        return Ok(bcrypt::verify(password, hashed_password)?);
+    }
+    let parsed_hash =
+        PasswordHash::new(hashed_password).map_err(|e| MyError::CryptoError(e.to_string()))?;
+    // 参数从哈希中读取，修改配置前生成的哈希依然可以验证
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok())
+}
+
+/// 哈希是否需要按当前配置重新生成：bcrypt 哈希或参数与配置不一致的 Argon2 哈希。
+pub fn needs_rehash(hashed_password: &str, config: &PasswordConfig) -> bool {
+    let Ok(parsed_hash) = PasswordHash::new(hashed_password) else {
+        return true;
+    };
+    if parsed_hash.algorithm != Algorithm::Argon2id.ident() {
+        return true;
+    }
+    match Params::try_from(&parsed_hash) {
+        Ok(params) => {
+            params.m_cost() != config.argon2_memory_kib
+                || params.t_cost() != config.argon2_iterations
+                || params.p_cost() != config.argon2_parallelism
+        }
+        Err(_) => true,
+    }
 }
 
-/// 验证密码与bcrypt哈希是否匹配。
-pub fn verify_password(password: &str, hashed_password: &str) -> Result<bool, bcrypt::BcryptError> {
-    verify(password, hashed_password)
+/// 生成指定长度的随机令牌（字母和数字）。
+pub fn generate_random_token(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
 }
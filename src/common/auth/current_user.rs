@@ -0,0 +1,46 @@
+use crate::common::auth::jwt::Claims;
+use crate::common::resp::create_error_response;
+use actix_web::dev::Payload;
+use actix_web::http::StatusCode;
+use actix_web::{Error, FromRequest, HttpMessage, HttpRequest};
+use futures::future::{ready, Ready};
+use jsonwebtoken::TokenData;
+
+// 当前登录用户，取自 JWTAuth 放入请求扩展中的 TokenData<Claims>
+// 用于写入 create_user / update_user 等审计字段
+#[derive(Debug, Clone)]
+pub struct CurrentUser {
+    pub user_id: i32,
+    pub user_name: String,
+    pub role_codes: Vec<String>,
+}
+
+impl From<&Claims> for CurrentUser {
+    fn from(claims: &Claims) -> Self {
+        CurrentUser {
+            user_id: claims.user_id,
+            user_name: claims.user_name.clone(),
+            role_codes: claims.role_codes.clone(),
+        }
+    }
+}
+
+impl FromRequest for CurrentUser {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        // 未经过 JWTAuth 的路由取不到用户，按未认证处理
+        let current_user = req
+            .extensions()
+            .get::<TokenData<Claims>>()
+            .map(|token_data| CurrentUser::from(&token_data.claims))
+            .ok_or_else(|| {
+                create_error_response(
+                    "Authorization Failed: Token missing",
+                    StatusCode::UNAUTHORIZED,
+                )
+            });
+        ready(current_user)
+    }
+}
@@ -1,16 +1,22 @@
+use crate::common::auth::crypto::generate_random_token;
+use crate::common::auth::jwt_keys::JwtKeyStore;
 use crate::config;
-use jsonwebtoken::{
-    decode, encode, Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation,
-};
+use jsonwebtoken::TokenData;
+use once_cell::sync::Lazy;
 use sea_orm::FromQueryResult;
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
+    pub user_id: i32,            // 用户ID
     pub user_name: String,       // 通常用于存放唯一用户标识
     pub exp: usize,              // Token的过期时间
     pub role_codes: Vec<String>, // 用户权限
+    pub jti: String,             // Token的唯一标识，用于吊销
+    pub iat: usize,              // Token的签发时间
+    #[serde(default)]
+    pub iat_ms: u64, // Token的签发时间（毫秒），用于判断是否在吊销之前签发
 }
 
 // 假设的结构体表示菜单项
@@ -22,38 +28,60 @@ pub struct MenuInfo {
     pub route_name: String,
 }
 
+const JTI_LENGTH: usize = 32;
+
+// 当前时间戳（秒）
+pub fn now_timestamp() -> usize {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as usize
+}
+
+// 当前时间戳（毫秒）
+pub fn now_timestamp_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis() as u64
+}
+
 impl Claims {
     pub fn is_expired(&self) -> bool {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as usize;
-        current_time > self.exp
+        now_timestamp() > self.exp
     }
     pub fn new() -> Self {
         Claims {
+            user_id: 0,
             user_name: "".to_string(),
             exp: 0,
             role_codes: vec![],
+            jti: "".to_string(),
+            iat: 0,
+            iat_ms: 0,
         }
     }
 }
 
+// 启动时加载的签名 key，配置有误时直接失败
+pub static JWT_KEYS: Lazy<JwtKeyStore> = Lazy::new(|| {
+    JwtKeyStore::from_config(&config::globals::APP_CONFIG.jwt).expect("Failed to load jwt keys")
+});
+
 pub fn decode_jwt(token: &str) -> Result<TokenData<Claims>, jsonwebtoken::errors::Error> {
-    decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(config::globals::APP_CONFIG.jwt.secret.as_ref()),
-        &Validation::new(Algorithm::HS256),
-    )
+    let jwt_config = &config::globals::APP_CONFIG.jwt;
+    JWT_KEYS.decode::<Claims>(token, &jwt_config.issuer, &jwt_config.audience)
 }
 
 pub fn generate_jwt(
+    user_id: i32,
     user_name: String,
     roles: Vec<String>,
 ) -> Result<String, jsonwebtoken::errors::Error> {
-    let expiration = SystemTime::now()
+    let issued_at = SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
+        .expect("Time went backwards");
+    let expiration = issued_at
         .checked_add(Duration::from_secs(
             config::globals::APP_CONFIG.jwt.expire_time,
         )) // Token有效期为1小时
@@ -61,14 +89,15 @@ pub fn generate_jwt(
         .as_secs() as usize;
 
     let claims = Claims {
+        user_id,
         user_name,
         exp: expiration,
         role_codes: roles,
+        jti: generate_random_token(JTI_LENGTH),
+        iat: issued_at.as_secs() as usize,
+        iat_ms: issued_at.as_millis() as u64,
     };
 
-    encode(
-        &Header::new(Algorithm::HS256),
-        &claims,
-        &EncodingKey::from_secret(config::globals::APP_CONFIG.jwt.secret.as_ref()),
-    )
+    let jwt_config = &config::globals::APP_CONFIG.jwt;
+    JWT_KEYS.encode(&claims, &jwt_config.issuer, &jwt_config.audience)
 }
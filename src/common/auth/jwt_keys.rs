@@ -0,0 +1,340 @@
+use crate::common::error::MyError;
+use crate::config::cfg::{JwtConfig, JwtKeyConfig};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters,
+    EllipticCurveKeyType, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType,
+    PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, TokenData,
+    Validation,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use simple_asn1::{oid, ASN1Block};
+use std::fs;
+use std::str::FromStr;
+
+// 签名或验签使用的一个 key
+pub struct JwtKey {
+    pub kid: Option<String>,
+    pub algorithm: Algorithm,
+    encoding_key: Option<EncodingKey>,
+    decoding_key: DecodingKey,
+    jwk: Option<Jwk>,
+}
+
+// 所有可用的 key，签名使用 signing_kid 对应的 key，验签按 token 头部的 kid 选择
+pub struct JwtKeyStore {
+    signing_index: usize,
+    keys: Vec<JwtKey>,
+}
+
+// 写入 token 的声明，在业务声明之外带上 iss 和 aud
+#[derive(Serialize)]
+struct SignedClaims<'a, T: Serialize> {
+    #[serde(flatten)]
+    claims: &'a T,
+    iss: &'a str,
+    aud: &'a str,
+}
+
+fn key_error(kid: &str, msg: impl std::fmt::Display) -> MyError {
+    MyError::CryptoError(format!("jwt key {}: {}", kid, msg))
+}
+
+fn is_hmac(algorithm: Algorithm) -> bool {
+    matches!(
+        algorithm,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    )
+}
+
+fn read_pem(kid: &str, path: &Option<String>, name: &str) -> Result<Option<Vec<u8>>, MyError> {
+    path.as_ref()
+        .filter(|path| !path.is_empty())
+        .map(|path| fs::read(path).map_err(|e| key_error(kid, format!("{} {}: {}", name, path, e))))
+        .transpose()
+}
+
+// 从 SubjectPublicKeyInfo 中取出算法 OID 和公钥内容
+fn parse_spki(der: &[u8]) -> Option<(simple_asn1::OID, Vec<u8>)> {
+    let blocks = simple_asn1::from_der(der).ok()?;
+    let ASN1Block::Sequence(_, spki) = blocks.first()? else {
+        return None;
+    };
+    let (ASN1Block::Sequence(_, algorithm), ASN1Block::BitString(_, _, public_key)) =
+        (spki.first()?, spki.get(1)?)
+    else {
+        return None;
+    };
+    let ASN1Block::ObjectIdentifier(_, oid) = algorithm.first()? else {
+        return None;
+    };
+    Some((oid.clone(), public_key.clone()))
+}
+
+// 从 PKCS#1 RSAPublicKey 中取出 n 和 e
+fn parse_rsa_public_key(der: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let blocks = simple_asn1::from_der(der).ok()?;
+    let ASN1Block::Sequence(_, numbers) = blocks.first()? else {
+        return None;
+    };
+    let (ASN1Block::Integer(_, n), ASN1Block::Integer(_, e)) = (numbers.first()?, numbers.get(1)?)
+    else {
+        return None;
+    };
+    Some((n.to_bytes_be().1, e.to_bytes_be().1))
+}
+
+// 根据 PEM 公钥生成 JWK，同时校验公钥类型与算法匹配
+fn build_jwk(kid: &str, algorithm: Algorithm, public_pem: &[u8]) -> Result<Jwk, MyError> {
+    let pem = pem::parse(public_pem).map_err(|e| key_error(kid, e))?;
+    let invalid = || key_error(kid, format!("public key does not match {:?}", algorithm));
+    let b64 = |bytes: &[u8]| URL_SAFE_NO_PAD.encode(bytes);
+
+    let algorithm_parameters = if pem.tag() == "RSA PUBLIC KEY" {
+        let (n, e) = parse_rsa_public_key(pem.contents()).ok_or_else(invalid)?;
+        rsa_parameters(algorithm, &n, &e).ok_or_else(invalid)?
+    } else {
+        let (oid, public_key) = parse_spki(pem.contents()).ok_or_else(invalid)?;
+        if oid == oid!(1, 2, 840, 113549, 1, 1, 1) {
+            let (n, e) = parse_rsa_public_key(&public_key).ok_or_else(invalid)?;
+            rsa_parameters(algorithm, &n, &e).ok_or_else(invalid)?
+        } else if oid == oid!(1, 2, 840, 10045, 2, 1) {
+            // 未压缩的椭圆曲线点：0x04 || x || y
+            let (curve, size) = match algorithm {
+                Algorithm::ES256 => (EllipticCurve::P256, 32),
+                Algorithm::ES384 => (EllipticCurve::P384, 48),
+                _ => return Err(invalid()),
+            };
+            if public_key.len() != 1 + 2 * size || public_key[0] != 0x04 {
+                return Err(invalid());
+            }
+            AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                key_type: EllipticCurveKeyType::EC,
+                curve,
+                x: b64(&public_key[1..1 + size]),
+                y: b64(&public_key[1 + size..]),
+            })
+        } else if oid == oid!(1, 3, 101, 112) && algorithm == Algorithm::EdDSA {
+            AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: b64(&public_key),
+            })
+        } else {
+            return Err(invalid());
+        }
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: KeyAlgorithm::from_str(&format!("{:?}", algorithm)).ok(),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: algorithm_parameters,
+    })
+}
+
+fn rsa_parameters(algorithm: Algorithm, n: &[u8], e: &[u8]) -> Option<AlgorithmParameters> {
+    let is_rsa = matches!(
+        algorithm,
+        Algorithm::RS256
+            | Algorithm::RS384
+            | Algorithm::RS512
+            | Algorithm::PS256
+            | Algorithm::PS384
+            | Algorithm::PS512
+    );
+    is_rsa.then(|| {
+        AlgorithmParameters::RSA(RSAKeyParameters {
+            key_type: RSAKeyType::RSA,
+            n: URL_SAFE_NO_PAD.encode(n),
+            e: URL_SAFE_NO_PAD.encode(e),
+        })
+    })
+}
+
+impl JwtKey {
+    // 使用 secret 做 HS256 签名的 key，不带 kid，兼容未配置 keys 的部署
+    fn legacy(secret: &str) -> Self {
+        JwtKey {
+            kid: None,
+            algorithm: Algorithm::HS256,
+            encoding_key: Some(EncodingKey::from_secret(secret.as_bytes())),
+            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            jwk: None,
+        }
+    }
+
+    fn from_config(config: &JwtKeyConfig, default_secret: &str) -> Result<Self, MyError> {
+        let kid = config.kid.as_str();
+        let algorithm = Algorithm::from_str(&config.algorithm)
+            .map_err(|_| key_error(kid, format!("unsupported algorithm {}", config.algorithm)))?;
+
+        if is_hmac(algorithm) {
+            let secret = config
+                .secret
+                .as_deref()
+                .filter(|secret| !secret.is_empty())
+                .unwrap_or(default_secret);
+            return Ok(JwtKey {
+                kid: Some(kid.to_string()),
+                algorithm,
+                encoding_key: Some(EncodingKey::from_secret(secret.as_bytes())),
+                decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+                jwk: None,
+            });
+        }
+
+        let public_pem = read_pem(kid, &config.public_key_path, "public key")?
+            .ok_or_else(|| key_error(kid, "public_key_path is required"))?;
+        let private_pem = read_pem(kid, &config.private_key_path, "private key")?;
+        let (decoding_key, encoding_key) = match algorithm {
+            Algorithm::ES256 | Algorithm::ES384 => (
+                DecodingKey::from_ec_pem(&public_pem),
+                private_pem
+                    .map(|pem| EncodingKey::from_ec_pem(&pem))
+                    .transpose(),
+            ),
+            Algorithm::EdDSA => (
+                DecodingKey::from_ed_pem(&public_pem),
+                private_pem
+                    .map(|pem| EncodingKey::from_ed_pem(&pem))
+                    .transpose(),
+            ),
+            _ => (
+                DecodingKey::from_rsa_pem(&public_pem),
+                private_pem
+                    .map(|pem| EncodingKey::from_rsa_pem(&pem))
+                    .transpose(),
+            ),
+        };
+        Ok(JwtKey {
+            kid: Some(kid.to_string()),
+            algorithm,
+            encoding_key: encoding_key.map_err(|e| key_error(kid, e))?,
+            decoding_key: decoding_key.map_err(|e| key_error(kid, e))?,
+            jwk: Some(build_jwk(kid, algorithm, &public_pem)?),
+        })
+    }
+}
+
+impl JwtKeyStore {
+    /// 根据配置加载所有 key，未配置 keys 时使用 secret 做 HS256 签名。
+    pub fn from_config(config: &JwtConfig) -> Result<Self, MyError> {
+        if config.keys.is_empty() {
+            return Ok(JwtKeyStore {
+                signing_index: 0,
+                keys: vec![JwtKey::legacy(&config.secret)],
+            });
+        }
+
+        let mut keys: Vec<JwtKey> = Vec::with_capacity(config.keys.len());
+        for key_config in &config.keys {
+            if keys
+                .iter()
+                .any(|key| key.kid.as_deref() == Some(key_config.kid.as_str()))
+            {
+                return Err(key_error(&key_config.kid, "duplicate kid"));
+            }
+            keys.push(JwtKey::from_config(key_config, &config.secret)?);
+        }
+
+        let signing_kid = config
+            .signing_kid
+            .as_deref()
+            .ok_or_else(|| MyError::CryptoError("jwt.signing_kid is required".to_string()))?;
+        let signing_index = keys
+            .iter()
+            .position(|key| key.kid.as_deref() == Some(signing_kid))
+            .ok_or_else(|| key_error(signing_kid, "signing key not found"))?;
+        if keys[signing_index].encoding_key.is_none() {
+            return Err(key_error(
+                signing_kid,
+                "private_key_path is required for signing",
+            ));
+        }
+        let store = JwtKeyStore {
+            signing_index,
+            keys,
+        };
+        // 私钥和公钥不是一对时，签发的 token 在其他服务上无法验签
+        let probe = store
+            .encode(&serde_json::json!({ "exp": u32::MAX }), "probe", "probe")
+            .map_err(|e| key_error(signing_kid, e))?;
+        store
+            .decode::<serde_json::Value>(&probe, "probe", "probe")
+            .map_err(|_| key_error(signing_kid, "private key does not match public key"))?;
+        Ok(store)
+    }
+
+    /// 当前用于签名的 key。
+    pub fn signing_key(&self) -> &JwtKey {
+        &self.keys[self.signing_index]
+    }
+
+    /// 按 kid 查找验签的 key，不带 kid 的 token 只能匹配未配置 keys 时的 HS256 key。
+    pub fn find(&self, kid: Option<&str>) -> Option<&JwtKey> {
+        self.keys.iter().find(|key| key.kid.as_deref() == kid)
+    }
+
+    /// 所有非对称 key 的公钥，HS 密钥不会公开。
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self.keys.iter().filter_map(|key| key.jwk.clone()).collect(),
+        }
+    }
+
+    /// 使用当前签名 key 签发 token，头部带上 kid。
+    pub fn encode<T: Serialize>(
+        &self,
+        claims: &T,
+        issuer: &str,
+        audience: &str,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let key = self.signing_key();
+        let encoding_key = key
+            .encoding_key
+            .as_ref()
+            .ok_or(jsonwebtoken::errors::Error::from(
+                ErrorKind::InvalidKeyFormat,
+            ))?;
+        let mut header = Header::new(key.algorithm);
+        header.kid = key.kid.clone();
+        encode(
+            &header,
+            &SignedClaims {
+                claims,
+                iss: issuer,
+                aud: audience,
+            },
+            encoding_key,
+        )
+    }
+
+    /// 按 kid 选择 key 验签，并校验算法、过期时间、iss 和 aud。
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        issuer: &str,
+        audience: &str,
+    ) -> Result<TokenData<T>, jsonwebtoken::errors::Error> {
+        let header = decode_header(token)?;
+        let key = self
+            .find(header.kid.as_deref())
+            .ok_or(jsonwebtoken::errors::Error::from(ErrorKind::InvalidToken))?;
+        // 只接受该 key 配置的算法，避免算法混淆
+        let mut validation = Validation::new(key.algorithm);
+        validation.set_issuer(&[issuer]);
+        validation.set_audience(&[audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+        decode::<T>(token, &key.decoding_key, &validation)
+    }
+}
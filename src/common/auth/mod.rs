@@ -1,2 +1,9 @@
+pub mod api_key;
 pub mod crypto;
+pub mod current_user;
 pub mod jwt;
+pub mod jwt_keys;
+pub mod oidc;
+pub mod password_policy;
+pub mod role_hierarchy;
+pub mod totp;
@@ -0,0 +1,435 @@
+use crate::common::auth::crypto::generate_random_token;
+use crate::common::error::MyError;
+use crate::config::cfg::{OidcClientAuthMethod, OidcProviderConfig, OidcRoleMapping};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use reqwest::header::{HeaderValue, ACCEPT, AUTHORIZATION};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use url::Url;
+
+const PKCE_VERIFIER_LENGTH: usize = 64;
+// 响应体最大字节数，避免对端返回超大内容
+const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+const DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+
+// 身份提供方的端点信息，来自 discovery 文档或配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+// discovery 文档，端点可能缺失，由配置补全
+#[derive(Debug, Deserialize)]
+struct DiscoveryDocument {
+    issuer: String,
+    authorization_endpoint: Option<String>,
+    token_endpoint: Option<String>,
+    jwks_uri: Option<String>,
+}
+
+// token 端点的响应
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcTokenResponse {
+    pub id_token: Option<String>,
+    pub access_token: Option<String>,
+    pub token_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawJwkSet {
+    keys: Vec<Value>,
+}
+
+// PKCE 参数，verifier 保存在服务端，challenge 随授权请求发出
+#[derive(Debug, Clone)]
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+fn to_oidc_error<E: std::fmt::Display>(err: E) -> MyError {
+    MyError::AuthError(format!("OIDC error: {}", err))
+}
+
+fn to_http_error<E: std::fmt::Display>(err: E) -> MyError {
+    MyError::HttpClientError(err.to_string())
+}
+
+// 按 JSON 解析响应体，非 2xx 响应和超过大小上限的响应体返回错误
+async fn read_json<T: DeserializeOwned>(mut response: reqwest::Response) -> Result<T, MyError> {
+    let status = response.status();
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(to_http_error)? {
+        if body.len() + chunk.len() > MAX_RESPONSE_SIZE {
+            return Err(MyError::HttpClientError(
+                "Response is too large".to_string(),
+            ));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    if !status.is_success() {
+        return Err(MyError::HttpClientError(format!(
+            "Unexpected status {}: {}",
+            status.as_u16(),
+            String::from_utf8_lossy(&body)
+                .chars()
+                .take(200)
+                .collect::<String>()
+        )));
+    }
+    serde_json::from_slice(&body).map_err(to_http_error)
+}
+
+/// 生成 PKCE verifier 和 S256 challenge。
+pub fn generate_pkce() -> Pkce {
+    let verifier = generate_random_token(PKCE_VERIFIER_LENGTH);
+    let challenge = pkce_challenge(&verifier);
+    Pkce {
+        verifier,
+        challenge,
+    }
+}
+
+/// 计算 S256 challenge：BASE64URL(SHA256(verifier))。
+pub fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// 拼接授权地址，携带 state、nonce 和 PKCE challenge。
+pub fn build_authorization_url(
+    provider: &OidcProviderConfig,
+    metadata: &OidcProviderMetadata,
+    state: &str,
+    nonce: &str,
+    code_challenge: &str,
+) -> Result<String, MyError> {
+    let mut url = Url::parse(&metadata.authorization_endpoint).map_err(to_oidc_error)?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &provider.client_id)
+        .append_pair("redirect_uri", &provider.redirect_uri)
+        .append_pair("scope", &provider.scopes.join(" "))
+        .append_pair("state", state)
+        .append_pair("nonce", nonce)
+        .append_pair("code_challenge", code_challenge)
+        .append_pair("code_challenge_method", "S256");
+    Ok(url.to_string())
+}
+
+/// 按 . 分隔的路径取出 claim，例如 `realm_access.roles`。
+pub fn claim_value<'a>(claims: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(claims, |value, segment| value.get(segment))
+}
+
+/// 取出字符串类型的 claim。
+pub fn claim_str<'a>(claims: &'a Value, path: &str) -> Option<&'a str> {
+    claim_value(claims, path).and_then(Value::as_str)
+}
+
+/// 按映射规则计算 claims 匹配到的角色编码。
+///
+/// claim 为字符串时需要与规则的值相等，为数组时需要包含该值。
+pub fn map_roles(claims: &Value, rules: &[OidcRoleMapping]) -> Vec<String> {
+    let mut role_codes: Vec<String> = Vec::new();
+    for rule in rules {
+        let matched = match claim_value(claims, &rule.claim) {
+            Some(Value::String(value)) => value == &rule.value,
+            Some(Value::Array(values)) => values
+                .iter()
+                .any(|value| value.as_str() == Some(rule.value.as_str())),
+            _ => false,
+        };
+        if matched && !role_codes.contains(&rule.role_code) {
+            role_codes.push(rule.role_code.clone());
+        }
+    }
+    role_codes
+}
+
+// 只接受非对称算法，避免用公开的 JWK 做 HMAC 密钥
+fn is_asymmetric(algorithm: Algorithm) -> bool {
+    !matches!(
+        algorithm,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    )
+}
+
+/// 校验 id_token 的签名、iss、aud、azp、exp 和 nonce，返回全部 claims。
+pub fn validate_id_token(
+    id_token: &str,
+    jwks: &JwkSet,
+    issuer: &str,
+    client_id: &str,
+    nonce: &str,
+) -> Result<Value, MyError> {
+    let header = jsonwebtoken::decode_header(id_token).map_err(to_oidc_error)?;
+    if !is_asymmetric(header.alg) {
+        return Err(MyError::AuthError(format!(
+            "Unsupported id_token algorithm {:?}",
+            header.alg
+        )));
+    }
+    let jwk = find_jwk(jwks, header.kid.as_deref())
+        .ok_or_else(|| MyError::AuthError("Unknown id_token signing key".to_string()))?;
+    let decoding_key = DecodingKey::from_jwk(jwk).map_err(to_oidc_error)?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[issuer]);
+    validation.set_audience(&[client_id]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+    let claims = jsonwebtoken::decode::<Value>(id_token, &decoding_key, &validation)
+        .map_err(to_oidc_error)?
+        .claims;
+
+    // 多个受众时 azp 必须是当前客户端
+    let multiple_audiences = claims
+        .get("aud")
+        .and_then(Value::as_array)
+        .is_some_and(|aud| aud.len() > 1);
+    match claim_str(&claims, "azp") {
+        Some(azp) if azp != client_id => {
+            return Err(MyError::AuthError("Invalid id_token azp".to_string()))
+        }
+        None if multiple_audiences => {
+            return Err(MyError::AuthError("Missing id_token azp".to_string()))
+        }
+        _ => {}
+    }
+    if claim_str(&claims, "nonce") != Some(nonce) {
+        return Err(MyError::AuthError("Invalid id_token nonce".to_string()));
+    }
+    if claim_str(&claims, "sub").is_none_or(str::is_empty) {
+        return Err(MyError::AuthError("Missing id_token sub".to_string()));
+    }
+    Ok(claims)
+}
+
+// 按 kid 查找 JWK；token 未带 kid 时只在 JWKS 只有一个 key 时使用它
+fn find_jwk<'a>(jwks: &'a JwkSet, kid: Option<&str>) -> Option<&'a Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+}
+
+// 带过期时间的缓存项
+struct CacheEntry<T> {
+    fetched_at: Instant,
+    value: Arc<T>,
+}
+
+/// OIDC 客户端：获取 discovery 文档和 JWKS 并缓存，完成授权码换取和 id_token 校验。
+pub struct OidcClient {
+    http: reqwest::Client,
+    cache_ttl: Duration,
+    metadata_cache: Mutex<HashMap<String, CacheEntry<OidcProviderMetadata>>>,
+    jwks_cache: Mutex<HashMap<String, CacheEntry<JwkSet>>>,
+}
+
+impl OidcClient {
+    pub fn new(http_timeout: Duration, cache_ttl: Duration) -> Self {
+        OidcClient {
+            http: reqwest::Client::builder()
+                .timeout(http_timeout)
+                .build()
+                .expect("Failed to build OIDC http client"),
+            cache_ttl,
+            metadata_cache: Mutex::new(HashMap::new()),
+            jwks_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    //get_json 发送 GET 请求并解析 JSON 响应
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, MyError> {
+        let response = self
+            .http
+            .get(url)
+            .header(ACCEPT, HeaderValue::from_static("application/json"))
+            .send()
+            .await
+            .map_err(to_http_error)?;
+        read_json(response).await
+    }
+
+    fn cached<T>(
+        &self,
+        cache: &Mutex<HashMap<String, CacheEntry<T>>>,
+        key: &str,
+    ) -> Option<Arc<T>> {
+        let cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .get(key)
+            .filter(|entry| entry.fetched_at.elapsed() < self.cache_ttl)
+            .map(|entry| entry.value.clone())
+    }
+
+    fn store<T>(cache: &Mutex<HashMap<String, CacheEntry<T>>>, key: &str, value: T) -> Arc<T> {
+        let value = Arc::new(value);
+        cache.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            key.to_string(),
+            CacheEntry {
+                fetched_at: Instant::now(),
+                value: value.clone(),
+            },
+        );
+        value
+    }
+
+    //metadata 获取身份提供方的端点，配置的端点优先于 discovery 文档
+    pub async fn metadata(
+        &self,
+        provider: &OidcProviderConfig,
+    ) -> Result<Arc<OidcProviderMetadata>, MyError> {
+        if let Some(metadata) = self.cached(&self.metadata_cache, &provider.name) {
+            return Ok(metadata);
+        }
+
+        let document = if provider.discovery {
+            let url = format!(
+                "{}{}",
+                provider.issuer.trim_end_matches('/'),
+                DISCOVERY_PATH
+            );
+            let document: DiscoveryDocument = self.get_json(&url).await?;
+            // issuer 必须与配置一致，防止被其它提供方的文档冒充
+            if document.issuer != provider.issuer {
+                return Err(MyError::AuthError(format!(
+                    "Discovery issuer mismatch: {}",
+                    document.issuer
+                )));
+            }
+            Some(document)
+        } else {
+            None
+        };
+        let endpoint = |configured: &Option<String>, discovered: Option<&Option<String>>, name| {
+            configured
+                .clone()
+                .or_else(|| discovered.cloned().flatten())
+                .ok_or_else(|| {
+                    MyError::AuthError(format!(
+                        "Missing {} for OIDC provider {}",
+                        name, provider.name
+                    ))
+                })
+        };
+        let metadata = OidcProviderMetadata {
+            issuer: provider.issuer.clone(),
+            authorization_endpoint: endpoint(
+                &provider.authorization_endpoint,
+                document.as_ref().map(|doc| &doc.authorization_endpoint),
+                "authorization_endpoint",
+            )?,
+            token_endpoint: endpoint(
+                &provider.token_endpoint,
+                document.as_ref().map(|doc| &doc.token_endpoint),
+                "token_endpoint",
+            )?,
+            jwks_uri: endpoint(
+                &provider.jwks_uri,
+                document.as_ref().map(|doc| &doc.jwks_uri),
+                "jwks_uri",
+            )?,
+        };
+        Ok(Self::store(&self.metadata_cache, &provider.name, metadata))
+    }
+
+    //jwks 获取身份提供方的 JWKS，force 为 true 时跳过缓存重新获取
+    async fn jwks(&self, jwks_uri: &str, force: bool) -> Result<Arc<JwkSet>, MyError> {
+        if !force {
+            if let Some(jwks) = self.cached(&self.jwks_cache, jwks_uri) {
+                return Ok(jwks);
+            }
+        }
+        let raw: RawJwkSet = self.get_json(jwks_uri).await?;
+        // 跳过无法解析的 key（例如加密用途的 key），不影响其它 key
+        let keys = raw
+            .keys
+            .into_iter()
+            .filter_map(|key| serde_json::from_value::<Jwk>(key).ok())
+            .collect();
+        Ok(Self::store(&self.jwks_cache, jwks_uri, JwkSet { keys }))
+    }
+
+    //exchange_code 使用授权码和 PKCE verifier 换取 token
+    pub async fn exchange_code(
+        &self,
+        provider: &OidcProviderConfig,
+        metadata: &OidcProviderMetadata,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<OidcTokenResponse, MyError> {
+        let mut request = self
+            .http
+            .post(&metadata.token_endpoint)
+            .header(ACCEPT, HeaderValue::from_static("application/json"));
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", provider.redirect_uri.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        let secret = provider.client_secret.as_deref().filter(|s| !s.is_empty());
+        match (provider.client_auth_method, secret) {
+            (OidcClientAuthMethod::ClientSecretBasic, Some(secret)) => {
+                let credentials = format!(
+                    "{}:{}",
+                    urlencoding::encode(&provider.client_id),
+                    urlencoding::encode(secret)
+                );
+                request = request.header(
+                    AUTHORIZATION,
+                    format!("Basic {}", STANDARD.encode(credentials)),
+                );
+            }
+            (OidcClientAuthMethod::ClientSecretPost, Some(secret)) => {
+                form.push(("client_id", provider.client_id.as_str()));
+                form.push(("client_secret", secret));
+            }
+            // 公共客户端只依靠 PKCE
+            (_, None) => form.push(("client_id", provider.client_id.as_str())),
+        }
+        let result = match request.form(&form).send().await {
+            Ok(response) => read_json(response).await,
+            Err(err) => Err(to_http_error(err)),
+        };
+        result.map_err(|e| MyError::AuthError(format!("OIDC token exchange failed: {}", e)))
+    }
+
+    //verify_id_token 校验 id_token，签名 key 未知时重新获取一次 JWKS 以支持提供方轮换 key
+    pub async fn verify_id_token(
+        &self,
+        provider: &OidcProviderConfig,
+        metadata: &OidcProviderMetadata,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<Value, MyError> {
+        let kid = jsonwebtoken::decode_header(id_token)
+            .map_err(to_oidc_error)?
+            .kid;
+        let mut jwks = self.jwks(&metadata.jwks_uri, false).await?;
+        if find_jwk(&jwks, kid.as_deref()).is_none() {
+            jwks = self.jwks(&metadata.jwks_uri, true).await?;
+        }
+        validate_id_token(
+            id_token,
+            &jwks,
+            &metadata.issuer,
+            &provider.client_id,
+            nonce,
+        )
+    }
+}
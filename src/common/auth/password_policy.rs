@@ -0,0 +1,55 @@
+use crate::common::error::MyError;
+use crate::config::cfg::PasswordConfig;
+
+fn violation(msg: &str) -> Result<(), MyError> {
+    Err(MyError::ValidationError(msg.to_string()))
+}
+
+/// 检查密码是否符合密码策略，返回第一条不满足的规则。
+pub fn check_password(
+    password: &str,
+    user_name: &str,
+    config: &PasswordConfig,
+) -> Result<(), MyError> {
+    let length = password.chars().count();
+    if length < config.min_length {
+        return Err(MyError::ValidationError(format!(
+            "Password must be at least {} characters",
+            config.min_length
+        )));
+    }
+    if length > config.max_length {
+        return Err(MyError::ValidationError(format!(
+            "Password must be at most {} characters",
+            config.max_length
+        )));
+    }
+    if config.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+        return violation("Password must contain an uppercase letter");
+    }
+    if config.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+        return violation("Password must contain a lowercase letter");
+    }
+    if config.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        return violation("Password must contain a digit");
+    }
+    if config.require_special && password.chars().all(|c| c.is_alphanumeric()) {
+        return violation("Password must contain a special character");
+    }
+
+    let lowercase_password = password.to_lowercase();
+    if config
+        .banned_passwords
+        .iter()
+        .any(|banned| banned.to_lowercase() == lowercase_password)
+    {
+        return violation("Password is too common");
+    }
+    if config.disallow_user_name
+        && !user_name.is_empty()
+        && lowercase_password.contains(&user_name.to_lowercase())
+    {
+        return violation("Password must not contain the user name");
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet};
+
+/// 角色继承关系中的一个节点。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoleNode {
+    pub id: i32,
+    pub parent_role_id: Option<i32>,
+    pub enabled: bool,
+}
+
+/// 角色继承关系：角色拥有父角色及其祖先的全部权限。
+///
+/// 禁用的角色不授予任何权限，也不再向下传递其继承的权限。
+#[derive(Debug, Clone, Default)]
+pub struct RoleHierarchy {
+    nodes: HashMap<i32, RoleNode>,
+}
+
+impl RoleHierarchy {
+    pub fn new(nodes: impl IntoIterator<Item = RoleNode>) -> Self {
+        RoleHierarchy {
+            nodes: nodes.into_iter().map(|node| (node.id, node)).collect(),
+        }
+    }
+
+    pub fn get(&self, role_id: i32) -> Option<&RoleNode> {
+        self.nodes.get(&role_id)
+    }
+
+    /// 角色自身及沿父角色向上的启用角色，遇到禁用或不存在的角色时停止。
+    pub fn inheritance_chain(&self, role_id: i32) -> Vec<i32> {
+        let mut chain = Vec::new();
+        let mut current = Some(role_id);
+        while let Some(id) = current {
+            // 数据中已存在的环只走一圈
+            if chain.contains(&id) {
+                break;
+            }
+            match self.nodes.get(&id) {
+                Some(node) if node.enabled => {
+                    chain.push(id);
+                    current = node.parent_role_id;
+                }
+                _ => break,
+            }
+        }
+        chain
+    }
+
+    /// 角色自身及沿父角色向上的全部角色，包含禁用的角色，用于解释授权链路。
+    pub fn lineage(&self, role_id: i32) -> Vec<i32> {
+        let mut lineage = Vec::new();
+        let mut current = Some(role_id);
+        while let Some(id) = current {
+            if lineage.contains(&id) {
+                break;
+            }
+            match self.nodes.get(&id) {
+                Some(node) => {
+                    lineage.push(id);
+                    current = node.parent_role_id;
+                }
+                None => break,
+            }
+        }
+        lineage
+    }
+
+    /// 多个角色的有效角色ID（去重，保持首次出现的顺序）。
+    pub fn effective_role_ids(&self, role_ids: &[i32]) -> Vec<i32> {
+        let mut seen = HashSet::new();
+        role_ids
+            .iter()
+            .flat_map(|&role_id| self.inheritance_chain(role_id))
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
+
+    /// 将 role_id 的父角色设为 parent_role_id 是否会形成环。
+    pub fn would_create_cycle(&self, role_id: i32, parent_role_id: i32) -> bool {
+        let mut visited = HashSet::new();
+        let mut current = Some(parent_role_id);
+        while let Some(id) = current {
+            if id == role_id {
+                return true;
+            }
+            if !visited.insert(id) {
+                // 已有的环不经过 role_id，不会因本次修改产生新环
+                return false;
+            }
+            current = self.nodes.get(&id).and_then(|node| node.parent_role_id);
+        }
+        false
+    }
+
+    /// 直接继承该角色的子角色ID，按ID升序。
+    pub fn children(&self, role_id: i32) -> Vec<i32> {
+        let mut children: Vec<i32> = self
+            .nodes
+            .values()
+            .filter(|node| node.parent_role_id == Some(role_id) && node.id != role_id)
+            .map(|node| node.id)
+            .collect();
+        children.sort_unstable();
+        children
+    }
+
+    /// 角色树的根：没有父角色或父角色不存在的角色，以及只能通过环到达的角色。
+    pub fn roots(&self) -> Vec<i32> {
+        let mut roots: Vec<i32> = self
+            .nodes
+            .values()
+            .filter(|node| {
+                node.parent_role_id
+                    .is_none_or(|parent| !self.nodes.contains_key(&parent))
+            })
+            .map(|node| node.id)
+            .collect();
+        roots.sort_unstable();
+
+        let mut reachable = HashSet::new();
+        let mut stack = roots.clone();
+        while let Some(id) = stack.pop() {
+            if reachable.insert(id) {
+                stack.extend(self.children(id));
+            }
+        }
+        let mut unreachable: Vec<i32> = self
+            .nodes
+            .keys()
+            .filter(|id| !reachable.contains(id))
+            .copied()
+            .collect();
+        unreachable.sort_unstable();
+        // 环中每次取最小的一个作为根，其余角色作为它的后代展示
+        while let Some(&id) = unreachable.first() {
+            roots.push(id);
+            let mut stack = vec![id];
+            while let Some(id) = stack.pop() {
+                if reachable.insert(id) {
+                    stack.extend(self.children(id));
+                }
+            }
+            unreachable.retain(|id| !reachable.contains(id));
+        }
+        roots
+    }
+}
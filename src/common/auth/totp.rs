@@ -0,0 +1,78 @@
+use rand::RngCore;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const SECRET_LENGTH: usize = 20;
+
+/// 对字节做 base32 编码（RFC 4648，不补 =）。
+pub fn base32_encode(data: &[u8]) -> String {
+    Secret::Raw(data.to_vec()).to_encoded().to_string()
+}
+
+/// base32 解码，忽略大小写、空格和末尾的 =，包含非法字符时返回 None。
+pub fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let normalized: String = encoded
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '=')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    Secret::Encoded(normalized).to_bytes().ok()
+}
+
+/// 生成随机的 TOTP 密钥，返回 base32 编码。
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LENGTH];
+    rand::thread_rng().fill_bytes(&mut secret);
+    base32_encode(&secret)
+}
+
+/// 生成 otpauth:// 地址，供认证器 App 扫码添加。
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str, digits: u32, period: u64) -> String {
+    let issuer = urlencoding::encode(issuer);
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        issuer,
+        urlencoding::encode(account),
+        secret,
+        issuer,
+        digits,
+        period
+    )
+}
+
+/// 按 RFC 4226 计算指定计数器的验证码，digits 不在 6 到 8 之间或密钥短于 128 位时返回 None。
+pub fn hotp(secret: &[u8], counter: u64, digits: u32) -> Option<String> {
+    // 时间步长为 1 时 TOTP 的时间即为 HOTP 的计数器
+    TOTP::new(Algorithm::SHA1, digits as usize, 0, 1, secret.to_vec())
+        .ok()
+        .map(|totp| totp.generate(counter))
+}
+
+/// 按 RFC 6238 校验验证码，允许前后 skew 个时间步的误差。
+/// 校验通过时返回匹配的时间步，调用方据此拒绝重复使用同一个验证码。
+pub fn verify_code(
+    secret: &str,
+    code: &str,
+    timestamp: u64,
+    period: u64,
+    digits: u32,
+    skew: u64,
+) -> Option<u64> {
+    let totp = TOTP::new(
+        Algorithm::SHA1,
+        digits as usize,
+        0,
+        period,
+        base32_decode(secret)?,
+    )
+    .ok()?;
+    if code.len() != digits as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let current_step = timestamp / period;
+    (current_step.saturating_sub(skew)..=current_step + skew)
+        .find(|&step| constant_time_eq(totp.generate(step * period).as_bytes(), code.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
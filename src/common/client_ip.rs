@@ -0,0 +1,17 @@
+use actix_web::dev::ConnectionInfo;
+use std::net::{IpAddr, SocketAddr};
+
+//client_ip 请求的客户端IP，只有信任代理时才读取 X-Forwarded-For、Forwarded 等请求头，否则使用连接的对端地址
+pub fn client_ip(
+    peer_addr: Option<SocketAddr>,
+    connection_info: &ConnectionInfo,
+    trust_proxy_headers: bool,
+) -> Option<IpAddr> {
+    if !trust_proxy_headers {
+        return peer_addr.map(|addr| addr.ip());
+    }
+    let addr = connection_info.realip_remote_addr()?;
+    addr.parse::<IpAddr>()
+        .or_else(|_| addr.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .ok()
+}
@@ -10,7 +10,12 @@ pub enum MyError {
     NotFound(String),
     ConversionError(String),
     PermissionDeniedError(String),
-    BcryptError(bcrypt::BcryptError), // 其他错误类型...
+    BcryptError(bcrypt::BcryptError),
+    RedisError(String),
+    TooManyRequestsError(String),
+    MailError(String),
+    HttpClientError(String),
+    CryptoError(String), // 其他错误类型...
 }
 
 impl From<DbErr> for MyError {
@@ -25,6 +30,12 @@ impl From<bcrypt::BcryptError> for MyError {
     }
 }
 
+impl From<actix_redis::Error> for MyError {
+    fn from(err: actix_redis::Error) -> MyError {
+        MyError::RedisError(err.to_string())
+    }
+}
+
 impl std::fmt::Display for MyError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
@@ -37,6 +48,11 @@ impl std::fmt::Display for MyError {
             MyError::ConversionError(ref err) => write!(f, "ConversionError  error: {}", err),
             MyError::PermissionDeniedError(ref msg) => write!(f, "Permission error: {}", msg),
             MyError::BcryptError(ref msg) => write!(f, "BcryptError error: {}", msg),
+            MyError::RedisError(ref msg) => write!(f, "Redis error: {}", msg),
+            MyError::TooManyRequestsError(ref msg) => write!(f, "Too many requests: {}", msg),
+            MyError::MailError(ref msg) => write!(f, "Mail error: {}", msg),
+            MyError::HttpClientError(ref msg) => write!(f, "Http client error: {}", msg),
+            MyError::CryptoError(ref msg) => write!(f, "Crypto error: {}", msg),
         }
     }
 }
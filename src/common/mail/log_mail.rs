@@ -0,0 +1,52 @@
+use crate::common::error::MyError;
+use crate::common::mail::{Mail, MailSender};
+use async_trait::async_trait;
+use chrono::Local;
+use log::info;
+use tokio::io::AsyncWriteExt;
+
+// 本地测试用：邮件只写入日志，配置了 file_path 时同时追加到文件
+pub struct LogMailSender {
+    file_path: Option<String>,
+}
+
+impl LogMailSender {
+    pub fn new(file_path: Option<String>) -> Self {
+        LogMailSender {
+            file_path: file_path.filter(|path| !path.is_empty()),
+        }
+    }
+}
+
+#[async_trait]
+impl MailSender for LogMailSender {
+    async fn send(&self, mail: Mail) -> Result<(), MyError> {
+        info!(
+            "mail to: {}, subject: {}\n{}",
+            mail.to, mail.subject, mail.body
+        );
+
+        let Some(file_path) = &self.file_path else {
+            return Ok(());
+        };
+        let content = format!(
+            "Date: {}\nTo: {}\nSubject: {}\n\n{}\n\n",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            mail.to,
+            mail.subject,
+            mail.body
+        );
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)
+            .await
+            .map_err(|e| MyError::MailError(e.to_string()))?;
+        file.write_all(content.as_bytes())
+            .await
+            .map_err(|e| MyError::MailError(e.to_string()))?;
+        file.flush()
+            .await
+            .map_err(|e| MyError::MailError(e.to_string()))
+    }
+}
@@ -0,0 +1,29 @@
+pub mod log_mail;
+pub mod smtp;
+
+use crate::common::error::MyError;
+use crate::config::cfg::{MailConfig, MailSenderKind};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+// 待发送的邮件，正文为纯文本
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// 邮件发送方式，按配置选择具体实现
+#[async_trait]
+pub trait MailSender: Send + Sync {
+    async fn send(&self, mail: Mail) -> Result<(), MyError>;
+}
+
+// 根据配置创建邮件发送器，SMTP 配置有误（如发件人地址无效）时返回错误
+pub fn build_mail_sender(config: &MailConfig) -> Result<Arc<dyn MailSender>, MyError> {
+    Ok(match config.sender {
+        MailSenderKind::Smtp => Arc::new(smtp::SmtpMailSender::new(config)?),
+        MailSenderKind::Log => Arc::new(log_mail::LogMailSender::new(config.file_path.clone())),
+    })
+}
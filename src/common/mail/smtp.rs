@@ -0,0 +1,76 @@
+use crate::common::error::MyError;
+use crate::common::mail::{Mail, MailSender};
+use crate::config::cfg::{MailConfig, SmtpSecurity};
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::extension::ClientId;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::time::Duration;
+
+// 通过 SMTP 发送邮件，支持明文、STARTTLS 和 TLS 直连
+#[derive(Clone)]
+pub struct SmtpMailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailSender {
+    pub fn new(config: &MailConfig) -> Result<Self, MyError> {
+        let mut builder = match config.smtp_security {
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+            }
+            SmtpSecurity::Tls => {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)
+                    .map_err(to_mail_error)?
+            }
+            SmtpSecurity::Starttls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+                    .map_err(to_mail_error)?
+            }
+        }
+        .port(config.smtp_port)
+        .hello_name(ClientId::Domain("localhost".to_string()))
+        .timeout(Some(Duration::from_secs(config.smtp_timeout)));
+        if let Some(user) = config.smtp_user.clone().filter(|user| !user.is_empty()) {
+            builder = builder.credentials(Credentials::new(
+                user,
+                config.smtp_password.clone().unwrap_or_default(),
+            ));
+        }
+        Ok(SmtpMailSender {
+            transport: builder.build(),
+            from: config.from.parse().map_err(to_mail_error)?,
+        })
+    }
+
+    // 收件人地址按邮箱格式解析，含换行等字符的地址会被拒绝，不会拼接进 SMTP 命令或邮件头
+    fn build_message(&self, mail: Mail) -> Result<Message, MyError> {
+        let to: Mailbox = mail.to.parse().map_err(to_mail_error)?;
+        Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(mail.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(mail.body)
+            .map_err(to_mail_error)
+    }
+}
+
+#[async_trait]
+impl MailSender for SmtpMailSender {
+    async fn send(&self, mail: Mail) -> Result<(), MyError> {
+        let message = self.build_message(mail)?;
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(to_mail_error)
+    }
+}
+
+fn to_mail_error<E: std::fmt::Display>(err: E) -> MyError {
+    MyError::MailError(err.to_string())
+}
@@ -1,7 +1,10 @@
+pub mod api_route;
 pub mod auth;
+pub mod client_ip;
 pub mod db;
 pub mod error;
 pub mod log;
+pub mod mail;
 pub mod redis;
 pub mod resp;
 pub mod value;
@@ -3,12 +3,11 @@ use actix_redis::RespError::Unexpected;
 use actix_redis::RespValue;
 use actix_redis::{resp_array, Command, Error, RedisActor};
 
-struct RedisOps;
+pub struct RedisOps;
 
 impl RedisOps {
     /// 设置键值对
-    #[allow(dead_code)]
-    async fn set(addr: &Addr<RedisActor>, key: &str, value: &str) -> Result<String, Error> {
+    pub async fn set(addr: &Addr<RedisActor>, key: &str, value: &str) -> Result<String, Error> {
         let res = addr
             .send(Command(resp_array!["SET", key, value]))
             .await
@@ -24,8 +23,7 @@ impl RedisOps {
     }
 
     /// 获取键的值
-    #[allow(dead_code)]
-    async fn get(addr: &Addr<RedisActor>, key: &str) -> Result<Option<String>, actix_redis::Error> {
+    pub async fn get(addr: &Addr<RedisActor>, key: &str) -> Result<Option<String>, actix_redis::Error> {
         let res = addr
             .send(Command(resp_array!["GET", key]))
             .await
@@ -40,8 +38,8 @@ impl RedisOps {
         }
     }
 
-    #[allow(dead_code)]
-    async fn del(addr: &Addr<RedisActor>, key: &str) -> Result<usize, actix_redis::Error> {
+    /// 删除键
+    pub async fn del(addr: &Addr<RedisActor>, key: &str) -> Result<usize, actix_redis::Error> {
         let res = addr
             .send(Command(resp_array!["DEL", key]))
             .await
@@ -54,6 +52,132 @@ impl RedisOps {
             ))),
         }
     }
+
+    /// 设置键值对并指定过期时间（秒）
+    pub async fn set_ex(
+        addr: &Addr<RedisActor>,
+        key: &str,
+        value: &str,
+        seconds: u64,
+    ) -> Result<String, Error> {
+        let res = addr
+            .send(Command(resp_array![
+                "SET",
+                key,
+                value,
+                "EX",
+                seconds.to_string()
+            ]))
+            .await
+            .map_err(|e| Error::Redis(Unexpected(e.to_string())))?;
+
+        match res {
+            Ok(RespValue::SimpleString(s)) if s == "OK" => Ok(s),
+            Ok(RespValue::Error(e)) => Err(Error::Redis(Unexpected(e))),
+            _ => Err(Error::Redis(Unexpected(
+                "Unexpected response from Redis".to_string(),
+            ))),
+        }
+    }
+
+    /// 键不存在时设置键值对并指定过期时间（秒），返回是否设置成功
+    pub async fn set_nx_ex(
+        addr: &Addr<RedisActor>,
+        key: &str,
+        value: &str,
+        seconds: u64,
+    ) -> Result<bool, Error> {
+        let res = addr
+            .send(Command(resp_array![
+                "SET",
+                key,
+                value,
+                "NX",
+                "EX",
+                seconds.to_string()
+            ]))
+            .await
+            .map_err(|e| Error::Redis(Unexpected(e.to_string())))?;
+
+        match res {
+            Ok(RespValue::SimpleString(s)) if s == "OK" => Ok(true),
+            Ok(RespValue::Nil) => Ok(false),
+            Ok(RespValue::Error(e)) => Err(Error::Redis(Unexpected(e))),
+            _ => Err(Error::Redis(Unexpected(
+                "Unexpected response from Redis".to_string(),
+            ))),
+        }
+    }
+
+    /// 原子地设置新值并返回旧值
+    pub async fn getset(
+        addr: &Addr<RedisActor>,
+        key: &str,
+        value: &str,
+    ) -> Result<Option<String>, Error> {
+        let res = addr
+            .send(Command(resp_array!["GETSET", key, value]))
+            .await
+            .map_err(|e| Error::Redis(Unexpected(e.to_string())))?;
+
+        match res {
+            Ok(RespValue::BulkString(bytes)) => {
+                Ok(Some(String::from_utf8_lossy(&bytes).to_string()))
+            }
+            Ok(RespValue::Nil) => Ok(None),
+            Ok(RespValue::Error(e)) => Err(Error::Redis(Unexpected(e))),
+            _ => Err(Error::Redis(Unexpected(
+                "Unexpected response from Redis".to_string(),
+            ))),
+        }
+    }
+
+    /// 设置键的过期时间（秒）
+    pub async fn expire(addr: &Addr<RedisActor>, key: &str, seconds: u64) -> Result<bool, Error> {
+        let res = addr
+            .send(Command(resp_array!["EXPIRE", key, seconds.to_string()]))
+            .await
+            .map_err(|e| Error::Redis(Unexpected(e.to_string())))?;
+
+        match res {
+            Ok(RespValue::Integer(flag)) => Ok(flag == 1),
+            _ => Err(Error::Redis(Unexpected(
+                "Unexpected response type".to_string(),
+            ))),
+        }
+    }
+
+    /// 获取键的剩余过期时间（秒），键不存在时返回 -2，没有过期时间时返回 -1
+    pub async fn ttl(addr: &Addr<RedisActor>, key: &str) -> Result<i64, Error> {
+        let res = addr
+            .send(Command(resp_array!["TTL", key]))
+            .await
+            .map_err(|e| Error::Redis(Unexpected(e.to_string())))?;
+
+        match res {
+            Ok(RespValue::Integer(value)) => Ok(value),
+            Ok(RespValue::Error(e)) => Err(Error::Redis(Unexpected(e))),
+            _ => Err(Error::Redis(Unexpected(
+                "Unexpected response type".to_string(),
+            ))),
+        }
+    }
+
+    /// 将键的值加一，返回加一后的值
+    pub async fn incr(addr: &Addr<RedisActor>, key: &str) -> Result<i64, Error> {
+        let res = addr
+            .send(Command(resp_array!["INCR", key]))
+            .await
+            .map_err(|e| Error::Redis(Unexpected(e.to_string())))?;
+
+        match res {
+            Ok(RespValue::Integer(value)) => Ok(value),
+            Ok(RespValue::Error(e)) => Err(Error::Redis(Unexpected(e))),
+            _ => Err(Error::Redis(Unexpected(
+                "Unexpected response type".to_string(),
+            ))),
+        }
+    }
 }
 
 #[cfg(test)]
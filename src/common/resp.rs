@@ -1,4 +1,4 @@
-use actix_web::error::ErrorUnauthorized;
+use actix_web::error::InternalError;
 use actix_web::http::StatusCode;
 use actix_web::{Error, HttpResponse, ResponseError};
 use serde::{Deserialize, Serialize};
@@ -78,6 +78,12 @@ pub enum ApiError {
 
     #[error("Invalid Argument: {0}")]
     InvalidArgument(String),
+
+    #[error("Too Many Requests {0}")]
+    TooManyRequests(String),
+
+    #[error("Forbidden {0}")]
+    Forbidden(String),
 }
 pub fn create_error_response(message: &str, code: StatusCode) -> Error {
     let json_error = ApiResponse {
@@ -85,11 +91,20 @@ pub fn create_error_response(message: &str, code: StatusCode) -> Error {
         msg: message.to_string(),
         data: (),
     };
-    let error_body = serde_json::to_string(&json_error).unwrap_or_else(|_| "{}".to_string());
-    ErrorUnauthorized(error_body)
+    // 按传入的状态码返回，而不是一律返回 401
+    InternalError::from_response(message.to_string(), HttpResponse::build(code).json(json_error))
+        .into()
 }
 
 impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
     fn error_response(&self) -> HttpResponse {
         let status_code = self.status_code();
         let error_message = match self {
@@ -99,6 +114,8 @@ impl ResponseError for ApiError {
             ApiError::NotFound(msg) => msg,
             ApiError::CustomError(msg) => msg,
             ApiError::InvalidArgument(msg) => msg,
+            ApiError::TooManyRequests(msg) => msg,
+            ApiError::Forbidden(msg) => msg,
         };
         let error_response = ApiResponse {
             code: status_code.as_u16(),
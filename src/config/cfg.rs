@@ -10,6 +10,20 @@ pub struct AppConfig {
     pub redis: RedisConfig,
     ///jwt配置
     pub jwt: JwtConfig,
+    ///权限配置
+    pub permission: PermissionConfig,
+    ///安全配置
+    pub security: SecurityConfig,
+    ///邮件配置
+    pub mail: MailConfig,
+    ///密码策略配置
+    pub password: PasswordConfig,
+    ///两步验证配置
+    pub two_factor: TwoFactorConfig,
+    ///API Key 配置
+    pub api_key: ApiKeyConfig,
+    ///OIDC 单点登录配置
+    pub oidc: OidcConfig,
 }
 
 /// DatabaseConfig 包含数据库连接池的配置参数。
@@ -71,6 +85,368 @@ pub struct RedisConfig {
 // 定义 Jwt 配置结构体
 #[derive(Debug, Deserialize, Serialize)]
 pub struct JwtConfig {
+    /// 未配置 keys 时使用 HS256 签名的密钥
     pub secret: String,
     pub expire_time: u64,
+    /// refresh token 的有效期（秒）
+    pub refresh_expire_time: u64,
+    /// 签发方，写入 iss 并在校验时比对
+    pub issuer: String,
+    /// 接收方，写入 aud 并在校验时比对
+    pub audience: String,
+    /// 当前用于签名的 key 的 kid，配置了 keys 时必填
+    pub signing_kid: Option<String>,
+    /// 签名和验签使用的 key，未配置时使用 secret 做 HS256 签名
+    #[serde(default)]
+    pub keys: Vec<JwtKeyConfig>,
+}
+
+// 定义 Jwt 签名 key 的配置结构体
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JwtKeyConfig {
+    /// key 的唯一标识，写入 token 头部的 kid
+    pub kid: String,
+
+    /// 签名算法：HS256/384/512、RS256/384/512、PS256/384/512、ES256/384、EdDSA
+    pub algorithm: String,
+
+    /// PEM 格式的私钥文件，只有签名的 key 需要；轮换后只保留公钥用于验签
+    pub private_key_path: Option<String>,
+
+    /// PEM 格式的公钥文件，非对称算法必填，会发布到 JWKS
+    pub public_key_path: Option<String>,
+
+    /// HS 算法使用的密钥，为空时使用 jwt.secret
+    pub secret: Option<String>,
+}
+
+// 定义权限配置结构体
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PermissionConfig {
+    /// 超级管理员角色编码，拥有全部接口权限
+    pub super_role_codes: Vec<String>,
+
+    /// 角色权限在 Redis 中的缓存时间（秒）
+    pub cache_ttl: u64,
+
+    /// 角色权限在本地 LRU 中的缓存时间（秒）
+    pub local_cache_ttl: u64,
+
+    /// 本地 LRU 最多缓存的角色数
+    pub local_cache_capacity: usize,
+
+    /// 启动时是否将已注册的路由同步到 sys_api
+    pub sync_api_on_startup: bool,
+}
+
+// 定义安全配置结构体
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SecurityConfig {
+    /// 登录失败次数的统计窗口（秒），窗口内没有新的失败则计数清零
+    pub login_failure_window: u64,
+
+    /// 失败达到该次数后，每次失败都会逐步延迟响应
+    pub login_delay_after: u32,
+
+    /// 每多失败一次增加的延迟（毫秒）
+    pub login_delay_step_ms: u64,
+
+    /// 单次失败响应的最大延迟（毫秒）
+    pub login_max_delay_ms: u64,
+
+    /// 同一用户名失败达到该次数后锁定
+    pub login_max_failures: u32,
+
+    /// 同一 IP 失败达到该次数后锁定
+    pub login_ip_max_failures: u32,
+
+    /// 锁定时长（秒）
+    pub login_lockout_duration: u64,
+
+    /// 重置密码 token 的有效期（秒）
+    pub password_reset_expire_time: u64,
+
+    /// 重置密码页面地址，token 拼接在末尾
+    pub password_reset_url: String,
+
+    /// 忘记密码时同一邮箱两次发送重置邮件的最小间隔（秒）
+    pub password_reset_cooldown: u64,
+
+    /// 登录限流和登录日志是否使用 X-Forwarded-For 等代理头中的客户端IP，仅在可信反向代理后开启
+    pub trust_proxy_headers: bool,
+}
+
+// 定义密码策略配置结构体
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PasswordConfig {
+    /// 密码最小长度
+    pub min_length: usize,
+
+    /// 密码最大长度
+    pub max_length: usize,
+
+    /// 是否必须包含大写字母
+    pub require_uppercase: bool,
+
+    /// 是否必须包含小写字母
+    pub require_lowercase: bool,
+
+    /// 是否必须包含数字
+    pub require_digit: bool,
+
+    /// 是否必须包含特殊字符
+    pub require_special: bool,
+
+    /// 禁止使用的密码，不区分大小写
+    pub banned_passwords: Vec<String>,
+
+    /// 密码中是否禁止包含用户名
+    pub disallow_user_name: bool,
+
+    /// Argon2id 的内存开销（KiB）
+    pub argon2_memory_kib: u32,
+
+    /// Argon2id 的迭代次数
+    pub argon2_iterations: u32,
+
+    /// Argon2id 的并行度
+    pub argon2_parallelism: u32,
+}
+
+// 定义两步验证配置结构体
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TwoFactorConfig {
+    /// 认证器 App 中显示的发行方名称
+    pub issuer: String,
+
+    /// 验证码的时间步长（秒）
+    pub period: u64,
+
+    /// 验证码位数，只支持 6 到 8 位
+    #[serde(deserialize_with = "deserialize_totp_digits")]
+    pub digits: u32,
+
+    /// 允许前后偏差的时间步数，用于容忍客户端时钟误差
+    pub skew: u64,
+
+    /// 登录挑战 token 的有效期（秒）
+    pub challenge_expire_time: u64,
+
+    /// 同一用户在统计窗口内最多允许输错验证码或恢复码的次数，不区分登录挑战，达到后锁定两步验证
+    pub max_failures: u32,
+
+    /// 验证码错误次数的统计窗口（秒）
+    pub failure_window: u64,
+
+    /// 两步验证的锁定时长（秒）
+    pub lockout_duration: u64,
+
+    /// 未确认的绑定密钥的有效期（秒）
+    pub enroll_expire_time: u64,
+
+    /// 每次生成的恢复码数量
+    pub recovery_code_count: usize,
+}
+
+// 定义 API Key 配置结构体
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ApiKeyConfig {
+    /// API Key 的固定前缀，用于和 JWT 区分
+    pub prefix: String,
+
+    /// 未指定有效期时的默认有效天数
+    pub default_expire_days: u32,
+
+    /// 允许的最长有效天数
+    pub max_expire_days: u32,
+
+    /// 每个用户最多拥有的未吊销 API Key 数量
+    pub max_keys_per_user: u64,
+
+    /// 是否使用 X-Forwarded-For 等代理头中的客户端IP校验白名单，仅在可信反向代理后开启
+    pub trust_proxy_headers: bool,
+}
+
+// 定义 OIDC 单点登录配置结构体
+#[derive(Debug, Deserialize, Serialize)]
+pub struct OidcConfig {
+    /// 登录请求的 state、nonce 和 PKCE verifier 在 Redis 中的有效时间（秒）
+    pub state_expire_time: u64,
+
+    /// 请求身份提供方的超时（秒）
+    pub http_timeout: u64,
+
+    /// discovery 文档和 JWKS 的缓存时间（秒）
+    pub metadata_cache_ttl: u64,
+
+    /// 身份提供方列表
+    #[serde(default)]
+    pub providers: Vec<OidcProviderConfig>,
+}
+
+// 调用 token 端点时客户端的认证方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OidcClientAuthMethod {
+    /// 通过 HTTP Basic 头传递 client_id 和 client_secret
+    #[default]
+    ClientSecretBasic,
+    /// 在表单中传递 client_id 和 client_secret
+    ClientSecretPost,
+}
+
+// 定义 OIDC 身份提供方的配置结构体
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OidcProviderConfig {
+    /// 提供方标识，用于 /auth/oidc/{provider} 路径
+    pub name: String,
+
+    /// 登录页展示的名称
+    pub display_name: String,
+
+    /// 提供方的 issuer，必须与 id_token 中的 iss 一致
+    pub issuer: String,
+
+    /// 在提供方注册的客户端ID
+    pub client_id: String,
+
+    /// 客户端密钥，公共客户端可以不配置，只使用 PKCE
+    pub client_secret: Option<String>,
+
+    /// 调用 token 端点时的认证方式：client_secret_basic 或 client_secret_post
+    #[serde(default)]
+    pub client_auth_method: OidcClientAuthMethod,
+
+    /// 在提供方注册的回调地址
+    pub redirect_uri: String,
+
+    /// 请求的 scope，必须包含 openid
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+
+    /// 是否通过 {issuer}/.well-known/openid-configuration 获取端点
+    #[serde(default = "default_true")]
+    pub discovery: bool,
+
+    /// 授权端点，配置后覆盖 discovery 的结果
+    pub authorization_endpoint: Option<String>,
+
+    /// token 端点，配置后覆盖 discovery 的结果
+    pub token_endpoint: Option<String>,
+
+    /// JWKS 地址，配置后覆盖 discovery 的结果
+    pub jwks_uri: Option<String>,
+
+    /// 作为用户名的 claim
+    #[serde(default = "default_oidc_user_name_claim")]
+    pub user_name_claim: String,
+
+    /// 首次登录时是否自动创建用户
+    #[serde(default)]
+    pub auto_create_user: bool,
+
+    /// 自动创建用户时分配的角色编码
+    #[serde(default)]
+    pub default_role_codes: Vec<String>,
+
+    /// claim 到角色的映射规则，每次登录时同步
+    #[serde(default)]
+    pub role_mappings: Vec<OidcRoleMapping>,
+}
+
+// 定义 claim 到角色的映射规则
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OidcRoleMapping {
+    /// claim 路径，嵌套字段用 . 分隔，例如 realm_access.roles
+    pub claim: String,
+
+    /// claim 为字符串时需要相等，为数组时需要包含该值
+    pub value: String,
+
+    /// 匹配时分配的角色编码
+    pub role_code: String,
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "profile".to_string(),
+        "email".to_string(),
+    ]
+}
+
+fn default_oidc_user_name_claim() -> String {
+    "preferred_username".to_string()
+}
+
+// 验证码位数超出 6 到 8 位时加载配置失败
+fn deserialize_totp_digits<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let digits = u32::deserialize(deserializer)?;
+    if !(6..=8).contains(&digits) {
+        return Err(serde::de::Error::custom(format!(
+            "two_factor.digits must be between 6 and 8, got {}",
+            digits
+        )));
+    }
+    Ok(digits)
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// 邮件发送方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MailSenderKind {
+    /// 通过 SMTP 服务器发送
+    Smtp,
+    /// 只写入日志或文件，用于本地测试
+    Log,
+}
+
+// SMTP 连接的加密方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// 明文连接
+    None,
+    /// 明文连接后通过 STARTTLS 升级
+    Starttls,
+    /// TLS 直连，通常是 465 端口
+    Tls,
+}
+
+// 定义邮件配置结构体
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MailConfig {
+    /// 发送方式：smtp 或 log
+    pub sender: MailSenderKind,
+
+    /// 发件人地址
+    pub from: String,
+
+    /// SMTP 服务器地址
+    pub smtp_host: String,
+
+    /// SMTP 服务器端口
+    pub smtp_port: u16,
+
+    /// SMTP 连接的加密方式：none、starttls 或 tls
+    pub smtp_security: SmtpSecurity,
+
+    /// SMTP 登录用户名，为空时不登录
+    pub smtp_user: Option<String>,
+
+    /// SMTP 登录密码
+    pub smtp_password: Option<String>,
+
+    /// SMTP 读写超时（秒）
+    pub smtp_timeout: u64,
+
+    /// sender 为 log 时，邮件同时追加到该文件
+    pub file_path: Option<String>,
 }
@@ -1,3 +1,4 @@
+use crate::common::mail::MailSender;
 use crate::config::{cfg, loader};
 use actix::Addr;
 use actix_redis::RedisActor;
@@ -19,6 +20,7 @@ pub fn set_app_state(state: AppState) -> Result<(), &'static str> {
 pub struct AppState {
     pub redis_conn: Addr<RedisActor>,
     pub mysql_conn: Arc<DatabaseConnection>,
+    pub mail_sender: Arc<dyn MailSender>,
 }
 
 pub static APP_CONFIG: Lazy<cfg::AppConfig> = Lazy::new(|| {
@@ -30,10 +32,15 @@ pub static APP_CONFIG: Lazy<cfg::AppConfig> = Lazy::new(|| {
 });
 
 impl AppState {
-    pub fn new(redis_conn: Addr<RedisActor>, mysql_conn: Arc<DatabaseConnection>) -> Self {
+    pub fn new(
+        redis_conn: Addr<RedisActor>,
+        mysql_conn: Arc<DatabaseConnection>,
+        mail_sender: Arc<dyn MailSender>,
+    ) -> Self {
         Self {
             redis_conn,
             mysql_conn,
+            mail_sender,
         }
     }
 }
@@ -49,6 +49,39 @@ pub fn validate_status(status: &str) -> Result<(), ValidationError> {
     }
 }
 
+pub fn validate_api_method(api_method: &str) -> Result<(), ValidationError> {
+    match api_method {
+        "GET" | "POST" | "PUT" | "DELETE" => Ok(()),
+        _ => {
+            let mut error = ValidationError::new("invalid_api_method");
+            error.message = Some("The apiMethod must be one of 'GET', 'POST', 'PUT', 'DELETE'.".into());
+            Err(error)
+        }
+    }
+}
+
+pub fn validate_data_scope(data_scope: &str) -> Result<(), ValidationError> {
+    match data_scope {
+        "ALL" | "CUSTOM" | "DEPT" | "DEPT_AND_CHILD" | "SELF_ONLY" => Ok(()),
+        _ => {
+            let mut error = ValidationError::new("invalid_data_scope");
+            error.message = Some("The dataScope must be one of 'ALL', 'CUSTOM', 'DEPT', 'DEPT_AND_CHILD', 'SELF_ONLY'.".into());
+            Err(error)
+        }
+    }
+}
+
+pub fn validate_permission_effect(effect: &str) -> Result<(), ValidationError> {
+    match effect {
+        "ALLOW" | "DENY" => Ok(()),
+        _ => {
+            let mut error = ValidationError::new("invalid_effect");
+            error.message = Some("The effect must be either 'ALLOW' or 'DENY'.".into());
+            Err(error)
+        }
+    }
+}
+
 pub fn validate_icon_type(icon_type: &str) -> Result<(), ValidationError> {
     match icon_type {
         "1" | "2" => Ok(()),
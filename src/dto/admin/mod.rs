@@ -1,9 +1,17 @@
 pub mod common_dto;
+pub mod sys_access_dto;
+pub mod sys_api_dto;
+pub mod sys_api_key_dto;
 pub mod sys_auth_dto;
+pub mod sys_dept_dto;
+pub mod sys_login_log_dto;
 pub mod sys_menu_dto;
+pub mod sys_oidc_dto;
 pub mod sys_permission_dto;
 pub mod sys_role_dto;
 pub mod sys_role_permission_dto;
+pub mod sys_two_factor_dto;
 pub mod sys_user_dto;
+pub mod sys_user_permission_dto;
 pub mod sys_user_role_dto;
 pub mod sys_route_dto;
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+// 一条从用户到权限的授权链路
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantChainDto {
+    pub source: String,     // ROLE 通过角色授予，USER 通过用户直接授权
+    pub roles: Vec<String>, // 用户分配的角色沿继承链到授予权限的角色，USER 来源为空
+    pub permission_id: i32,
+    pub permission_code: String,
+    pub effect: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub actions: Vec<String>, // 权限允许的操作，仅接口有
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub blocked_by: Option<String>, // 链路未生效的原因
+}
+
+// 用户可访问的菜单、按钮或接口，以及授予它的链路
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveTargetDto {
+    pub id: i32,
+    pub name: String,
+    pub path: String, // 菜单为路由路径，按钮为按钮编码，接口为接口路径
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub method: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub actions: Vec<String>,
+    pub grants: Vec<GrantChainDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectivePermissionsRespDto {
+    pub user_id: i32,
+    pub user_enabled: bool,
+    pub super_admin: bool, // 超级管理员可访问全部未被拒绝的接口
+    pub menus: Vec<EffectiveTargetDto>,
+    pub buttons: Vec<EffectiveTargetDto>,
+    pub apis: Vec<EffectiveTargetDto>,
+}
+
+// 查询无法访问的原因，有 method 时按接口解释，否则按菜单路由路径或按钮编码解释
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccessExplainQueryDto {
+    pub path: String,
+    pub method: Option<String>,
+}
+
+// 单个匹配目标的解释
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetExplanationDto {
+    pub target_type: String, // MENU、BUTTON 或 API
+    pub id: i32,
+    pub name: String,
+    pub allowed: bool,
+    pub granted_by: Vec<GrantChainDto>,
+    pub denied_by: Vec<GrantChainDto>,
+    pub blocked: Vec<GrantChainDto>,
+    pub unassigned_permissions: Vec<String>, // 关联了该目标但未分配给用户或其角色的权限
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessExplanationDto {
+    pub allowed: bool,
+    pub reasons: Vec<String>,
+    pub targets: Vec<TargetExplanationDto>,
+}
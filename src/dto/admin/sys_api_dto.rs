@@ -0,0 +1,104 @@
+use crate::common::api_route::ApiRoute;
+use crate::dto::admin::common_dto::validate_api_method;
+use crate::schemas::admin::sys_api::Model;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiCreateDto {
+    #[validate(length(min = 1, max = 64))]
+    pub api_name: String,
+    #[validate(length(min = 1, max = 64))]
+    pub api_group: String,
+    #[validate(length(min = 1, max = 255))]
+    pub api_path: String,
+    #[validate(custom(function = "validate_api_method"))]
+    pub api_method: String,
+    #[validate(length(max = 255))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiUpdateDto {
+    #[validate(length(min = 1, max = 64))]
+    pub api_name: Option<String>,
+    #[validate(length(min = 1, max = 64))]
+    pub api_group: Option<String>,
+    #[validate(length(min = 1, max = 255))]
+    pub api_path: Option<String>,
+    #[validate(custom(function = "validate_api_method"))]
+    pub api_method: Option<String>,
+    #[validate(length(max = 255))]
+    pub description: Option<String>,
+}
+
+// 分页查询参数，可按分组和请求方法过滤
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiQueryDto {
+    pub current: Option<u64>,
+    pub size: Option<u64>,
+    pub api_group: Option<String>,
+    pub api_method: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiDto {
+    pub id: i32,
+    pub api_name: String,
+    pub api_group: String,
+    pub api_path: String,
+    pub api_method: String,
+    pub description: String,
+    pub create_by: String,
+    pub create_time: String,
+    pub update_by: String,
+    pub update_time: String,
+    pub deprecated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiDeleteRespDto {
+    pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApisDeleteRespDto {
+    pub deleted_api_ids: Vec<i32>,
+}
+
+impl From<Model> for ApiDto {
+    fn from(model: Model) -> Self {
+        ApiDto {
+            id: model.id,
+            api_name: model.api_name,
+            api_group: model.api_group,
+            api_path: model.api_path,
+            api_method: model.api_method.as_str().to_string(),
+            description: model.description.unwrap_or_default(),
+            create_by: model.create_user,
+            create_time: model
+                .create_time
+                .map_or_else(|| "".to_string(), |dt| dt.to_string()),
+            update_by: model.update_user.unwrap_or_default(),
+            update_time: model
+                .update_time
+                .map_or_else(|| "".to_string(), |dt| dt.to_string()),
+            deprecated: model.deprecated == 1,
+        }
+    }
+}
+
+// 路由同步到 sys_api 的差异
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiSyncDiffDto {
+    pub created: Vec<ApiRoute>,    // 新增的路由
+    pub regrouped: Vec<ApiRoute>,  // 所属分组变化的路由
+    pub restored: Vec<ApiRoute>,   // 重新出现、取消废弃标记的路由
+    pub deprecated: Vec<ApiDto>,   // 已不存在、将被标记为废弃的接口
+}
@@ -0,0 +1,88 @@
+use crate::schemas::admin::sys_api_key::Model;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyCreateDto {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    #[validate(range(min = 1))]
+    pub expire_days: Option<u32>, // 不传时使用默认有效期
+    #[validate(length(max = 32))]
+    pub allowed_ips: Option<Vec<String>>, // 单个 IP 或 CIDR，为空表示不限制
+    #[validate(length(min = 1))]
+    pub permission_ids: Vec<i32>, // 只能是当前用户拥有的权限
+}
+
+// 分页查询参数，可按是否已吊销过滤
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyQueryDto {
+    pub current: Option<u64>,
+    pub size: Option<u64>,
+    pub revoked: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyDto {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub key_prefix: String,
+    pub allowed_ips: Vec<String>,
+    pub permission_ids: Vec<i32>,
+    pub expire_time: String,
+    pub last_used_time: String,
+    pub last_used_ip: String,
+    pub revoked: bool,
+    pub create_by: String,
+    pub create_time: String,
+}
+
+// 创建 API Key 的返回，key 只在这里出现一次
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyCreateRespDto {
+    #[serde(flatten)]
+    pub base: ApiKeyDto,
+    pub key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKeyRevokeRespDto {
+    pub success: bool,
+}
+
+fn format_time(time: Option<chrono::DateTime<chrono::Utc>>) -> String {
+    time.map_or_else(
+        || "".to_string(),
+        |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+    )
+}
+
+impl ApiKeyDto {
+    pub fn from_model(model: Model, permission_ids: Vec<i32>) -> Self {
+        ApiKeyDto {
+            id: model.id,
+            user_id: model.user_id,
+            name: model.name,
+            key_prefix: model.key_prefix,
+            allowed_ips: model
+                .allowed_ips
+                .unwrap_or_default()
+                .split(',')
+                .filter(|ip| !ip.is_empty())
+                .map(str::to_string)
+                .collect(),
+            permission_ids,
+            expire_time: format_time(model.expire_time),
+            last_used_time: format_time(model.last_used_time),
+            last_used_ip: model.last_used_ip.unwrap_or_default(),
+            revoked: model.revoked != 0,
+            create_by: model.create_user,
+            create_time: format_time(model.create_time),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use validator::Validate;
 
+use crate::dto::admin::sys_two_factor_dto::TwoFactorChallengeDto;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -19,6 +20,52 @@ pub struct SysLoginRespDto {
     pub refresh_token: String,
 }
 
+// 登录结果：直接签发 token，或需要继续完成两步验证
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SysLoginResultDto {
+    Token(SysLoginRespDto),
+    Challenge(TwoFactorChallengeDto),
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenDto {
+    #[validate(length(min = 1, max = 255))]
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogoutDto {
+    pub refresh_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordDto {
+    #[validate(length(min = 1, max = 255))]
+    pub old_password: String,
+    #[validate(length(min = 6, max = 255))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordDto {
+    #[validate(length(min = 1, max = 255))]
+    pub user_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordDto {
+    #[validate(length(min = 1, max = 255))]
+    pub token: String,
+    #[validate(length(min = 6, max = 255))]
+    pub new_password: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UserInfoDto {
     pub user_name: String,
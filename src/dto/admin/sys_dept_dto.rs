@@ -0,0 +1,86 @@
+use crate::dto::admin::common_dto::validate_status;
+use crate::schemas::admin::sys_dept::Model;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct DeptCreateDto {
+    #[validate(length(min = 1, max = 64))]
+    pub dept_name: String, // 部门名称
+    pub parent_id: i32,         // 上级部门，0 表示顶级部门
+    pub leader_id: Option<i32>, // 负责人用户ID
+    pub order: Option<i32>,     // 同级排序
+    #[validate(length(min = 1), custom(function = "validate_status"))]
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct DeptUpdateDto {
+    #[validate(length(min = 1, max = 64))]
+    pub dept_name: Option<String>,
+    pub leader_id: Option<i32>, // 为 0 时清除负责人
+    pub order: Option<i32>,
+    #[validate(custom(function = "validate_status"))]
+    pub status: Option<String>,
+}
+
+// 移动部门，上级部门不能是自身或其下级部门
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeptMoveDto {
+    pub parent_id: i32,     // 新的上级部门，0 表示移动为顶级部门
+    pub order: Option<i32>, // 在新位置的排序，为空时保持原排序
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeptRespDto {
+    pub id: i32,
+    pub parent_id: i32,
+    pub dept_name: String,
+    pub leader_id: Option<i32>,
+    pub order: i32,
+    pub status: String,
+    pub create_by: String,
+    pub create_time: String,
+    pub update_by: String,
+    pub update_time: String,
+}
+
+impl From<Model> for DeptRespDto {
+    fn from(model: Model) -> Self {
+        DeptRespDto {
+            id: model.id,
+            parent_id: model.parent_id.unwrap_or_default(),
+            dept_name: model.dept_name,
+            leader_id: model.leader_id,
+            order: model.order,
+            status: model.status.to_string(),
+            create_by: model.create_user,
+            create_time: model.create_time.map_or_else(
+                || "".to_string(),
+                |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+            ),
+            update_by: model.update_user.unwrap_or_default(),
+            update_time: model.update_time.map_or_else(
+                || "".to_string(),
+                |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+            ),
+        }
+    }
+}
+
+// 部门树，下级部门按排序号和ID升序
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeptTreeDto {
+    #[serde(flatten)]
+    pub base: DeptRespDto,
+    pub children: Vec<DeptTreeDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeptDeleteRespDto {
+    pub success: bool,
+}
@@ -0,0 +1,50 @@
+use crate::schemas::admin::sys_login_log::Model;
+use serde::{Deserialize, Serialize};
+
+// 登录请求的客户端信息
+#[derive(Debug, Clone, Default)]
+pub struct LoginClientDto {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+// 分页查询参数，可按用户名、IP、登录结果和时间范围过滤
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginLogQueryDto {
+    pub current: Option<u64>,
+    pub size: Option<u64>,
+    pub user_name: Option<String>,
+    pub ip: Option<String>,
+    pub result: Option<String>,     // SUCCESS / FAILURE
+    pub start_time: Option<String>, // 格式：%Y-%m-%d %H:%M:%S
+    pub end_time: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginLogDto {
+    pub id: i32,
+    pub user_name: String,
+    pub ip: String,
+    pub user_agent: String,
+    pub result: String,
+    pub failure_reason: String,
+    pub login_time: String,
+}
+
+impl From<Model> for LoginLogDto {
+    fn from(model: Model) -> Self {
+        LoginLogDto {
+            id: model.id,
+            user_name: model.user_name,
+            ip: model.ip.unwrap_or_default(),
+            user_agent: model.user_agent.unwrap_or_default(),
+            result: model.result.as_str().to_string(),
+            failure_reason: model.failure_reason.unwrap_or_default(),
+            login_time: model
+                .login_time
+                .map_or_else(|| "".to_string(), |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+        }
+    }
+}
@@ -7,10 +7,12 @@ use serde_json::{Value};
 use validator::Validate;
 
 // 定义一个结构体来表示菜单按钮
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct MenuButton {
-    code: String, // 按钮代码
-    desc: String, // 按钮描述
+    #[validate(length(min = 1, max = 255))]
+    pub code: String, // 按钮代码
+    #[validate(length(max = 64))]
+    pub desc: String, // 按钮描述
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -42,6 +44,7 @@ pub struct MenuCreateDto {
     pub component: Option<String>,  //组建路径
     pub active_menu: Option<String>, // 高亮的菜单
     pub query: Option<Value>,       //路由参数
+    #[validate(nested)]
     pub buttons: Option<Vec<MenuButton>>, //按钮
 }
 
@@ -161,6 +164,7 @@ pub struct MenuUpdateDto {
     pub component: Option<String>,  //组建路径
     pub active_menu: Option<String>, // 高亮的菜单
     pub query: Option<Value>,       //路由参数
+    #[validate(nested)]
     pub buttons: Option<Vec<MenuButton>>, //按钮
 }
 
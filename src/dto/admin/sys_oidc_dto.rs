@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// 可用于登录的身份提供方
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcProviderDto {
+    pub name: String,
+    pub display_name: String,
+}
+
+// 前端跳转到该地址完成身份提供方的登录
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcAuthorizeRespDto {
+    pub authorization_url: String,
+}
+
+// 身份提供方回调带回的授权码和 state
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcCallbackDto {
+    #[validate(length(min = 1, max = 2048))]
+    pub code: String,
+    #[validate(length(min = 1, max = 255))]
+    pub state: String,
+}
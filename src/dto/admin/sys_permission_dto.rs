@@ -1,3 +1,4 @@
+use crate::dto::admin::common_dto::validate_permission_effect;
 use crate::schemas::admin::sys_permission::Model;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -13,6 +14,8 @@ pub struct PermissionCreationDto {
     pub menus: Option<Vec<i32>>,
     pub apis: Option<Vec<i32>>,
     pub status: String,
+    #[validate(custom(function = "validate_permission_effect"))]
+    pub effect: Option<String>, // ALLOW 或 DENY，为空时为 ALLOW
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,6 +37,7 @@ pub struct PermissionSimpleRespDto {
     pub permission_name: String,
     pub permission_code: String,
     pub status: String,
+    pub effect: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -58,6 +62,7 @@ pub struct PermissionDto {
     pub menus: Option<Vec<i32>>,
     pub apis: Option<Vec<i32>>,
     pub status: Option<String>,
+    pub effect: Option<String>, // ALLOW 或 DENY，DENY 权限关联的菜单和接口对拥有者一律拒绝
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -77,6 +82,7 @@ impl From<Model> for PermissionDto {
             menus: None,
             apis: None,
             status: None,
+            effect: Some(model.effect.as_str().to_string()),
         }
     }
 }
@@ -98,6 +104,7 @@ pub struct PermissionDetailsDto {
     pub menus: Vec<String>,
     pub apis: Vec<ApiDetail>,
     pub status: String,
+    pub effect: String,
 }
 
 
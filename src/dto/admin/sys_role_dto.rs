@@ -1,4 +1,4 @@
-use crate::dto::admin::common_dto::validate_status;
+use crate::dto::admin::common_dto::{validate_data_scope, validate_status};
 use crate::schemas::admin::sys_role;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -11,6 +11,12 @@ pub struct RoleDto {
     pub role_name: Option<String>,
     pub permission_ids: Option<Vec<i32>>,
     pub role_desc: Option<String>,
+    pub role_home: Option<String>,
+    pub require_two_factor: bool,
+    pub parent_role_id: Option<i32>, // 继承的父角色
+    pub inherited_permission_ids: Option<Vec<i32>>, // 从父角色及其祖先继承的权限
+    pub data_scope: String, // 数据权限范围
+    pub data_scope_dept_ids: Option<Vec<i32>>, // 自定义数据权限的部门
     pub status: String,
 }
 
@@ -22,10 +28,24 @@ impl From<sys_role::Model> for RoleDto {
             role_name: Some(model.role_name),
             permission_ids: None,
             role_desc: model.description,
+            role_home: model.home,
+            require_two_factor: model.require_two_factor == 1,
+            parent_role_id: model.parent_role_id,
+            inherited_permission_ids: None,
+            data_scope: model.data_scope.as_str().to_string(),
+            data_scope_dept_ids: None,
             status: model.status.to_string(),
         }
     }
 }
+
+// 角色继承树，子角色继承父角色的全部权限
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoleTreeDto {
+    #[serde(flatten)]
+    pub base: RoleDto,
+    pub children: Vec<RoleTreeDto>,
+}
 #[derive(Debug, Serialize, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct RoleCreationDto {
@@ -47,6 +67,13 @@ pub struct RoleCreationDto {
         message = "description must be between 1 and 512 characters long"
     ))]
     pub role_desc: String,
+    #[validate(length(min = 1, max = 64))]
+    pub role_home: Option<String>, // 角色首页路由名称，为空时使用默认首页
+    pub require_two_factor: Option<bool>, // 是否要求该角色的用户启用两步验证
+    pub parent_role_id: Option<i32>, // 继承的父角色，为空或 0 时不继承
+    #[validate(custom(function = "validate_data_scope"))]
+    pub data_scope: Option<String>, // 数据权限范围，为空时为 ALL
+    pub data_scope_dept_ids: Option<Vec<i32>>, // data_scope 为 CUSTOM 时可见的部门
     pub permission_ids: Option<Vec<i32>>,
     #[validate(length(min = 1), custom(function = "validate_status"))]
     pub status: String,
@@ -91,6 +118,13 @@ pub struct RoleUpdateDto {
     ))]
     pub permission_ids: Option<Vec<i32>>,
     pub role_desc: Option<String>,
+    #[validate(length(min = 1, max = 64))]
+    pub role_home: Option<String>, // 角色首页路由名称
+    pub require_two_factor: Option<bool>, // 是否要求该角色的用户启用两步验证
+    pub parent_role_id: Option<i32>, // 继承的父角色，为 0 时取消继承
+    #[validate(custom(function = "validate_data_scope"))]
+    pub data_scope: Option<String>, // 数据权限范围
+    pub data_scope_dept_ids: Option<Vec<i32>>, // data_scope 为 CUSTOM 时可见的部门
     pub status: Option<String>,
 }
 
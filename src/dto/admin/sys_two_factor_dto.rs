@@ -0,0 +1,63 @@
+use crate::dto::admin::sys_auth_dto::SysLoginRespDto;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// 绑定认证器所需的密钥和 otpauth 地址
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpSetupDto {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+// 密码校验通过、需要两步验证时登录接口的返回
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorChallengeDto {
+    pub user_name: String,
+    pub challenge_token: String,
+    pub enrollment_required: bool, // 角色要求两步验证但用户尚未绑定
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorEnrollDto {
+    #[validate(length(min = 1, max = 255))]
+    pub challenge_token: String,
+}
+
+// 使用验证码或恢复码完成登录，尚未绑定时 code 同时用于确认绑定
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorVerifyDto {
+    #[validate(length(min = 1, max = 255))]
+    pub challenge_token: String,
+    #[validate(length(min = 1, max = 16))]
+    pub code: Option<String>,
+    #[validate(length(min = 1, max = 32))]
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorVerifyRespDto {
+    #[serde(flatten)]
+    pub base: SysLoginRespDto,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>, // 登录时完成绑定才会返回
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorCodeDto {
+    #[validate(length(min = 1, max = 16))]
+    pub code: Option<String>,
+    #[validate(length(min = 1, max = 32))]
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodesDto {
+    pub recovery_codes: Vec<String>,
+}
@@ -21,6 +21,16 @@ pub struct UserCreateDto {
     pub status: String,
     #[serde(rename = "userRoles")]
     pub user_roles: Option<Vec<i32>>,
+    pub dept_id: Option<i32>, // 所属部门，为空或 0 时不属于任何部门
+}
+
+// 用户分页查询参数，指定部门时包含其全部下级部门的用户
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserQueryDto {
+    pub current: Option<u64>,
+    pub size: Option<u64>,
+    pub dept_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -44,6 +54,10 @@ pub struct UserWithRolesDto {
     pub update_by: String,
     pub update_time: String,
     pub user_roles: Option<Vec<i32>>,
+    #[serde(default)]
+    pub dept_id: Option<i32>,
+    #[serde(default)]
+    pub locked_for: Option<u64>, // 登录锁定的剩余时间（秒），未锁定时为空
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -74,6 +88,8 @@ impl From<(Model, Option<Vec<i32>>)> for UserWithRolesDto {
                 .update_time
                 .map_or_else(|| "".to_string(), |dt| dt.to_string()),
             user_roles: roles,
+            dept_id: user.dept_id,
+            locked_for: None,
         }
     }
 }
@@ -96,4 +112,5 @@ pub struct UserUpdateDto {
     pub status: Option<String>,
     #[serde(rename = "userRoles")]
     pub user_roles: Option<Vec<i32>>,
+    pub dept_id: Option<i32>, // 所属部门，为 0 时移出部门
 }
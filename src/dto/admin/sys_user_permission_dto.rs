@@ -0,0 +1,57 @@
+use crate::dto::admin::common_dto::validate_permission_effect;
+use crate::schemas::admin::sys_user_permission::Model;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// 单条用户直接授权
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPermissionItemDto {
+    pub permission_id: i32,
+    #[validate(custom(function = "validate_permission_effect"))]
+    pub effect: String, // ALLOW 额外授予，DENY 拒绝该权限关联的菜单和接口
+}
+
+// 设置用户的直接授权，会覆盖用户原有的直接授权
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct AssignUserPermissionsDto {
+    #[validate(nested)]
+    pub permissions: Vec<UserPermissionItemDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPermissionDto {
+    pub id: i32,
+    pub user_id: i32,
+    pub permission_id: i32,
+    pub effect: String,
+    pub create_user: String,
+}
+
+impl From<Model> for UserPermissionDto {
+    fn from(model: Model) -> Self {
+        UserPermissionDto {
+            id: model.id,
+            user_id: model.user_id,
+            permission_id: model.permission_id,
+            effect: model.effect.as_str().to_string(),
+            create_user: model.create_user,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssignUserPermissionsRespDto {
+    pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserPermissionsRespDto {
+    pub permissions: Vec<UserPermissionDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoveUserPermissionRespDto {
+    pub success: bool,
+}
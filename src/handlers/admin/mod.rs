@@ -1,8 +1,61 @@
+use crate::common::api_route::ApiRoute;
+use actix_web::web;
+
+pub mod sys_access_handler;
+pub mod sys_api_handler;
+pub mod sys_api_key_handler;
 pub mod sys_auth_handler;
+pub mod sys_dept_handler;
+pub mod sys_jwks_handler;
+pub mod sys_login_log_handler;
 pub mod sys_menu_handler;
+pub mod sys_oidc_handler;
 pub mod sys_permission_handler;
 pub mod sys_role_handler;
 pub mod sys_role_permission_handler;
+pub mod sys_two_factor_handler;
 pub mod sys_user_handler;
+pub mod sys_user_permission_handler;
 pub mod sys_user_role_handler;
 pub mod sys_route_handler;
+
+// 系统管理接口的路由前缀
+pub const SYSTEM_MANAGE_SCOPE: &str = "/system-manage";
+
+// 注册系统管理下的所有接口
+pub fn system_manage_config(cfg: &mut web::ServiceConfig) {
+    sys_role_handler::api_config(cfg);
+    sys_menu_handler::api_config(cfg);
+    sys_permission_handler::api_config(cfg);
+    sys_user_role_handler::api_config(cfg);
+    sys_user_permission_handler::api_config(cfg);
+    sys_access_handler::api_config(cfg);
+    sys_role_permission_handler::api_config(cfg);
+    sys_user_handler::api_config(cfg);
+    sys_api_handler::api_config(cfg);
+    sys_login_log_handler::api_config(cfg);
+    sys_api_key_handler::api_config(cfg);
+    sys_dept_handler::api_config(cfg);
+}
+
+// 系统管理下所有接口的清单，路径带上 SYSTEM_MANAGE_SCOPE 前缀
+pub fn system_manage_routes() -> Vec<ApiRoute> {
+    [
+        sys_role_handler::api_routes(),
+        sys_menu_handler::api_routes(),
+        sys_permission_handler::api_routes(),
+        sys_user_role_handler::api_routes(),
+        sys_user_permission_handler::api_routes(),
+        sys_access_handler::api_routes(),
+        sys_role_permission_handler::api_routes(),
+        sys_user_handler::api_routes(),
+        sys_api_handler::api_routes(),
+        sys_login_log_handler::api_routes(),
+        sys_api_key_handler::api_routes(),
+        sys_dept_handler::api_routes(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|route| route.with_prefix(SYSTEM_MANAGE_SCOPE))
+    .collect()
+}
@@ -0,0 +1,53 @@
+use crate::common::error::MyError;
+use crate::common::resp::{ApiError, ApiResponse};
+use crate::config::globals;
+use crate::dto::admin::sys_access_dto::AccessExplainQueryDto;
+use crate::services::admin::sys_access_services;
+use crate::{api_routes, create_response};
+use actix_web::ResponseError;
+use actix_web::{web, HttpResponse, Responder};
+
+// 将授权解释服务的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        MyError::InvalidTypeError(msg) => ApiError::InvalidArgument(msg),
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
+// 获取用户可访问的全部菜单、按钮和接口，以及授予它们的角色和权限链路
+pub async fn get_effective_permissions(
+    app_state: web::Data<globals::AppState>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let result =
+        sys_access_services::get_effective_permissions(&app_state.mysql_conn, path.into_inner())
+            .await
+            .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+// 解释用户为什么不能访问某个接口或菜单
+pub async fn explain_access(
+    app_state: web::Data<globals::AppState>,
+    path: web::Path<i32>,
+    web::Query(query): web::Query<AccessExplainQueryDto>,
+) -> impl Responder {
+    let result = sys_access_services::explain_access(
+        &app_state.mysql_conn,
+        &app_state.redis_conn,
+        path.into_inner(),
+        query,
+    )
+    .await
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+api_routes! {
+    get_effective_permissions => GET "/users/{user_id}/effective-permissions",
+    explain_access => GET "/users/{user_id}/effective-permissions/why-not",
+}
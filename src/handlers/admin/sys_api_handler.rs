@@ -0,0 +1,189 @@
+use crate::common::auth::current_user::CurrentUser;
+use crate::common::error::MyError;
+use crate::common::resp::ApiError;
+use crate::common::resp::ApiResponse;
+use crate::config::globals;
+use crate::{api_routes, create_response};
+use crate::dto::admin::sys_api_dto::{
+    ApiCreateDto, ApiDeleteRespDto, ApiDto, ApiQueryDto, ApiUpdateDto, ApisDeleteRespDto,
+};
+use crate::handlers::admin::{system_manage_routes, SYSTEM_MANAGE_SCOPE};
+use crate::services::admin::sys_api_services;
+use actix_web::ResponseError;
+use actix_web::{web, HttpResponse, Responder};
+use validator::Validate;
+
+// 将接口服务的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        MyError::InvalidTypeError(msg) => ApiError::InvalidArgument(msg),
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
+// 创建接口
+pub async fn create_api(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    api_create_dto: web::Json<ApiCreateDto>,
+) -> impl Responder {
+    if let Err(errors) = api_create_dto.0.validate() {
+        return create_response!(Err::<ApiDto, ApiError>(ApiError::InvalidArgument(
+            errors.to_string()
+        )));
+    }
+
+    let result = sys_api_services::create_api(
+        &app_state.mysql_conn,
+        api_create_dto.into_inner(),
+        current_user.user_name,
+    )
+    .await
+    .map(ApiDto::from)
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+// 分页获取接口列表
+pub async fn get_apis(
+    app_state: web::Data<globals::AppState>,
+    web::Query(info): web::Query<ApiQueryDto>,
+) -> impl Responder {
+    let current = info.current.unwrap_or(1);
+    let size = info.size.unwrap_or(10);
+
+    let result = sys_api_services::get_paginated_apis(
+        &app_state.mysql_conn,
+        current,
+        size,
+        info.api_group,
+        info.api_method,
+    )
+    .await
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+// 获取单个接口
+pub async fn get_api_by_id(
+    app_state: web::Data<globals::AppState>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let api_id = path.into_inner();
+    let result = match sys_api_services::get_api_by_id(&app_state.mysql_conn, api_id).await {
+        Ok(Some(api)) => Ok(ApiDto::from(api)),
+        Ok(None) => Err(ApiError::NotFound(format!("Api with id {} not found", api_id))),
+        Err(error) => Err(to_api_error(error)),
+    };
+
+    create_response!(result)
+}
+
+// 更新接口
+pub async fn update_api(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    path: web::Path<i32>,
+    api_update_dto: web::Json<ApiUpdateDto>,
+) -> impl Responder {
+    if let Err(errors) = api_update_dto.0.validate() {
+        return create_response!(Err::<ApiDto, ApiError>(ApiError::InvalidArgument(
+            errors.to_string()
+        )));
+    }
+
+    let result = sys_api_services::update_api(
+        &app_state.mysql_conn,
+        path.into_inner(),
+        api_update_dto.into_inner(),
+        current_user.user_name,
+    )
+    .await
+    .map(ApiDto::from)
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+// 删除接口
+pub async fn delete_api(
+    app_state: web::Data<globals::AppState>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let result = match sys_api_services::delete_api(&app_state.mysql_conn, path.into_inner()).await
+    {
+        Ok(rows) if rows > 0 => Ok(ApiDeleteRespDto { success: true }),
+        Ok(_) => Err(ApiError::NotFound("Api not found".to_string())),
+        Err(error) => Err(to_api_error(error)),
+    };
+
+    create_response!(result)
+}
+
+// 批量删除接口
+pub async fn delete_apis(
+    app_state: web::Data<globals::AppState>,
+    api_ids: web::Json<Vec<i32>>,
+) -> impl Responder {
+    let api_ids = api_ids.into_inner();
+    let result = match sys_api_services::delete_apis(&app_state.mysql_conn, api_ids.clone()).await
+    {
+        Ok(rows) if rows > 0 => Ok(ApisDeleteRespDto {
+            deleted_api_ids: api_ids,
+        }),
+        Ok(_) => Err(ApiError::NotFound("Apis not found".to_string())),
+        Err(error) => Err(to_api_error(error)),
+    };
+
+    create_response!(result)
+}
+
+// 预览路由同步到 sys_api 的差异，不落库
+pub async fn get_api_sync_diff(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+) -> impl Responder {
+    let result = sys_api_services::sync_api_routes(
+        &app_state.mysql_conn,
+        system_manage_routes(),
+        SYSTEM_MANAGE_SCOPE,
+        true,
+        current_user.user_name,
+    )
+    .await
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+// 将已注册的路由同步到 sys_api
+pub async fn sync_apis(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+) -> impl Responder {
+    let result = sys_api_services::sync_api_routes(
+        &app_state.mysql_conn,
+        system_manage_routes(),
+        SYSTEM_MANAGE_SCOPE,
+        false,
+        current_user.user_name,
+    )
+    .await
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+api_routes! {
+    create_api => POST "/apis",
+    get_apis => GET "/apis",
+    get_api_sync_diff => GET "/apis/sync",
+    sync_apis => POST "/apis/sync",
+    get_api_by_id => GET "/apis/{id}",
+    update_api => PUT "/apis/{id}",
+    delete_apis => DELETE "/apis",
+    delete_api => DELETE "/apis/{id}",
+}
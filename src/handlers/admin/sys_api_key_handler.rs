@@ -0,0 +1,101 @@
+use crate::common::auth::api_key::ApiKeyScope;
+use crate::common::auth::current_user::CurrentUser;
+use crate::common::error::MyError;
+use crate::common::resp::{ApiError, ApiResponse};
+use crate::config::globals;
+use crate::dto::admin::sys_api_key_dto::{
+    ApiKeyCreateDto, ApiKeyCreateRespDto, ApiKeyQueryDto, ApiKeyRevokeRespDto,
+};
+use crate::services::admin::sys_api_key_services;
+use crate::{api_routes, create_response};
+use actix_web::ResponseError;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use validator::Validate;
+
+// 将 API Key 服务的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::BadRequestError(msg) | MyError::PermissionDeniedError(msg) => {
+            ApiError::InvalidArgument(msg)
+        }
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
+// 创建 API Key，完整的 Key 只在本次响应中返回
+pub async fn create_api_key(
+    app_state: web::Data<globals::AppState>,
+    req: HttpRequest,
+    current_user: CurrentUser,
+    create_dto: web::Json<ApiKeyCreateDto>,
+) -> impl Responder {
+    if let Err(errors) = create_dto.0.validate() {
+        return create_response!(Err::<ApiKeyCreateRespDto, ApiError>(
+            ApiError::InvalidArgument(errors.to_string())
+        ));
+    }
+    // 不允许用 API Key 再创建 API Key，避免泄露的 Key 自我续期
+    if req.extensions().get::<ApiKeyScope>().is_some() {
+        return create_response!(Err::<ApiKeyCreateRespDto, ApiError>(
+            ApiError::InvalidArgument("API keys cannot create API keys".to_string())
+        ));
+    }
+
+    let result = sys_api_key_services::create_api_key(
+        &app_state.mysql_conn,
+        current_user.user_id,
+        current_user.user_name,
+        &current_user.role_codes,
+        create_dto.into_inner(),
+    )
+    .await
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+// 分页获取当前用户的 API Key，不包含 Key 本身
+pub async fn get_api_keys(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    web::Query(query): web::Query<ApiKeyQueryDto>,
+) -> impl Responder {
+    let result = sys_api_key_services::get_paginated_api_keys(
+        &app_state.mysql_conn,
+        current_user.user_id,
+        query,
+    )
+    .await
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+// 吊销当前用户的 API Key
+pub async fn revoke_api_key(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let result = match sys_api_key_services::revoke_api_key(
+        &app_state.mysql_conn,
+        current_user.user_id,
+        path.into_inner(),
+        current_user.user_name,
+    )
+    .await
+    {
+        Ok(rows) if rows > 0 => Ok(ApiKeyRevokeRespDto { success: true }),
+        Ok(_) => Err(ApiError::NotFound("API key not found".to_string())),
+        Err(error) => Err(to_api_error(error)),
+    };
+
+    create_response!(result)
+}
+
+api_routes! {
+    create_api_key => POST "/api-keys",
+    get_api_keys => GET "/api-keys",
+    revoke_api_key => DELETE "/api-keys/{id}",
+}
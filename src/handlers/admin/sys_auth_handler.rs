@@ -1,77 +1,537 @@
-use crate::common::auth::crypto::verify_password;
-use crate::common::auth::jwt::generate_jwt;
+use crate::common::auth::crypto::{hash_password_with, needs_rehash, verify_password};
+use crate::common::auth::password_policy;
+use crate::common::auth::jwt::{generate_jwt, Claims};
 use crate::common::error::MyError;
 use crate::common::error::MyError::AuthError;
 use crate::common::resp::ApiError;
 use crate::common::resp::ApiResponse;
-use crate::dto::admin::sys_auth_dto::{SysLoginDto, SysLoginRespDto};
+use crate::common::auth::current_user::CurrentUser;
+use crate::common::client_ip::client_ip;
+use crate::dto::admin::sys_auth_dto::{
+    ChangePasswordDto, ForgotPasswordDto, LogoutDto, RefreshTokenDto, ResetPasswordDto,
+    SysLoginDto, SysLoginRespDto, SysLoginResultDto,
+};
+use crate::dto::admin::sys_two_factor_dto::TwoFactorChallengeDto;
+use crate::dto::admin::sys_login_log_dto::LoginClientDto;
 use crate::dto::admin::sys_user_dto::UserInfo;
+use crate::schemas::admin::sea_orm_active_enums::LoginResult;
 use crate::schemas::admin::sys_user;
-use crate::services::admin::sys_user_services;
+use crate::services::admin::sys_route_services::Status;
+use crate::services::admin::{
+    sys_auth_services, sys_login_log_services, sys_two_factor_services, sys_user_role_services,
+    sys_user_services,
+};
 use crate::{config, create_response};
 use actix_session::Session;
-use actix_web::HttpResponse;
 use actix_web::ResponseError;
 use actix_web::{get, post, web, Responder};
+use actix_web::http::header::USER_AGENT;
+use actix_web::{HttpMessage, HttpRequest, HttpResponse};
 use config::globals;
+use jsonwebtoken::TokenData;
+use log::{error, warn};
+use once_cell::sync::Lazy;
 use sea_orm::DatabaseConnection;
+use std::time::Duration;
 use validator::Validate;
 
+// 校验用户名密码，失败次数过多时逐步延迟响应并锁定用户名或 IP
+// 用户不存在时用于校验的哈希，使其与存在的用户耗时一致，避免通过响应时间探测用户名
+static DUMMY_PASSWORD_HASH: Lazy<String> = Lazy::new(|| {
+    hash_password_with("dummy-password", &globals::APP_CONFIG.password)
+        .expect("Failed to hash dummy password")
+});
+
 async fn authenticate_user(
-    conn: &DatabaseConnection,
+    app_state: &globals::AppState,
     sys_login_dto: &SysLoginDto,
+    client_ip: Option<&str>,
 ) -> Result<sys_user::Model, MyError> {
+    let user_name = sys_login_dto
+        .user_name
+        .clone()
+        .ok_or(AuthError("Invalid username or password".to_string()))?;
+
+    if let Some(ttl) =
+        sys_auth_services::get_login_lock_ttl(&app_state.redis_conn, &user_name, client_ip).await?
+    {
+        return Err(MyError::TooManyRequestsError(format!(
+            "Too many failed login attempts, try again in {} seconds",
+            ttl
+        )));
+    }
+
     let user_opt =
-        sys_user_services::find_user_by_username(&*conn, sys_login_dto.user_name.clone()).await?;
-    let user = user_opt.ok_or(AuthError("Invalid username or password".to_string()))?;
+        sys_user_services::find_user_by_username(&app_state.mysql_conn, Some(user_name.clone()))
+            .await?;
+    // 用户不存在时同样计入失败次数，避免通过响应差异探测用户名
+    let verified_user = match (user_opt, sys_login_dto.password.as_deref()) {
+        (Some(user), Some(password)) if verify_password(password, &user.password)? => Some(user),
+        (None, Some(password)) => {
+            let _ = verify_password(password, &DUMMY_PASSWORD_HASH);
+            None
+        }
+        _ => None,
+    };
+
+    if let Some(user) = verified_user {
+        // 密码正确才提示禁用，避免泄露账号状态；禁用的用户不升级密码哈希
+        if user.status != Status::Enable as i8 {
+            return Err(AuthError("User is disabled".to_string()));
+        }
+        if let Some(password) = sys_login_dto.password.as_deref() {
+            rehash_password_if_needed(app_state, &user, password).await;
+        }
+        return Ok(user);
+    }
+
+    let failure =
+        sys_auth_services::record_login_failure(&app_state.redis_conn, &user_name, client_ip)
+            .await?;
+    if failure.delay_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(failure.delay_ms)).await;
+    }
+    if failure.locked {
+        return Err(MyError::TooManyRequestsError(
+            "Too many failed login attempts, account is temporarily locked".to_string(),
+        ));
+    }
+    Err(AuthError("Invalid username or password".to_string()))
+}
 
-    if verify_password(&sys_login_dto.password.clone().unwrap(), &user.password).unwrap() {
-        Ok(user)
-    } else {
-        Err(AuthError("Invalid username or password".to_string()))
+// 历史的 bcrypt 哈希或参数已过时的哈希，在登录成功后按当前配置重新生成
+// 升级失败不影响登录
+async fn rehash_password_if_needed(
+    app_state: &globals::AppState,
+    user: &sys_user::Model,
+    password: &str,
+) {
+    let config = &globals::APP_CONFIG.password;
+    if !needs_rehash(&user.password, config) {
+        return;
     }
+    let rs = match hash_password_with(password, config) {
+        Ok(password_hash) => {
+            sys_user_services::update_password_hash(&app_state.mysql_conn, user.id, password_hash)
+                .await
+                .map_err(MyError::from)
+        }
+        Err(err) => Err(err),
+    };
+    if let Err(err) = rs {
+        error!("failed to rehash password of {}: {}", user.user_name, err);
+    }
+}
+
+// 将修改密码时的错误转换为响应错误
+fn to_password_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::ValidationError(msg) => ApiError::InvalidArgument(msg),
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
+// 根据用户当前启用的角色签发 access token
+async fn generate_user_jwt(
+    conn: &DatabaseConnection,
+    user_id: i32,
+    user_name: String,
+) -> Result<String, MyError> {
+    let role_codes = sys_user_role_services::get_role_codes_by_user_id(conn, user_id).await?;
+    generate_jwt(user_id, user_name, role_codes).map_err(|e| AuthError(e.to_string()))
+}
+
+// 签发 access token 和 refresh token
+pub(crate) async fn issue_tokens(
+    app_state: &globals::AppState,
+    user: &sys_user::Model,
+) -> Result<SysLoginRespDto, MyError> {
+    let token =
+        generate_user_jwt(&app_state.mysql_conn, user.id, user.user_name.clone()).await?;
+    let refresh_token = sys_auth_services::issue_refresh_token(
+        &app_state.redis_conn,
+        user.id,
+        user.user_name.clone(),
+    )
+    .await?;
+    Ok(SysLoginRespDto {
+        user_name: user.user_name.clone(),
+        token,
+        refresh_token,
+    })
+}
+
+// 需要两步验证时返回挑战 token，否则直接签发 token
+pub(crate) async fn start_session(
+    app_state: &globals::AppState,
+    user: &sys_user::Model,
+) -> Result<SysLoginResultDto, MyError> {
+    let two_factor_enabled = user.totp_enabled == 1;
+    if two_factor_enabled
+        || sys_two_factor_services::is_two_factor_required_by_role(&app_state.mysql_conn, user.id)
+            .await?
+    {
+        let challenge_token =
+            sys_two_factor_services::issue_challenge(&app_state.redis_conn, user.id).await?;
+        return Ok(SysLoginResultDto::Challenge(TwoFactorChallengeDto {
+            user_name: user.user_name.clone(),
+            challenge_token,
+            enrollment_required: !two_factor_enabled,
+        }));
+    }
+    issue_tokens(app_state, user)
+        .await
+        .map(SysLoginResultDto::Token)
+}
+
+// 请求的客户端 IP 和 User-Agent，只有配置信任代理时才读取代理头中的 IP
+pub(crate) fn login_client(req: &HttpRequest) -> LoginClientDto {
+    LoginClientDto {
+        ip: client_ip(
+            req.peer_addr(),
+            &req.connection_info(),
+            globals::APP_CONFIG.security.trust_proxy_headers,
+        )
+        .map(|ip| ip.to_string()),
+        user_agent: req
+            .headers()
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+    }
+}
+
+// 登录成功：清空密码的失败计数，更新最后登录时间并写入登录日志
+// 需要两步验证时在第二步通过后才调用，只通过密码不会重置失败计数
+pub(crate) async fn record_login_success(
+    app_state: &globals::AppState,
+    user: &sys_user::Model,
+    client: &LoginClientDto,
+) {
+    if let Err(err) =
+        sys_auth_services::clear_login_failures(&app_state.redis_conn, &user.user_name).await
+    {
+        error!("failed to clear login failures of {}: {}", user.user_name, err);
+    }
+    if let Err(err) = sys_user_services::update_last_login(&app_state.mysql_conn, user.id).await {
+        error!("failed to update last login of {}: {}", user.user_name, err);
+    }
+    record_login(app_state, &user.user_name, client, None).await;
 }
 
 // 用户登录
 #[post("/login")]
 pub async fn login(
     app_state: web::Data<globals::AppState>,
+    req: HttpRequest,
     sys_login_dto: web::Json<SysLoginDto>,
 ) -> impl Responder {
-    let rs: Result<SysLoginRespDto, ApiError>;
+    let client = login_client(&req);
+    let rs: Result<SysLoginResultDto, ApiError>;
     match sys_login_dto.0.validate() {
-        Ok(_) => match authenticate_user(&app_state.mysql_conn, &sys_login_dto).await {
-            Ok(user) => {
-                let token = generate_jwt(user.user_name.clone(), vec!["user".to_string()]).unwrap();
-                let resp_dto = SysLoginRespDto {
-                    user_name: user.user_name,
-                    token,
-                    refresh_token: "xxx".to_string(),
+        Ok(_) => {
+            let authenticated =
+                match authenticate_user(&app_state, &sys_login_dto, client.ip.as_deref()).await {
+                    Ok(user) => start_session(&app_state, &user).await.map(|resp| (user, resp)),
+                    Err(err) => Err(err),
                 };
-                rs = Ok(resp_dto);
+            let user_name = sys_login_dto.user_name.clone().unwrap_or_default();
+            match authenticated {
+                Ok((user, resp_dto)) => {
+                    // 需要两步验证时，登录成功在验证通过后记录
+                    if let SysLoginResultDto::Token(_) = resp_dto {
+                        record_login_success(&app_state, &user, &client).await;
+                    }
+                    rs = Ok(resp_dto)
+                }
+                Err(err) => {
+                    record_login(&app_state, &user_name, &client, Some(err.to_string())).await;
+                    rs = Err(match err {
+                        MyError::AuthError(_) => ApiError::Unauthorized(err.to_string()),
+                        MyError::TooManyRequestsError(msg) => ApiError::TooManyRequests(msg),
+                        err => ApiError::InternalServerError(err.to_string()),
+                    })
+                }
             }
-            Err(err) => rs = Err(ApiError::Unauthorized(err.to_string())),
-        },
+        }
         Err(errors) => rs = Err(ApiError::BadRequest(errors.to_string())),
     }
     create_response!(rs)
 }
 
+// 写入登录日志，写入失败不影响登录结果
+pub(crate) async fn record_login(
+    app_state: &globals::AppState,
+    user_name: &str,
+    client: &LoginClientDto,
+    failure_reason: Option<String>,
+) {
+    let result = match failure_reason {
+        Some(_) => LoginResult::Failure,
+        None => LoginResult::Success,
+    };
+    if let Err(err) = sys_login_log_services::record_login_log(
+        &app_state.mysql_conn,
+        user_name,
+        client,
+        result,
+        failure_reason,
+    )
+    .await
+    {
+        error!("failed to record login log of {}: {}", user_name, err);
+    }
+}
+
+// 使用 refresh token 换取新的 token，旧的 refresh token 随即失效
+#[post("/refresh-token")]
+pub async fn refresh_token_handler(
+    app_state: web::Data<globals::AppState>,
+    refresh_token_dto: web::Json<RefreshTokenDto>,
+) -> impl Responder {
+    if let Err(errors) = refresh_token_dto.0.validate() {
+        return create_response!(Err::<SysLoginRespDto, ApiError>(ApiError::BadRequest(
+            errors.to_string()
+        )));
+    }
+
+    let rs = match sys_auth_services::rotate_refresh_token(
+        &app_state.redis_conn,
+        &refresh_token_dto.refresh_token,
+    )
+    .await
+    {
+        Ok((info, refresh_token)) => {
+            match sys_user_services::is_user_enabled(&app_state.mysql_conn, info.user_id).await {
+                Ok(true) => generate_user_jwt(
+                    &app_state.mysql_conn,
+                    info.user_id,
+                    info.user_name.clone(),
+                )
+                .await
+                .map(|token| SysLoginRespDto {
+                    user_name: info.user_name,
+                    token,
+                    refresh_token,
+                })
+                .map_err(|e| ApiError::InternalServerError(e.to_string())),
+                Ok(false) => {
+                    // 用户已被禁用，吊销该 refresh token 所在的 family
+                    let _ = sys_auth_services::revoke_token_family(
+                        &app_state.redis_conn,
+                        &info.family_id,
+                    )
+                    .await;
+                    Err(ApiError::Unauthorized("User is disabled".to_string()))
+                }
+                Err(e) => Err(ApiError::InternalServerError(e.to_string())),
+            }
+        }
+        Err(err) => Err(ApiError::Unauthorized(err.to_string())),
+    };
+    create_response!(rs)
+}
+
+// 用户登出：吊销当前 access token，以及请求中携带的 refresh token
+#[post("/logout")]
+pub async fn logout(
+    app_state: web::Data<globals::AppState>,
+    req: HttpRequest,
+    logout_dto: Option<web::Json<LogoutDto>>,
+) -> impl Responder {
+    let claims = match req.extensions().get::<TokenData<Claims>>() {
+        Some(token_data) => token_data.claims.clone(),
+        None => {
+            return create_response!(Err::<bool, ApiError>(ApiError::Unauthorized(
+                "Token missing".to_string()
+            )))
+        }
+    };
+
+    if let Err(err) = sys_auth_services::revoke_access_token(&app_state.redis_conn, &claims).await {
+        return create_response!(Err::<bool, ApiError>(ApiError::InternalServerError(
+            err.to_string()
+        )));
+    }
+
+    let rs: Result<bool, ApiError> = match logout_dto.and_then(|dto| dto.into_inner().refresh_token) {
+        Some(refresh_token) => {
+            sys_auth_services::revoke_refresh_token(&app_state.redis_conn, &refresh_token)
+                .await
+                .map(|_| true)
+                .map_err(|err| ApiError::InternalServerError(err.to_string()))
+        }
+        None => Ok(true),
+    };
+    create_response!(rs)
+}
+
+// 修改自己的密码，需要校验旧密码，修改后已签发的 token 全部失效
+#[post("/change-password")]
+pub async fn change_password(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    change_password_dto: web::Json<ChangePasswordDto>,
+) -> impl Responder {
+    if let Err(errors) = change_password_dto.0.validate() {
+        return create_response!(Err::<bool, ApiError>(ApiError::BadRequest(
+            errors.to_string()
+        )));
+    }
+    let dto = change_password_dto.into_inner();
+
+    let user = match sys_user_services::get_user_by_id_pure(&app_state.mysql_conn, current_user.user_id)
+        .await
+    {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return create_response!(Err::<bool, ApiError>(ApiError::NotFound(
+                "User not found".to_string()
+            )))
+        }
+        Err(err) => {
+            return create_response!(Err::<bool, ApiError>(ApiError::InternalServerError(
+                err.to_string()
+            )))
+        }
+    };
+    match verify_password(&dto.old_password, &user.password) {
+        Ok(true) => {}
+        Ok(false) => {
+            return create_response!(Err::<bool, ApiError>(ApiError::InvalidArgument(
+                "Old password is incorrect".to_string()
+            )))
+        }
+        Err(err) => {
+            return create_response!(Err::<bool, ApiError>(ApiError::InternalServerError(
+                err.to_string()
+            )))
+        }
+    }
+
+    let rs = update_password_and_revoke(&app_state, user.id, dto.new_password, user.user_name)
+        .await
+        .map_err(to_password_api_error);
+    create_response!(rs)
+}
+
+// 忘记密码：向用户邮箱发送重置链接，同一邮箱在冷却时间内只发送一封
+// 无论用户是否存在、是否在冷却中都返回成功，避免通过响应差异探测用户名
+#[post("/forgot-password")]
+pub async fn forgot_password(
+    app_state: web::Data<globals::AppState>,
+    forgot_password_dto: web::Json<ForgotPasswordDto>,
+) -> impl Responder {
+    if let Err(errors) = forgot_password_dto.0.validate() {
+        return create_response!(Err::<bool, ApiError>(ApiError::BadRequest(
+            errors.to_string()
+        )));
+    }
+
+    let user_name = forgot_password_dto.into_inner().user_name;
+    match sys_user_services::find_user_by_username(&app_state.mysql_conn, Some(user_name.clone()))
+        .await
+    {
+        Ok(Some(user)) if user.status == Status::Enable as i8 => {
+            match sys_auth_services::acquire_password_reset_cooldown(
+                &app_state.redis_conn,
+                &user.email,
+            )
+            .await
+            {
+                Ok(true) => {
+                    if let Err(err) = sys_auth_services::send_password_reset_mail(
+                        &app_state.redis_conn,
+                        app_state.mail_sender.as_ref(),
+                        &user,
+                    )
+                    .await
+                    {
+                        error!("failed to send password reset mail to {}: {}", user_name, err);
+                    }
+                }
+                Ok(false) => warn!("password reset mail of {} is cooling down", user_name),
+                Err(err) => error!(
+                    "failed to check password reset cooldown of {}: {}",
+                    user_name, err
+                ),
+            }
+        }
+        Ok(_) => {}
+        Err(err) => error!("failed to find user {}: {}", user_name, err),
+    }
+    create_response!(Ok::<bool, ApiError>(true))
+}
+
+// 使用重置链接中的 token 设置新密码，修改后已签发的 token 全部失效
+#[post("/reset-password")]
+pub async fn reset_password(
+    app_state: web::Data<globals::AppState>,
+    reset_password_dto: web::Json<ResetPasswordDto>,
+) -> impl Responder {
+    if let Err(errors) = reset_password_dto.0.validate() {
+        return create_response!(Err::<bool, ApiError>(ApiError::BadRequest(
+            errors.to_string()
+        )));
+    }
+    let dto = reset_password_dto.into_inner();
+
+    let rs = reset_password_with_token(&app_state, &dto.token, dto.new_password)
+        .await
+        .map_err(|err| match err {
+            MyError::AuthError(_) => ApiError::InvalidArgument(err.to_string()),
+            err => to_password_api_error(err),
+        });
+    create_response!(rs)
+}
+
+// 新密码不符合策略时不消耗 token，用户可以换一个密码重试
+async fn reset_password_with_token(
+    app_state: &globals::AppState,
+    token: &str,
+    password: String,
+) -> Result<bool, MyError> {
+    let user_id = sys_auth_services::get_password_reset_user_id(&app_state.redis_conn, token).await?;
+    let user = sys_user_services::get_user_by_id_pure(&app_state.mysql_conn, user_id)
+        .await?
+        .ok_or(MyError::NotFound("User not found".to_string()))?;
+    password_policy::check_password(&password, &user.user_name, &globals::APP_CONFIG.password)?;
+
+    sys_auth_services::consume_password_reset_token(&app_state.redis_conn, token).await?;
+    update_password_and_revoke(app_state, user.id, password, user.user_name).await
+}
+
+// 更新密码并吊销该用户已签发的全部 token
+async fn update_password_and_revoke(
+    app_state: &globals::AppState,
+    user_id: i32,
+    password: String,
+    user_name: String,
+) -> Result<bool, MyError> {
+    sys_user_services::update_password(&app_state.mysql_conn, user_id, password, user_name.clone())
+        .await?;
+    sys_auth_services::revoke_user_tokens(&app_state.redis_conn, user_id).await?;
+    Ok(true)
+}
+
 #[get("/user-info")]
-async fn user_info() -> impl Responder {
-    // 创建User结构体实例并插入到HashMap中
-    let user1 = UserInfo {
-        user_id: "0".to_string(),
-        user_name: "Soybean".to_string(),
-        buttons: vec![
-            "B_CODE1".to_string(),
-            "B_CODE2".to_string(),
-            "B_CODE3".to_string(),
-        ],
-        roles: vec!["R_SUPER".to_string()],
+async fn user_info(app_state: web::Data<globals::AppState>, req: HttpRequest) -> impl Responder {
+    let user_id = match req.extensions().get::<TokenData<Claims>>() {
+        Some(token_data) => token_data.claims.user_id,
+        None => {
+            return create_response!(Err::<UserInfo, ApiError>(ApiError::Unauthorized(
+                "Token missing".to_string()
+            )))
+        }
     };
 
-    let rs: Result<UserInfo, ApiError> = Ok(user1);
+    let rs: Result<UserInfo, ApiError> =
+        match sys_user_services::get_user_info(&app_state.mysql_conn, user_id).await {
+            Ok(user_info) => Ok(user_info),
+            Err(MyError::NotFound(msg)) => Err(ApiError::NotFound(msg)),
+            Err(err) => Err(ApiError::InternalServerError(err.to_string())),
+        };
 
     create_response!(rs)
 }
@@ -108,6 +568,11 @@ async fn index(session: Session) -> impl Responder {
 
 pub fn api_config(cfg: &mut web::ServiceConfig) {
     cfg.service(login)
+        .service(refresh_token_handler)
+        .service(logout)
+        .service(change_password)
+        .service(forgot_password)
+        .service(reset_password)
         .service(index)
         .service(user_info)
         .service(health_checker_handler);
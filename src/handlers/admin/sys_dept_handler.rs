@@ -0,0 +1,143 @@
+use crate::common::auth::current_user::CurrentUser;
+use crate::common::error::MyError;
+use crate::common::resp::{ApiError, ApiResponse};
+use crate::config::globals;
+use crate::dto::admin::sys_dept_dto::{
+    DeptCreateDto, DeptDeleteRespDto, DeptMoveDto, DeptRespDto, DeptUpdateDto,
+};
+use crate::services::admin::sys_dept_services;
+use crate::{api_routes, create_response};
+use actix_web::ResponseError;
+use actix_web::{web, HttpResponse, Responder};
+use validator::Validate;
+
+// 将部门服务的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        MyError::BadRequestError(msg) => ApiError::InvalidArgument(msg),
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
+pub async fn create_dept(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    dept_create_dto: web::Json<DeptCreateDto>,
+) -> impl Responder {
+    if let Err(errors) = dept_create_dto.validate() {
+        return create_response!(Err::<DeptRespDto, ApiError>(ApiError::InvalidArgument(
+            errors.to_string()
+        )));
+    }
+    let result = sys_dept_services::create_dept(
+        &app_state.mysql_conn,
+        dept_create_dto.into_inner(),
+        current_user.user_name,
+    )
+    .await
+    .map(DeptRespDto::from)
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+pub async fn get_depts(app_state: web::Data<globals::AppState>) -> impl Responder {
+    let result = sys_dept_services::get_depts(&app_state.mysql_conn)
+        .await
+        .map(|depts| depts.into_iter().map(DeptRespDto::from).collect::<Vec<_>>())
+        .map_err(|error| ApiError::InternalServerError(error.to_string()));
+
+    create_response!(result)
+}
+
+pub async fn get_depts_tree(app_state: web::Data<globals::AppState>) -> impl Responder {
+    let result = sys_dept_services::get_dept_tree(&app_state.mysql_conn)
+        .await
+        .map_err(|error| ApiError::InternalServerError(error.to_string()));
+
+    create_response!(result)
+}
+
+pub async fn get_dept_by_id(
+    app_state: web::Data<globals::AppState>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let dept_id = path.into_inner();
+    let result = match sys_dept_services::get_dept_by_id(&app_state.mysql_conn, dept_id).await {
+        Ok(Some(dept)) => Ok(DeptRespDto::from(dept)),
+        Ok(None) => Err(ApiError::NotFound(format!(
+            "Department with id {} not found",
+            dept_id
+        ))),
+        Err(error) => Err(ApiError::InternalServerError(error.to_string())),
+    };
+
+    create_response!(result)
+}
+
+pub async fn update_dept(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    path: web::Path<i32>,
+    dept_update_dto: web::Json<DeptUpdateDto>,
+) -> impl Responder {
+    if let Err(errors) = dept_update_dto.validate() {
+        return create_response!(Err::<DeptRespDto, ApiError>(ApiError::InvalidArgument(
+            errors.to_string()
+        )));
+    }
+    let result = sys_dept_services::update_dept(
+        &app_state.mysql_conn,
+        path.into_inner(),
+        dept_update_dto.into_inner(),
+        current_user.user_name,
+    )
+    .await
+    .map(DeptRespDto::from)
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+// 移动部门到新的上级部门下
+pub async fn move_dept(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    path: web::Path<i32>,
+    dept_move_dto: web::Json<DeptMoveDto>,
+) -> impl Responder {
+    let result = sys_dept_services::move_dept(
+        &app_state.mysql_conn,
+        path.into_inner(),
+        dept_move_dto.into_inner(),
+        current_user.user_name,
+    )
+    .await
+    .map(DeptRespDto::from)
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+pub async fn delete_dept(
+    app_state: web::Data<globals::AppState>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let result = sys_dept_services::delete_dept(&app_state.mysql_conn, path.into_inner())
+        .await
+        .map(|rows| DeptDeleteRespDto { success: rows != 0 })
+        .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+api_routes! {
+    create_dept => POST "/depts",
+    get_depts => GET "/depts",
+    get_depts_tree => GET "/depts/tree",
+    get_dept_by_id => GET "/depts/{id}",
+    update_dept => PUT "/depts/{id}",
+    move_dept => PUT "/depts/{id}/move",
+    delete_dept => DELETE "/depts/{id}",
+}
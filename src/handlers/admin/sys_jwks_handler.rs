@@ -0,0 +1,14 @@
+use crate::common::auth::jwt::JWT_KEYS;
+use actix_web::{get, web, HttpResponse, Responder};
+
+// 发布验签公钥，其他服务据此校验本服务签发的 token
+#[get("/jwks.json")]
+pub async fn get_jwks() -> impl Responder {
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "public, max-age=300"))
+        .json(JWT_KEYS.jwks())
+}
+
+pub fn api_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_jwks);
+}
@@ -0,0 +1,29 @@
+use crate::common::error::MyError;
+use crate::common::resp::{ApiError, ApiResponse};
+use crate::config::globals;
+use crate::dto::admin::sys_login_log_dto::LoginLogQueryDto;
+use crate::services::admin::sys_login_log_services;
+use crate::{api_routes, create_response};
+use actix_web::ResponseError;
+use actix_web::{web, HttpResponse, Responder};
+
+// 分页获取登录日志
+pub async fn get_login_logs(
+    app_state: web::Data<globals::AppState>,
+    web::Query(query): web::Query<LoginLogQueryDto>,
+) -> impl Responder {
+    let result = sys_login_log_services::get_paginated_login_logs(&app_state.mysql_conn, query)
+        .await
+        .map_err(|error| match error {
+            MyError::BadRequestError(msg) | MyError::InvalidTypeError(msg) => {
+                ApiError::InvalidArgument(msg)
+            }
+            error => ApiError::InternalServerError(error.to_string()),
+        });
+
+    create_response!(result)
+}
+
+api_routes! {
+    get_login_logs => GET "/login-logs",
+}
@@ -1,20 +1,33 @@
+use crate::common::auth::current_user::CurrentUser;
+use crate::common::error::MyError;
 use crate::common::resp::{ApiError, ApiResponse};
 use crate::config::globals;
-use crate::create_response;
+use crate::{api_routes, create_response};
 use crate::dto::admin::common_dto::{PaginationQueryDto, PaginationResponseDto};
 use crate::dto::admin::sys_menu_dto::{
-    MenuBaseRespDto, MenuCreateDto, MenuCreationResponseDto, MenuDeleteResponseDto, MenuUpdateDto,
+    MenuCreateDto, MenuCreationResponseDto, MenuDeleteResponseDto, MenuUpdateDto,
     MenuUpdateResponseDto,
 };
 use crate::services::admin::sys_menu_services;
 use crate::services::admin::sys_menu_services::{build_menu_tree};
 use actix_web::ResponseError;
-use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse, Responder};
 use validator::Validate;
 
-#[post("/menus")]
+// 将菜单服务的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        MyError::BadRequestError(msg) | MyError::InvalidTypeError(msg) => {
+            ApiError::InvalidArgument(msg)
+        }
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
 pub async fn create_menu(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     menu_create_dto: web::Json<MenuCreateDto>,
 ) -> impl Responder {
     if let Err(errors) = menu_create_dto.0.validate() {
@@ -22,19 +35,22 @@ pub async fn create_menu(
             ApiError::InvalidArgument(errors.to_string())
         ));
     }
-    let result = sys_menu_services::create_menu(
-        &*app_state.mysql_conn,
+    let result = match sys_menu_services::create_menu(
+        &app_state.mysql_conn,
         menu_create_dto.into_inner(),
-        "admin".to_string(),
+        current_user.user_name,
     )
     .await
-    .map(|menu| MenuBaseRespDto::from(menu))
-    .map_err(|error| ApiError::InternalServerError(error.to_string()));
+    {
+        Ok(menu) => sys_menu_services::to_menu_resp_dto(&app_state.mysql_conn, menu)
+            .await
+            .map_err(|error| ApiError::InternalServerError(error.to_string())),
+        Err(error) => Err(to_api_error(error)),
+    };
 
     create_response!(result)
 }
 
-#[get("/menus/tree")]
 pub async fn get_menus_tree(app_state: web::Data<globals::AppState>) -> impl Responder {
     let result = sys_menu_services::get_menus(&*app_state.mysql_conn)
         .await
@@ -44,7 +60,6 @@ pub async fn get_menus_tree(app_state: web::Data<globals::AppState>) -> impl Res
     create_response!(result)
 }
 
-#[get("/menus/paged")]
 pub async fn get_menus_paged(
     app_state: web::Data<globals::AppState>,
     web::Query(info): web::Query<PaginationQueryDto>,
@@ -52,60 +67,47 @@ pub async fn get_menus_paged(
     let current = info.current.unwrap_or(1);
     let page_size = info.size.unwrap_or(10);
 
-    let result = sys_menu_services::get_menus_paged(&*app_state.mysql_conn, current, page_size)
-        .await
-        .map(|(menus, total_menus)| {
-            PaginationResponseDto::new(
-                current,
-                page_size,
-                total_menus,
-                menus
-                    .into_iter()
-                    .map(|menu| MenuBaseRespDto::from(menu))
-                    .collect::<Vec<MenuBaseRespDto>>(),
-            )
-        })
-        .map_err(|error| ApiError::InternalServerError(error.to_string()));
+    let result = match sys_menu_services::get_menus_paged(&app_state.mysql_conn, current, page_size).await {
+        Ok((menus, total_menus)) => sys_menu_services::to_menu_resp_dtos(&app_state.mysql_conn, menus)
+            .await
+            .map(|menus| PaginationResponseDto::new(current, page_size, total_menus, menus))
+            .map_err(|error| ApiError::InternalServerError(error.to_string())),
+        Err(error) => Err(to_api_error(error)),
+    };
     create_response!(result)
 }
 
-#[get("/menus")]
 pub async fn get_menus(app_state: web::Data<globals::AppState>) -> impl Responder {
-    let result = sys_menu_services::get_menus(&*app_state.mysql_conn)
-        .await
-        .map(|menus| {
-            PaginationResponseDto::new(
-                1,
-                10,
-                menus.len() as u64,
-                menus
-                    .into_iter()
-                    .map(|menu| MenuBaseRespDto::from(menu))
-                    .collect::<Vec<MenuBaseRespDto>>(),
-            )
-        })
-        .map_err(|error| ApiError::InternalServerError(error.to_string()));
+    let result = match sys_menu_services::get_menus(&app_state.mysql_conn).await {
+        Ok(menus) => sys_menu_services::to_menu_resp_dtos(&app_state.mysql_conn, menus)
+            .await
+            .map(|menus| PaginationResponseDto::new(1, 10, menus.len() as u64, menus)),
+        Err(error) => Err(error),
+    }
+    .map_err(|error| ApiError::InternalServerError(error.to_string()));
 
     create_response!(result)
 }
 
-#[get("/menus/{id}")]
 pub async fn get_menu_by_id(
     app_state: web::Data<globals::AppState>,
     path: web::Path<i32>,
 ) -> impl Responder {
     let menu_id = path.into_inner();
-    let result = sys_menu_services::get_menu_by_id(&*app_state.mysql_conn, menu_id)
-        .await
-        .map(|menu| MenuBaseRespDto::from(menu.unwrap()))
-        .map_err(|error| ApiError::NotFound(error.to_string()));
+    let result = match sys_menu_services::get_menu_by_id(&app_state.mysql_conn, menu_id).await {
+        Ok(Some(menu)) => sys_menu_services::to_menu_resp_dto(&app_state.mysql_conn, menu)
+            .await
+            .map_err(|error| ApiError::InternalServerError(error.to_string())),
+        Ok(None) => Err(ApiError::NotFound(format!("Menu with id {} not found", menu_id))),
+        Err(error) => Err(ApiError::InternalServerError(error.to_string())),
+    };
 
     create_response!(result)
 }
 
-#[put("/menus/{id}")]
 pub async fn update_menu(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     path: web::Path<i32>,
     menu_update_dto: web::Json<MenuUpdateDto>,
 ) -> impl Responder {
@@ -116,19 +118,24 @@ pub async fn update_menu(
         ));
     }
 
-    let result = sys_menu_services::update_menu(
-        &*app_state.mysql_conn,
+    let result = match sys_menu_services::update_menu(
+        &app_state.mysql_conn,
         menu_id,
         menu_update_dto.into_inner(),
+        current_user.user_name,
     )
     .await
-    .map(|menu| MenuBaseRespDto::from(menu.unwrap()))
-    .map_err(|error| ApiError::InternalServerError(error.to_string()));
+    {
+        Ok(Some(menu)) => sys_menu_services::to_menu_resp_dto(&app_state.mysql_conn, menu)
+            .await
+            .map_err(|error| ApiError::InternalServerError(error.to_string())),
+        Ok(None) => Err(ApiError::NotFound(format!("Menu with id {} not found", menu_id))),
+        Err(error) => Err(to_api_error(error)),
+    };
 
     create_response!(result)
 }
 
-#[delete("/menus/{id}")]
 pub async fn delete_menu(
     app_state: web::Data<globals::AppState>,
     path: web::Path<i32>,
@@ -144,7 +151,6 @@ pub async fn delete_menu(
     create_response!(result)
 }
 
-#[delete("/menus")]
 pub async fn delete_menus(
     app_state: web::Data<globals::AppState>,
     menu_ids: web::Json<Vec<i32>>,
@@ -161,13 +167,13 @@ pub async fn delete_menus(
 }
 
 
-pub fn api_config(cfg: &mut web::ServiceConfig) {
-    cfg.service(create_menu)
-        .service(get_menus)
-        .service(get_menus_paged)
-        .service(get_menus_tree)
-        .service(get_menu_by_id)
-        .service(update_menu)
-        .service(delete_menu)
-        .service(delete_menus);
+api_routes! {
+    create_menu => POST "/menus",
+    get_menus => GET "/menus",
+    get_menus_paged => GET "/menus/paged",
+    get_menus_tree => GET "/menus/tree",
+    get_menu_by_id => GET "/menus/{id}",
+    update_menu => PUT "/menus/{id}",
+    delete_menu => DELETE "/menus/{id}",
+    delete_menus => DELETE "/menus",
 }
@@ -0,0 +1,117 @@
+use crate::common::error::MyError;
+use crate::common::resp::{ApiError, ApiResponse};
+use crate::config::globals;
+use crate::create_response;
+use crate::dto::admin::sys_auth_dto::SysLoginResultDto;
+use crate::dto::admin::sys_oidc_dto::{OidcAuthorizeRespDto, OidcCallbackDto, OidcProviderDto};
+use crate::handlers::admin::sys_auth_handler::{
+    login_client, record_login, record_login_success, start_session,
+};
+use crate::schemas::admin::sys_user;
+use crate::services::admin::sys_oidc_services;
+use crate::services::admin::sys_route_services::Status;
+use actix_web::ResponseError;
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
+use validator::Validate;
+
+// 将单点登录服务的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::AuthError(msg) => ApiError::Unauthorized(msg),
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
+// 完成回调并校验用户状态
+async fn authenticate_callback(
+    app_state: &globals::AppState,
+    provider: &str,
+    callback_dto: &OidcCallbackDto,
+) -> Result<sys_user::Model, MyError> {
+    let provider = sys_oidc_services::find_provider(provider)?;
+    let user = sys_oidc_services::complete_login(
+        &app_state.mysql_conn,
+        &app_state.redis_conn,
+        provider,
+        &callback_dto.code,
+        &callback_dto.state,
+    )
+    .await?;
+    if user.status != Status::Enable as i8 {
+        return Err(MyError::AuthError("User is disabled".to_string()));
+    }
+    Ok(user)
+}
+
+// 列出可用于单点登录的身份提供方
+#[get("/oidc/providers")]
+pub async fn get_providers() -> impl Responder {
+    create_response!(Ok::<Vec<OidcProviderDto>, ApiError>(
+        sys_oidc_services::list_providers()
+    ))
+}
+
+// 发起单点登录，返回身份提供方的授权地址
+#[get("/oidc/{provider}/login")]
+pub async fn oidc_login(
+    app_state: web::Data<globals::AppState>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let rs = match sys_oidc_services::find_provider(&path.into_inner()) {
+        Ok(provider) => sys_oidc_services::start_login(&app_state.redis_conn, provider)
+            .await
+            .map_err(to_api_error),
+        Err(err) => Err::<OidcAuthorizeRespDto, ApiError>(to_api_error(err)),
+    };
+    create_response!(rs)
+}
+
+// 身份提供方回调后用授权码换取登录 token，用户需要两步验证时返回挑战 token
+#[post("/oidc/{provider}/callback")]
+pub async fn oidc_callback(
+    app_state: web::Data<globals::AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    callback_dto: web::Json<OidcCallbackDto>,
+) -> impl Responder {
+    if let Err(errors) = callback_dto.0.validate() {
+        return create_response!(Err::<SysLoginResultDto, ApiError>(ApiError::BadRequest(
+            errors.to_string()
+        )));
+    }
+    let provider = path.into_inner();
+    let client = login_client(&req);
+
+    let authenticated = match authenticate_callback(&app_state, &provider, &callback_dto).await {
+        Ok(user) => match start_session(&app_state, &user).await {
+            Ok(resp_dto) => Ok((user, resp_dto)),
+            Err(err) => Err((Some(user), err)),
+        },
+        Err(err) => Err((None, err)),
+    };
+    let rs = match authenticated {
+        Ok((user, resp_dto)) => {
+            // 需要两步验证时，登录成功在验证通过后记录
+            if let SysLoginResultDto::Token(_) = resp_dto {
+                record_login_success(&app_state, &user, &client).await;
+            }
+            Ok(resp_dto)
+        }
+        Err((user, err)) => {
+            // 回调失败时还不知道本地用户，按提供方记录
+            let user_name = user
+                .map(|user| user.user_name)
+                .unwrap_or_else(|| format!("oidc:{}", provider));
+            record_login(&app_state, &user_name, &client, Some(err.to_string())).await;
+            Err(to_api_error(err))
+        }
+    };
+    create_response!(rs)
+}
+
+pub fn api_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_providers)
+        .service(oidc_login)
+        .service(oidc_callback);
+}
@@ -1,18 +1,29 @@
+use crate::common::auth::current_user::CurrentUser;
+use crate::common::error::MyError;
 use crate::common::resp::ApiError;
 use crate::common::resp::ApiResponse;
 use crate::config::globals;
-use crate::create_response;
+use crate::{api_routes, create_response};
 use crate::dto::admin::common_dto::{PaginationQueryDto, PaginationResponseDto};
 use crate::dto::admin::sys_permission_dto::{PermissionCreationDto, PermissionCreationRespDto, PermissionDeleteRespDto, PermissionDto, PermissionMenuIdsRespDto, PermissionRespDto, PermissionSimpleRespDto, PermissionUpdateDto, PermissionUpdateRespDto};
 use crate::services::admin::sys_permission_services;
 use actix_web::ResponseError;
-use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse, Responder};
 use validator::Validate;
 
+// 将权限服务的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        MyError::InvalidTypeError(msg) => ApiError::InvalidArgument(msg),
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
 // Create a new permission
-#[post("/permissions")]
 async fn create_permission(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     permission_dto: web::Json<PermissionCreationDto>,
 ) -> impl Responder {
     if let Err(errors) = permission_dto.0.validate() {
@@ -24,19 +35,18 @@ async fn create_permission(
     let result = sys_permission_services::create_permission(
         &*app_state.mysql_conn,
         permission_req_data,
-        "admin".to_string(),
+        current_user.user_name,
     )
     .await
     .map(|permission| PermissionCreationRespDto {
         base: PermissionDto::from(permission),
     })
-    .map_err(|error| ApiError::InternalServerError(error.to_string()));
+    .map_err(to_api_error);
 
     create_response!(result)
 }
 
 // Get all permissions
-#[get("/permissions")]
 async fn get_permissions(
     app_state: web::Data<globals::AppState>,
     web::Query(info): web::Query<PaginationQueryDto>,
@@ -60,7 +70,6 @@ async fn get_permissions(
 }
 
 // Get a single permission by ID
-#[get("/permissions/{id}")]
 async fn get_permission_by_id(
     app_state: web::Data<globals::AppState>,
     path: web::Path<i32>,
@@ -93,7 +102,6 @@ async fn get_permission_by_id(
     create_response!(result)
 }
 
-#[get("/permissions/simple")]
 async fn get_simple_permission(app_state: web::Data<globals::AppState>) -> impl Responder {
     let result = sys_permission_services::get_permissions(&*app_state.mysql_conn)
         .await
@@ -105,6 +113,7 @@ async fn get_simple_permission(app_state: web::Data<globals::AppState>) -> impl
                     permission_name: permission.permission_name.clone(),
                     permission_code: permission.permission_code.clone(),
                     status: permission.status.to_string(),
+                    effect: permission.effect.as_str().to_string(),
                 })
                 .collect::<Vec<_>>()
         })
@@ -113,9 +122,9 @@ async fn get_simple_permission(app_state: web::Data<globals::AppState>) -> impl
 }
 
 // Update a permission
-#[put("/permissions/{id}")]
 async fn update_permission(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     path: web::Path<i32>,
     permission_dto: web::Json<PermissionUpdateDto>,
 ) -> impl Responder {
@@ -125,19 +134,18 @@ async fn update_permission(
         &*app_state.mysql_conn,
         permission_id,
         permission_update_req_data.base,
-        "admin".to_string(),
+        current_user.user_name,
     )
     .await
     .map(|_| PermissionUpdateRespDto {
         base: None,
     })
-    .map_err(|error| ApiError::InternalServerError(error.to_string()));
+    .map_err(to_api_error);
 
     create_response!(result)
 }
 
 // Delete a permission
-#[delete("/permissions/{id}")]
 async fn delete_permission(
     app_state: web::Data<globals::AppState>,
     path: web::Path<i32>,
@@ -153,7 +161,6 @@ async fn delete_permission(
     create_response!(result)
 }
 
-#[get("/permissions/menus/{code}")]
 async fn get_user_menu_ids(
     app_state: web::Data<globals::AppState>,
     permission_code: web::Path<String>,
@@ -167,12 +174,12 @@ async fn get_user_menu_ids(
     create_response!(result)
 }
 
-pub fn api_config(cfg: &mut web::ServiceConfig) {
-    cfg.service(create_permission)
-        .service(get_permissions)
-        .service(get_simple_permission)
-        .service(get_permission_by_id)
-        .service(get_user_menu_ids)
-        .service(update_permission)
-        .service(delete_permission);
+api_routes! {
+    create_permission => POST "/permissions",
+    get_permissions => GET "/permissions",
+    get_simple_permission => GET "/permissions/simple",
+    get_permission_by_id => GET "/permissions/{id}",
+    get_user_menu_ids => GET "/permissions/menus/{code}",
+    update_permission => PUT "/permissions/{id}",
+    delete_permission => DELETE "/permissions/{id}",
 }
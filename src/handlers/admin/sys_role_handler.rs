@@ -1,19 +1,32 @@
+use crate::common::auth::current_user::CurrentUser;
 use crate::common::resp::ApiError;
 use crate::common::resp::ApiResponse;
+use crate::common::error::MyError;
 use crate::config::globals;
-use crate::create_response;
+use crate::{api_routes, create_response};
 use crate::dto::admin::common_dto::PaginationQueryDto;
 use crate::dto::admin::sys_role_dto;
 use crate::dto::admin::sys_role_dto::{RoleDeleteRespDto, RolesDeleteRespDto};
 use crate::services::admin::sys_role_services;
 use actix_web::ResponseError;
-use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse, Responder};
 use validator::Validate;
 
+// 将创建、更新角色时的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::BadRequestError(msg) | MyError::InvalidTypeError(msg) => {
+            ApiError::InvalidArgument(msg)
+        }
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
 // 创建角色
-#[post("/roles")]
 pub async fn create_role(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     role_create_dto: web::Json<sys_role_dto::RoleCreationDto>,
 ) -> impl Responder {
     // 首先验证DTO
@@ -26,18 +39,22 @@ pub async fn create_role(
     // 尝试创建角色
     let result = sys_role_services::create_role(
         &*app_state.mysql_conn,
-        "admin".to_string(),
+        current_user.user_name,
         role_create_dto.into_inner(),
     )
     .await
     .map(|role_resp| role_resp) // 返回创建后的完整数据
-    .map_err(|error| ApiError::BadRequest(error.to_string()));
+    .map_err(|error| match error {
+        MyError::BadRequestError(msg) | MyError::InvalidTypeError(msg) => {
+            ApiError::InvalidArgument(msg)
+        }
+        error => ApiError::BadRequest(error.to_string()),
+    });
 
     create_response!(result)
 }
 
 // 获取角色列表
-#[get("/roles")]
 pub async fn get_roles(
     app_state: web::Data<globals::AppState>,
     web::Query(info): web::Query<PaginationQueryDto>,
@@ -53,7 +70,6 @@ pub async fn get_roles(
 }
 
 // Route to get all roles
-#[get("/roles-options")]
 pub async fn get_roles_options(app_state: web::Data<globals::AppState>) -> impl Responder {
     let result = sys_role_services::get_all_roles(&*app_state.mysql_conn)
         .await
@@ -62,8 +78,16 @@ pub async fn get_roles_options(app_state: web::Data<globals::AppState>) -> impl
     create_response!(result)
 }
 
+// 获取角色继承树
+pub async fn get_roles_tree(app_state: web::Data<globals::AppState>) -> impl Responder {
+    let result = sys_role_services::get_role_tree(&app_state.mysql_conn)
+        .await
+        .map_err(|error| ApiError::InternalServerError(error.to_string()));
+
+    create_response!(result)
+}
+
 // 获取单个角色
-#[get("/roles/{id}")]
 pub async fn get_role_by_id(
     app_state: web::Data<globals::AppState>,
     path: web::Path<i32>,
@@ -79,9 +103,9 @@ pub async fn get_role_by_id(
 }
 
 // 更新角色
-#[put("/roles/{id}")]
 pub async fn update_role(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     path: web::Path<i32>,
     role_update_dto: web::Json<sys_role_dto::RoleUpdateDto>,
 ) -> impl Responder {
@@ -99,16 +123,16 @@ pub async fn update_role(
         &*app_state.mysql_conn,
         role_id,
         role_update_dto.into_inner(),
+        current_user.user_name,
     )
     .await
     .map(|role_resp| role_resp) // 返回更新后的完整数据
-    .map_err(|error| ApiError::InternalServerError(error.to_string()));
+    .map_err(to_api_error);
 
     create_response!(result)
 }
 
 // 删除角色
-#[delete("/roles/{id}")]
 pub async fn delete_role(
     app_state: web::Data<globals::AppState>,
     path: web::Path<i32>,
@@ -132,7 +156,6 @@ pub async fn delete_role(
 }
 
 //批量删除角色
-#[delete("/roles")]
 pub async fn delete_roles(
     app_state: web::Data<globals::AppState>,
     role_ids: web::Json<Vec<i32>>,
@@ -156,12 +179,13 @@ pub async fn delete_roles(
     create_response!(result)
 }
 
-pub fn api_config(cfg: &mut web::ServiceConfig) {
-    cfg.service(create_role)
-        .service(get_roles)
-        .service(get_role_by_id)
-        .service(get_roles_options)
-        .service(update_role)
-        .service(delete_roles)
-        .service(delete_role);
+api_routes! {
+    create_role => POST "/roles",
+    get_roles => GET "/roles",
+    get_role_by_id => GET "/roles/{id}",
+    get_roles_options => GET "/roles-options",
+    get_roles_tree => GET "/roles-tree",
+    update_role => PUT "/roles/{id}",
+    delete_roles => DELETE "/roles",
+    delete_role => DELETE "/roles/{id}",
 }
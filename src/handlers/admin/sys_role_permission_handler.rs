@@ -1,6 +1,7 @@
+use crate::common::auth::current_user::CurrentUser;
 use crate::common::resp::{ApiError, ApiResponse};
 use crate::config::globals;
-use crate::create_response;
+use crate::{api_routes, create_response};
 use crate::dto::admin::sys_role_permission_dto::{
     AssignPermissionsDto, AssignPermissionsRespDto, RemovePermissionRespDto, RolePermissionDto,
     RolePermissionsRespDto,
@@ -9,12 +10,12 @@ use crate::services::admin::sys_role_permission_services::{
     assign_permissions_to_role, get_role_permissions, remove_permission_from_role,
 };
 use actix_web::ResponseError;
-use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse, Responder};
 
 // Assign permissions to a role
-#[post("/roles/{roleId}/permissions")]
 async fn assign_permissions(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     path: web::Path<i32>,
     permissions_dto: web::Json<AssignPermissionsDto>,
 ) -> impl Responder {
@@ -23,7 +24,7 @@ async fn assign_permissions(
         &*app_state.mysql_conn,
         role_id,
         permissions_dto.permission_ids.clone(),
-        "admin".to_string(),
+        current_user.user_name,
     )
     .await
     .map(|_| AssignPermissionsRespDto { success: true })
@@ -33,7 +34,6 @@ async fn assign_permissions(
 }
 
 // Get a role's permissions
-#[get("/roles/{roleId}/permissions")]
 async fn get_permissions(
     app_state: web::Data<globals::AppState>,
     path: web::Path<i32>,
@@ -53,7 +53,6 @@ async fn get_permissions(
     create_response!(result)
 }
 
-#[delete("/roles/{roleId}/permissions/{permissionId}")]
 async fn remove_permission(
     app_state: web::Data<globals::AppState>,
     path: web::Path<(i32, i32)>,
@@ -67,8 +66,8 @@ async fn remove_permission(
     create_response!(result)
 }
 
-pub fn api_config(cfg: &mut web::ServiceConfig) {
-    cfg.service(assign_permissions)
-        .service(get_permissions)
-        .service(remove_permission);
+api_routes! {
+    assign_permissions => POST "/roles/{roleId}/permissions",
+    get_permissions => GET "/roles/{roleId}/permissions",
+    remove_permission => DELETE "/roles/{roleId}/permissions/{permissionId}",
 }
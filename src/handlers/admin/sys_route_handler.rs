@@ -1,19 +1,20 @@
 use actix_web::{get, Responder, web};
+use crate::common::auth::current_user::CurrentUser;
 use crate::common::resp::ApiError;
 use crate::config::globals;
 use crate::create_response;
-use crate::handlers::admin::sys_role_handler::{ create_role };
 use crate::services::admin::{sys_route_services};
 use actix_web::HttpResponse;
 use crate::common::resp::ApiResponse;
 use actix_web::ResponseError;
 
+// 获取当前用户所有角色可见的路由
 #[get("/get-user-routes")]
 pub async fn get_user_menus_by_role_code(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
 ) -> impl Responder {
-    let role_code:&'static str = "admin";
-    let result = sys_route_services::get_menus_by_role_code(&*app_state.mysql_conn, &role_code)
+    let result = sys_route_services::get_menus_by_role_code(&app_state.mysql_conn, current_user.user_id, &current_user.role_codes)
         .await
         .map_err(|error| ApiError::InternalServerError(error.to_string()));
 
@@ -33,7 +34,6 @@ pub async fn get_constant_menus_by_role_code(
 
 
 pub fn api_config(cfg: &mut web::ServiceConfig) {
-    cfg.service(create_role)
-        .service(get_user_menus_by_role_code)
+    cfg.service(get_user_menus_by_role_code)
         .service(get_constant_menus_by_role_code);
 }
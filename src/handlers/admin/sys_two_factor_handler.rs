@@ -0,0 +1,326 @@
+use crate::common::auth::current_user::CurrentUser;
+use crate::common::error::MyError;
+use crate::common::resp::{ApiError, ApiResponse};
+use crate::config::globals;
+use crate::create_response;
+use crate::dto::admin::sys_two_factor_dto::{
+    RecoveryCodesDto, TotpSetupDto, TwoFactorCodeDto, TwoFactorEnrollDto, TwoFactorVerifyDto,
+    TwoFactorVerifyRespDto,
+};
+use crate::handlers::admin::sys_auth_handler::{
+    issue_tokens, login_client, record_login, record_login_success,
+};
+use crate::schemas::admin::sys_user;
+use crate::services::admin::sys_route_services::Status;
+use crate::services::admin::{sys_two_factor_services, sys_user_services};
+use actix_web::ResponseError;
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder};
+use validator::Validate;
+
+// 将两步验证服务的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::AuthError(msg) => ApiError::Unauthorized(msg),
+        MyError::BadRequestError(msg) => ApiError::InvalidArgument(msg),
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        MyError::TooManyRequestsError(msg) => ApiError::TooManyRequests(msg),
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
+async fn find_user(
+    app_state: &globals::AppState,
+    user_id: i32,
+) -> Result<sys_user::Model, MyError> {
+    sys_user_services::get_user_by_id_pure(&app_state.mysql_conn, user_id)
+        .await?
+        .ok_or(MyError::NotFound("User not found".to_string()))
+}
+
+// 挑战 token 对应的用户，用户被禁用时挑战同样无效
+async fn find_challenge_user(
+    app_state: &globals::AppState,
+    challenge_token: &str,
+) -> Result<sys_user::Model, MyError> {
+    let user_id =
+        sys_two_factor_services::get_challenge_user_id(&app_state.redis_conn, challenge_token)
+            .await?;
+    let user = find_user(app_state, user_id).await?;
+    if user.status != Status::Enable as i8 {
+        return Err(MyError::AuthError("User is disabled".to_string()));
+    }
+    Ok(user)
+}
+
+// 登录时角色要求两步验证但尚未绑定，使用挑战 token 获取绑定密钥
+#[post("/2fa/enroll")]
+pub async fn enroll_with_challenge(
+    app_state: web::Data<globals::AppState>,
+    enroll_dto: web::Json<TwoFactorEnrollDto>,
+) -> impl Responder {
+    if let Err(errors) = enroll_dto.0.validate() {
+        return create_response!(Err::<TotpSetupDto, ApiError>(ApiError::BadRequest(
+            errors.to_string()
+        )));
+    }
+
+    let rs = match find_challenge_user(&app_state, &enroll_dto.challenge_token).await {
+        Ok(user) if user.totp_enabled == 1 => Err(ApiError::InvalidArgument(
+            "Two-factor authentication is already enabled".to_string(),
+        )),
+        Ok(user) => sys_two_factor_services::start_enrollment(&app_state.redis_conn, &user)
+            .await
+            .map_err(to_api_error),
+        Err(err) => Err(to_api_error(err)),
+    };
+    create_response!(rs)
+}
+
+// 校验验证码或恢复码，换取 token；尚未绑定时同时完成绑定并返回恢复码
+#[post("/2fa/verify")]
+pub async fn verify_two_factor(
+    app_state: web::Data<globals::AppState>,
+    req: HttpRequest,
+    verify_dto: web::Json<TwoFactorVerifyDto>,
+) -> impl Responder {
+    if let Err(errors) = verify_dto.0.validate() {
+        return create_response!(Err::<TwoFactorVerifyRespDto, ApiError>(
+            ApiError::BadRequest(errors.to_string())
+        ));
+    }
+    let client = login_client(&req);
+    let dto = verify_dto.into_inner();
+
+    let user = match find_challenge_user(&app_state, &dto.challenge_token).await {
+        Ok(user) => user,
+        Err(err) => {
+            return create_response!(Err::<TwoFactorVerifyRespDto, ApiError>(to_api_error(err)))
+        }
+    };
+
+    // Ok(Some(_)) 表示校验通过，内层是登录时完成绑定生成的恢复码
+    let verified = if user.totp_enabled == 1 {
+        sys_two_factor_services::verify_second_factor(
+            &app_state.mysql_conn,
+            &app_state.redis_conn,
+            &user,
+            dto.code.as_deref(),
+            dto.recovery_code.as_deref(),
+        )
+        .await
+        .map(|ok| ok.then_some(None))
+    } else {
+        match sys_two_factor_services::confirm_enrollment(
+            &app_state.mysql_conn,
+            &app_state.redis_conn,
+            user.id,
+            dto.code.as_deref().unwrap_or_default(),
+        )
+        .await
+        {
+            Ok(recovery_codes) => Ok(Some(Some(recovery_codes))),
+            Err(MyError::AuthError(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    };
+
+    let rs = match verified {
+        Ok(Some(recovery_codes)) => {
+            match sys_two_factor_services::consume_challenge(
+                &app_state.redis_conn,
+                &dto.challenge_token,
+            )
+            .await
+            {
+                Ok(_) => match issue_tokens(&app_state, &user).await {
+                    Ok(base) => {
+                        record_login_success(&app_state, &user, &client).await;
+                        Ok(TwoFactorVerifyRespDto {
+                            base,
+                            recovery_codes,
+                        })
+                    }
+                    Err(err) => Err(to_api_error(err)),
+                },
+                Err(err) => Err(to_api_error(err)),
+            }
+        }
+        Ok(None) => {
+            let reason = "Invalid two-factor code".to_string();
+            record_login(&app_state, &user.user_name, &client, Some(reason.clone())).await;
+            Err(ApiError::Unauthorized(reason))
+        }
+        Err(MyError::TooManyRequestsError(msg)) => {
+            record_login(&app_state, &user.user_name, &client, Some(msg.clone())).await;
+            Err(ApiError::TooManyRequests(msg))
+        }
+        Err(err) => Err(to_api_error(err)),
+    };
+    create_response!(rs)
+}
+
+// 已登录用户开始绑定认证器
+#[post("/2fa/setup")]
+pub async fn setup_two_factor(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+) -> impl Responder {
+    let rs = match find_user(&app_state, current_user.user_id).await {
+        Ok(user) if user.totp_enabled == 1 => Err(ApiError::InvalidArgument(
+            "Two-factor authentication is already enabled".to_string(),
+        )),
+        Ok(user) => sys_two_factor_services::start_enrollment(&app_state.redis_conn, &user)
+            .await
+            .map_err(to_api_error),
+        Err(err) => Err(to_api_error(err)),
+    };
+    create_response!(rs)
+}
+
+// 使用认证器上的验证码确认绑定，返回恢复码
+#[post("/2fa/enable")]
+pub async fn enable_two_factor(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    code_dto: web::Json<TwoFactorCodeDto>,
+) -> impl Responder {
+    if let Err(errors) = code_dto.0.validate() {
+        return create_response!(Err::<RecoveryCodesDto, ApiError>(ApiError::BadRequest(
+            errors.to_string()
+        )));
+    }
+
+    let rs = sys_two_factor_services::confirm_enrollment(
+        &app_state.mysql_conn,
+        &app_state.redis_conn,
+        current_user.user_id,
+        code_dto.code.as_deref().unwrap_or_default(),
+    )
+    .await
+    .map(|recovery_codes| RecoveryCodesDto { recovery_codes })
+    .map_err(|err| match err {
+        MyError::AuthError(msg) => ApiError::InvalidArgument(msg),
+        err => to_api_error(err),
+    });
+    create_response!(rs)
+}
+
+// 已登录用户校验验证码或恢复码，校验不通过时返回错误
+async fn verify_current_user(
+    app_state: &globals::AppState,
+    user_id: i32,
+    code: Option<&str>,
+    recovery_code: Option<&str>,
+) -> Result<sys_user::Model, ApiError> {
+    let user = find_user(app_state, user_id).await.map_err(to_api_error)?;
+    if user.totp_enabled != 1 {
+        return Err(ApiError::InvalidArgument(
+            "Two-factor authentication is not enabled".to_string(),
+        ));
+    }
+    let verified = sys_two_factor_services::verify_second_factor(
+        &app_state.mysql_conn,
+        &app_state.redis_conn,
+        &user,
+        code,
+        recovery_code,
+    )
+    .await
+    .map_err(to_api_error)?;
+    if !verified {
+        return Err(ApiError::InvalidArgument(
+            "Invalid two-factor code".to_string(),
+        ));
+    }
+    Ok(user)
+}
+
+// 关闭两步验证，角色要求两步验证时不允许关闭
+#[post("/2fa/disable")]
+pub async fn disable_two_factor(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    code_dto: web::Json<TwoFactorCodeDto>,
+) -> impl Responder {
+    if let Err(errors) = code_dto.0.validate() {
+        return create_response!(Err::<bool, ApiError>(ApiError::BadRequest(
+            errors.to_string()
+        )));
+    }
+
+    match sys_two_factor_services::is_two_factor_required_by_role(
+        &app_state.mysql_conn,
+        current_user.user_id,
+    )
+    .await
+    {
+        Ok(false) => {}
+        Ok(true) => {
+            return create_response!(Err::<bool, ApiError>(ApiError::InvalidArgument(
+                "Two-factor authentication is required by your role".to_string()
+            )))
+        }
+        Err(err) => {
+            return create_response!(Err::<bool, ApiError>(ApiError::InternalServerError(
+                err.to_string()
+            )))
+        }
+    }
+
+    let rs = match verify_current_user(
+        &app_state,
+        current_user.user_id,
+        code_dto.code.as_deref(),
+        code_dto.recovery_code.as_deref(),
+    )
+    .await
+    {
+        Ok(user) => sys_two_factor_services::disable_two_factor(&app_state.mysql_conn, user.id)
+            .await
+            .map(|_| true)
+            .map_err(to_api_error),
+        Err(err) => Err(err),
+    };
+    create_response!(rs)
+}
+
+// 重新生成恢复码，需要校验认证器上的验证码
+#[post("/2fa/recovery-codes")]
+pub async fn regenerate_recovery_codes(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    code_dto: web::Json<TwoFactorCodeDto>,
+) -> impl Responder {
+    if let Err(errors) = code_dto.0.validate() {
+        return create_response!(Err::<RecoveryCodesDto, ApiError>(ApiError::BadRequest(
+            errors.to_string()
+        )));
+    }
+
+    let rs = match verify_current_user(
+        &app_state,
+        current_user.user_id,
+        code_dto.code.as_deref(),
+        None,
+    )
+    .await
+    {
+        Ok(user) => {
+            sys_two_factor_services::regenerate_recovery_codes(&app_state.mysql_conn, user.id)
+                .await
+                .map(|recovery_codes| RecoveryCodesDto { recovery_codes })
+                .map_err(to_api_error)
+        }
+        Err(err) => Err(err),
+    };
+    create_response!(rs)
+}
+
+pub fn api_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(enroll_with_challenge)
+        .service(verify_two_factor)
+        .service(setup_two_factor)
+        .service(enable_two_factor)
+        .service(disable_two_factor)
+        .service(regenerate_recovery_codes);
+}
@@ -1,20 +1,52 @@
+use crate::common::auth::current_user::CurrentUser;
+use crate::common::error::MyError;
 use crate::common::resp::{ApiError, ApiResponse};
 use crate::config::globals;
-use crate::create_response;
-use crate::dto::admin::common_dto;
+use crate::{api_routes, create_response};
 use crate::dto::admin::common_dto::PaginationResponseDto;
-use crate::services::admin::{sys_user_role_services, sys_user_services};
+use crate::services::admin::sys_route_services::Status;
+use crate::services::admin::{
+    sys_auth_services, sys_data_scope_services, sys_dept_services, sys_user_role_services,
+    sys_user_services,
+};
 use actix_web::ResponseError;
-use actix_web::{delete, put, HttpResponse};
-use actix_web::{get, post, web, Responder};
+use actix_web::HttpResponse;
+use actix_web::{web, Responder};
 use validator::Validate;
 use crate::dto::admin::sys_user_dto::{
-    UserCreateDto, UserCreateRespDto, UserUpdateDto, UserWithRolesDto,
+    UserCreateDto, UserCreateRespDto, UserQueryDto, UserUpdateDto, UserWithRolesDto,
 };
 
-#[post("/users")]
+// 将用户服务的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        MyError::PermissionDeniedError(msg) => ApiError::Forbidden(msg),
+        MyError::ValidationError(msg) | MyError::BadRequestError(msg) => {
+            ApiError::InvalidArgument(msg)
+        }
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
+// 校验目标用户在当前用户的数据权限内，超出范围时返回 403
+async fn check_data_scope(
+    app_state: &globals::AppState,
+    current_user: &CurrentUser,
+    user_ids: &[i32],
+) -> Result<sys_data_scope_services::DataScopeFilter, ApiError> {
+    let data_scope = sys_data_scope_services::get_data_scope(&app_state.mysql_conn, current_user)
+        .await
+        .map_err(to_api_error)?;
+    sys_data_scope_services::ensure_users_in_scope(&app_state.mysql_conn, &data_scope, user_ids)
+        .await
+        .map_err(to_api_error)?;
+    Ok(data_scope)
+}
+
 pub async fn create_user(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     user_create_dto: web::Json<UserCreateDto>,
 ) -> impl Responder {
     let result: Result<UserCreateRespDto, ApiError>;
@@ -25,19 +57,38 @@ pub async fn create_user(
         return create_response!(result);
     }
 
+    // 只能在数据权限范围内的部门下新建用户
+    let data_scope =
+        match sys_data_scope_services::get_data_scope(&app_state.mysql_conn, &current_user).await
+        {
+            Ok(data_scope) => data_scope,
+            Err(err) => {
+                result = Err(to_api_error(err));
+                return create_response!(result);
+            }
+        };
+    if let Err(err) = sys_data_scope_services::check_dept_in_scope(
+        &data_scope,
+        user_create_dto.dept_id.unwrap_or_default(),
+        None,
+    ) {
+        result = Err(to_api_error(err));
+        return create_response!(result);
+    }
+
     let roles = user_create_dto.user_roles.clone();
 
     // Create the user
     let user = match sys_user_services::create_user(
         &*app_state.mysql_conn,
         user_create_dto.into_inner(),
-        "admin".to_string(),
+        current_user.user_name.clone(),
     )
     .await
     {
         Ok(user) => user,
         Err(err) => {
-            result = Err(ApiError::InternalServerError(err.to_string()));
+            result = Err(to_api_error(err));
             return create_response!(result);
         }
     };
@@ -48,7 +99,7 @@ pub async fn create_user(
             &*app_state.mysql_conn,
             user.id,
             r.clone(),
-            "admin".to_string(),
+            current_user.user_name,
         )
         .await
         {
@@ -66,16 +117,45 @@ pub async fn create_user(
     create_response!(result)
 }
 
-#[get("/users")]
 pub async fn get_users_with_roles(
     app_state: web::Data<globals::AppState>,
-    query: web::Query<common_dto::PaginationQueryDto>,
+    current_user: CurrentUser,
+    query: web::Query<UserQueryDto>,
 ) -> impl Responder {
     let current_page = query.current.unwrap_or(1);
     let page_size = query.size.unwrap_or(10);
     let result: Result<PaginationResponseDto<UserWithRolesDto>, ApiError>;
+    // 当前用户角色的数据权限
+    let data_scope =
+        match sys_data_scope_services::get_data_scope(&app_state.mysql_conn, &current_user).await
+        {
+            Ok(data_scope) => data_scope,
+            Err(error) => {
+                result = Err(ApiError::InternalServerError(error.to_string()));
+                return create_response!(result);
+            }
+        };
+    // 按部门过滤时包含其全部下级部门
+    let dept_ids = match query.dept_id {
+        Some(dept_id) => {
+            match sys_dept_services::get_dept_subtree_ids(&app_state.mysql_conn, dept_id).await {
+                Ok(dept_ids) => Some(dept_ids),
+                Err(error) => {
+                    result = Err(ApiError::InternalServerError(error.to_string()));
+                    return create_response!(result);
+                }
+            }
+        }
+        None => None,
+    };
     // 查询总条数
-    let total_count = match sys_user_services::get_total_users_count(&app_state.mysql_conn).await {
+    let total_count = match sys_user_services::get_total_users_count(
+        &app_state.mysql_conn,
+        dept_ids.clone(),
+        &data_scope,
+    )
+    .await
+    {
         Ok(count) => count,
         Err(error) => {
             return {
@@ -88,10 +168,24 @@ pub async fn get_users_with_roles(
         &app_state.mysql_conn,
         current_page as usize,
         page_size as usize,
+        dept_ids,
+        &data_scope,
     )
     .await
     {
-        Ok(users_with_roles) => {
+        Ok(mut users_with_roles) => {
+            // 补充登录锁定状态
+            for user in users_with_roles.iter_mut() {
+                match sys_auth_services::get_user_lock_ttl(&app_state.redis_conn, &user.user_name)
+                    .await
+                {
+                    Ok(locked_for) => user.locked_for = locked_for,
+                    Err(error) => {
+                        result = Err(ApiError::InternalServerError(error.to_string()));
+                        return create_response!(result);
+                    }
+                }
+            }
             let response = PaginationResponseDto::new(
                 current_page,
                 page_size,
@@ -105,9 +199,9 @@ pub async fn get_users_with_roles(
     create_response!(result)
 }
 
-#[put("/users/{user_id}")]
 pub async fn update_user(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     user_id: web::Path<i32>,
     user_update_dto: web::Json<UserUpdateDto>,
 ) -> impl Responder {
@@ -118,35 +212,68 @@ pub async fn update_user(
         result = Err(ApiError::InvalidArgument(err.to_string()));
         return create_response!(result);
     }
+    let data_scope = match check_data_scope(&app_state, &current_user, &[*user_id]).await {
+        Ok(data_scope) => data_scope,
+        Err(err) => {
+            result = Err(err);
+            return create_response!(result);
+        }
+    };
+    // 不能把用户移到数据权限范围外的部门
+    if let Some(dept_id) = user_update_dto.dept_id {
+        if let Err(err) =
+            sys_data_scope_services::check_dept_in_scope(&data_scope, dept_id, Some(*user_id))
+        {
+            result = Err(to_api_error(err));
+            return create_response!(result);
+        }
+    }
     let roles = user_update_dto.user_roles.clone();
+    let password_changed = user_update_dto.password.is_some();
     // Update the user
     let updated_user = match sys_user_services::update_user(
         &*app_state.mysql_conn,
         *user_id,
         user_update_dto.into_inner(),
-        "admin".to_string(),
+        current_user.user_name.clone(),
     )
     .await
     {
         Ok(user) => user,
         Err(err) => {
-            result = Err(ApiError::InternalServerError(err.to_string()));
+            result = Err(to_api_error(err));
             return create_response!(result);
         }
     };
 
     // Optionally, update roles if provided
+    let mut roles_changed = false;
     if let Some(roles) = roles {
-        if let Err(err) = sys_user_role_services::assign_roles_to_user(
+        match sys_user_role_services::replace_user_roles(
             &*app_state.mysql_conn,
-            user_id.into_inner(),
+            *user_id,
             roles,
-            "admin".to_string(),
+            current_user.user_name,
         )
         .await
         {
-            result = Err(ApiError::InternalServerError(err.to_string()));
-            return create_response!(result);
+            Ok(changed) => roles_changed = changed,
+            Err(err) => {
+                result = Err(ApiError::InternalServerError(err.to_string()));
+                return create_response!(result);
+            }
+        }
+    }
+
+    // 用户被禁用、密码或角色被修改时，立即吊销其已签发的所有 token，token 中的角色随之失效
+    if let Some(ref user) = updated_user {
+        if user.status == Status::Disable as i8 || password_changed || roles_changed {
+            if let Err(err) =
+                sys_auth_services::revoke_user_tokens(&app_state.redis_conn, user.id).await
+            {
+                result = Err(ApiError::InternalServerError(err.to_string()));
+                return create_response!(result);
+            }
         }
     }
 
@@ -155,36 +282,122 @@ pub async fn update_user(
     create_response!(result)
 }
 
-#[delete("/users/{user_id}")]
 pub async fn delete_user_handler(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     user_id: web::Path<i32>,
 ) -> impl Responder {
     let result: Result<u64, ApiError> =
-        sys_user_services::delete_user(&app_state.mysql_conn, *user_id)
-            .await
-            .map_err(|e| ApiError::InternalServerError(e.to_string()));
+        match check_data_scope(&app_state, &current_user, &[*user_id]).await {
+            Ok(_) => sys_user_services::delete_user(&app_state.mysql_conn, *user_id)
+                .await
+                .map_err(|e| ApiError::InternalServerError(e.to_string())),
+            Err(err) => Err(err),
+        };
 
     create_response!(result)
 }
 
-#[delete("/users")]
 pub async fn batch_delete_users_handler(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     user_ids: web::Json<Vec<i32>>,
 ) -> impl Responder {
+    // 任一用户超出数据权限时整批拒绝
     let result: Result<u64, ApiError> =
-        sys_user_services::batch_delete_users(&app_state.mysql_conn, user_ids.into_inner())
+        match check_data_scope(&app_state, &current_user, &user_ids).await {
+            Ok(_) => {
+                sys_user_services::batch_delete_users(&app_state.mysql_conn, user_ids.into_inner())
+                    .await
+                    .map_err(|e| ApiError::InternalServerError(e.to_string()))
+            }
+            Err(err) => Err(err),
+        };
+
+    create_response!(result)
+}
+
+// 吊销用户所有已签发的 token（强制下线）
+pub async fn revoke_user_tokens_handler(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    user_id: web::Path<i32>,
+) -> impl Responder {
+    if let Err(err) = check_data_scope(&app_state, &current_user, &[*user_id]).await {
+        return create_response!(Err::<bool, ApiError>(err));
+    }
+    let result: Result<bool, ApiError> =
+        match sys_user_services::get_user_by_id(&app_state.mysql_conn, *user_id).await {
+            Ok(Some(user)) => {
+                sys_auth_services::revoke_user_tokens(&app_state.redis_conn, user.id)
+                    .await
+                    .map(|_| true)
+                    .map_err(|e| ApiError::InternalServerError(e.to_string()))
+            }
+            Ok(None) => Err(ApiError::NotFound("User not found".to_string())),
+            Err(e) => Err(ApiError::InternalServerError(e.to_string())),
+        };
+
+    create_response!(result)
+}
+
+// 解除用户的登录锁定
+pub async fn unlock_user_handler(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    user_id: web::Path<i32>,
+) -> impl Responder {
+    if let Err(err) = check_data_scope(&app_state, &current_user, &[*user_id]).await {
+        return create_response!(Err::<bool, ApiError>(err));
+    }
+    let result: Result<bool, ApiError> =
+        match sys_user_services::get_user_by_id(&app_state.mysql_conn, *user_id).await {
+            Ok(Some(user)) => sys_auth_services::unlock_user(&app_state.redis_conn, &user.user_name)
+                .await
+                .map_err(|e| ApiError::InternalServerError(e.to_string())),
+            Ok(None) => Err(ApiError::NotFound("User not found".to_string())),
+            Err(e) => Err(ApiError::InternalServerError(e.to_string())),
+        };
+
+    create_response!(result)
+}
+
+// 向用户邮箱发送重置密码链接
+pub async fn send_password_reset_handler(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    user_id: web::Path<i32>,
+) -> impl Responder {
+    if let Err(err) = check_data_scope(&app_state, &current_user, &[*user_id]).await {
+        return create_response!(Err::<bool, ApiError>(err));
+    }
+    let result: Result<bool, ApiError> =
+        match sys_user_services::get_user_by_id_pure(&app_state.mysql_conn, *user_id).await {
+            Ok(Some(user)) => sys_auth_services::send_password_reset_mail(
+                &app_state.redis_conn,
+                app_state.mail_sender.as_ref(),
+                &user,
+            )
             .await
-            .map_err(|e| ApiError::InternalServerError(e.to_string()));
+            .map(|_| true)
+            .map_err(|e| match e {
+                MyError::BadRequestError(msg) => ApiError::InvalidArgument(msg),
+                e => ApiError::InternalServerError(e.to_string()),
+            }),
+            Ok(None) => Err(ApiError::NotFound("User not found".to_string())),
+            Err(e) => Err(ApiError::InternalServerError(e.to_string())),
+        };
 
     create_response!(result)
 }
 
-pub fn api_config(cfg: &mut web::ServiceConfig) {
-    cfg.service(get_users_with_roles)
-        .service(create_user)
-        .service(update_user)
-        .service(delete_user_handler)
-        .service(batch_delete_users_handler);
+api_routes! {
+    get_users_with_roles => GET "/users",
+    create_user => POST "/users",
+    update_user => PUT "/users/{user_id}",
+    delete_user_handler => DELETE "/users/{user_id}",
+    batch_delete_users_handler => DELETE "/users",
+    revoke_user_tokens_handler => POST "/users/{user_id}/revoke-tokens",
+    unlock_user_handler => POST "/users/{user_id}/unlock",
+    send_password_reset_handler => POST "/users/{user_id}/reset-password",
 }
@@ -0,0 +1,94 @@
+use crate::common::auth::current_user::CurrentUser;
+use crate::common::error::MyError;
+use crate::common::resp::{ApiError, ApiResponse};
+use crate::config::globals;
+use crate::dto::admin::sys_user_permission_dto::{
+    AssignUserPermissionsDto, AssignUserPermissionsRespDto, RemoveUserPermissionRespDto,
+    UserPermissionDto, UserPermissionsRespDto,
+};
+use crate::services::admin::sys_user_permission_services;
+use crate::{api_routes, create_response};
+use actix_web::ResponseError;
+use actix_web::{web, HttpResponse, Responder};
+use validator::Validate;
+
+// 将用户直接授权服务的错误转换为响应错误
+fn to_api_error(error: MyError) -> ApiError {
+    match error {
+        MyError::NotFound(msg) => ApiError::NotFound(msg),
+        MyError::BadRequestError(msg) | MyError::InvalidTypeError(msg) => {
+            ApiError::InvalidArgument(msg)
+        }
+        error => ApiError::InternalServerError(error.to_string()),
+    }
+}
+
+// 设置用户的直接授权，覆盖原有的直接授权
+async fn assign_user_permissions(
+    app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
+    path: web::Path<i32>,
+    permissions_dto: web::Json<AssignUserPermissionsDto>,
+) -> impl Responder {
+    if let Err(errors) = permissions_dto.validate() {
+        return create_response!(Err::<AssignUserPermissionsRespDto, ApiError>(
+            ApiError::InvalidArgument(errors.to_string())
+        ));
+    }
+    let result = sys_user_permission_services::assign_permissions_to_user(
+        &app_state.mysql_conn,
+        path.into_inner(),
+        permissions_dto.into_inner().permissions,
+        current_user.user_name,
+    )
+    .await
+    .map(|_| AssignUserPermissionsRespDto { success: true })
+    .map_err(to_api_error);
+
+    create_response!(result)
+}
+
+// 获取用户的直接授权
+async fn get_user_permissions(
+    app_state: web::Data<globals::AppState>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let result = sys_user_permission_services::get_user_permissions(
+        &app_state.mysql_conn,
+        path.into_inner(),
+    )
+    .await
+    .map(|permissions| UserPermissionsRespDto {
+        permissions: permissions
+            .into_iter()
+            .map(UserPermissionDto::from)
+            .collect(),
+    })
+    .map_err(|error| ApiError::InternalServerError(error.to_string()));
+
+    create_response!(result)
+}
+
+// 删除用户的一条直接授权
+async fn remove_user_permission(
+    app_state: web::Data<globals::AppState>,
+    path: web::Path<(i32, i32)>,
+) -> impl Responder {
+    let (user_id, permission_id) = path.into_inner();
+    let result = sys_user_permission_services::remove_permission_from_user(
+        &app_state.mysql_conn,
+        user_id,
+        permission_id,
+    )
+    .await
+    .map(|rows| RemoveUserPermissionRespDto { success: rows != 0 })
+    .map_err(|error| ApiError::InternalServerError(error.to_string()));
+
+    create_response!(result)
+}
+
+api_routes! {
+    assign_user_permissions => POST "/users/{userId}/permissions",
+    get_user_permissions => GET "/users/{userId}/permissions",
+    remove_user_permission => DELETE "/users/{userId}/permissions/{permissionId}",
+}
@@ -1,20 +1,22 @@
+use crate::common::auth::current_user::CurrentUser;
 use crate::common::resp::{ApiError, ApiResponse};
 use crate::config::globals;
-use crate::create_response;
+use crate::{api_routes, create_response};
 use crate::dto::admin::sys_user_role_dto::{
     AssignRolesDto, AssignRolesRespDto, RemoveRoleRespDto, UserRoleDto, UserRolesRespDto,
 };
+use crate::services::admin::sys_auth_services::revoke_user_tokens;
 use crate::services::admin::sys_user_role_services::{
-    assign_roles_to_user, get_user_roles, remove_role_from_user,
+    get_user_roles, remove_role_from_user, replace_user_roles,
 };
 use actix_web::ResponseError;
-use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse, Responder};
 use validator::Validate;
 
 // Assign roles to a user
-#[post("/users/{userId}/roles")]
 async fn assign_roles(
     app_state: web::Data<globals::AppState>,
+    current_user: CurrentUser,
     path: web::Path<i32>,
     roles_dto: web::Json<AssignRolesDto>,
 ) -> impl Responder {
@@ -25,21 +27,27 @@ async fn assign_roles(
         ));
     }
     let role_ids = roles_dto.into_inner().role_ids;
-    let result = assign_roles_to_user(
+    let result = match replace_user_roles(
         &*app_state.mysql_conn,
         user_id,
         role_ids,
-        "admin".to_string(),
+        current_user.user_name,
     )
     .await
-    .map(|_| AssignRolesRespDto { success: true })
-    .map_err(|error| ApiError::InternalServerError(error.to_string()));
+    {
+        // 角色变化后吊销已签发的 token，避免 token 中的旧角色继续生效
+        Ok(true) => revoke_user_tokens(&app_state.redis_conn, user_id)
+            .await
+            .map(|_| AssignRolesRespDto { success: true })
+            .map_err(|error| ApiError::InternalServerError(error.to_string())),
+        Ok(false) => Ok(AssignRolesRespDto { success: true }),
+        Err(error) => Err(ApiError::InternalServerError(error.to_string())),
+    };
 
     create_response!(result)
 }
 
 // Get a user's roles
-#[get("/users/{userId}/roles")]
 async fn get_roles(
     app_state: web::Data<globals::AppState>,
     path: web::Path<i32>,
@@ -60,23 +68,27 @@ async fn get_roles(
 }
 
 // Remove a role from a user
-#[delete("/users/{userId}/roles/{roleId}")]
 async fn remove_role(
     app_state: web::Data<globals::AppState>,
     path: web::Path<(i32, i32)>,
 ) -> impl Responder {
     let (user_id, role_id) = path.into_inner();
-    let result = remove_role_from_user(&*app_state.mysql_conn, user_id, role_id)
-        .await
-        .map(|_| RemoveRoleRespDto { success: true })
-        .map_err(|error| ApiError::InternalServerError(error.to_string()));
+    let result = match remove_role_from_user(&*app_state.mysql_conn, user_id, role_id).await {
+        // 移除的角色可能仍在已签发的 token 中，吊销后需要重新登录
+        Ok(removed) if removed > 0 => revoke_user_tokens(&app_state.redis_conn, user_id)
+            .await
+            .map(|_| RemoveRoleRespDto { success: true })
+            .map_err(|error| ApiError::InternalServerError(error.to_string())),
+        Ok(_) => Ok(RemoveRoleRespDto { success: true }),
+        Err(error) => Err(ApiError::InternalServerError(error.to_string())),
+    };
 
     create_response!(result)
 }
 
 // Remember to register these handlers in your Actix Web app configuration
-pub fn api_config(cfg: &mut web::ServiceConfig) {
-    cfg.service(assign_roles)
-        .service(get_roles)
-        .service(remove_role);
+api_routes! {
+    assign_roles => POST "/users/{userId}/roles",
+    get_roles => GET "/users/{userId}/roles",
+    remove_role => DELETE "/users/{userId}/roles/{roleId}",
 }
@@ -1,16 +1,36 @@
 use crate::common;
+use crate::common::auth::api_key::ApiKeyScope;
+use crate::common::auth::jwt::Claims;
+use crate::common::client_ip::client_ip;
+use crate::common::error::MyError;
 use crate::common::resp::create_error_response;
+use crate::config::globals;
+use crate::handlers::admin::SYSTEM_MANAGE_SCOPE;
+use crate::services::admin::{sys_api_key_services, sys_auth_services, sys_user_services};
 use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
 use actix_web::http::StatusCode;
-use actix_web::{Error, HttpMessage};
-use log::debug;
+use actix_web::{web, Error, HttpMessage};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Header, TokenData};
+use log::{debug, error};
 use std::future::{ready, Future, Ready};
+use std::net::IpAddr;
 use std::pin::Pin;
+use std::rc::Rc;
+
+// 校验 API Key IP 白名单使用的客户端IP，只有配置信任代理时才读取 X-Forwarded-For 等请求头
+fn api_key_client_ip(req: &ServiceRequest) -> Option<IpAddr> {
+    client_ip(
+        req.peer_addr(),
+        &req.connection_info(),
+        globals::APP_CONFIG.api_key.trust_proxy_headers,
+    )
+}
 
 pub struct JWTAuth;
 impl<S, B> Transform<S, ServiceRequest> for JWTAuth
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -22,25 +42,106 @@ where
 
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(JWTAuthHiMiddleware {
-            service,
-            verification_path: vec!["/system-manage"],
+            service: Rc::new(service),
+            verification_path: vec![
+                "/system-manage",
+                "/auth/logout",
+                "/auth/user-info",
+                "/auth/change-password",
+                "/auth/2fa/setup",
+                "/auth/2fa/enable",
+                "/auth/2fa/disable",
+                "/auth/2fa/recovery-codes",
+                "/route/get-user-routes",
+            ],
             no_verification_path: vec!["/api/health-checker"],
         }))
     }
 }
 
 pub struct JWTAuthHiMiddleware<S> {
-    service: S,
+    service: Rc<S>,
     verification_path: Vec<&'static str>,
     no_verification_path: Vec<&'static str>,
 }
 
 impl<S, B> JWTAuthHiMiddleware<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
+    // API Key 认证：校验通过后以所属用户的身份继续处理，并附带 Key 的权限范围
+    fn call_with_api_key(
+        &self,
+        req: ServiceRequest,
+        key: String,
+    ) -> Pin<Box<dyn Future<Output = Result<ServiceResponse<B>, Error>>>> {
+        // API Key 只用于调用系统管理接口，不能用于登出、改密码等账号操作
+        if !req.path().starts_with(SYSTEM_MANAGE_SCOPE) {
+            return Box::pin(async {
+                Err(create_error_response(
+                    "API keys are not accepted for this endpoint",
+                    StatusCode::UNAUTHORIZED,
+                ))
+            });
+        }
+        let client_ip = api_key_client_ip(&req);
+        let app_state = req.app_data::<web::Data<globals::AppState>>().unwrap();
+        let mysql_conn = app_state.mysql_conn.clone();
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let identity =
+                match sys_api_key_services::authenticate_api_key(&mysql_conn, &key, client_ip)
+                    .await
+                {
+                    Ok(identity) => identity,
+                    Err(MyError::AuthError(msg)) => {
+                        return Err(create_error_response(&msg, StatusCode::UNAUTHORIZED))
+                    }
+                    Err(err) => {
+                        error!("failed to check api key: {}", err);
+                        return Err(create_error_response(
+                            "Authorization Failed",
+                            StatusCode::UNAUTHORIZED,
+                        ));
+                    }
+                };
+
+            debug!(
+                "api key auth success key_prefix: {} user_name: {}",
+                identity.api_key.key_prefix, identity.user.user_name
+            );
+
+            let timestamp = |time: Option<DateTime<Utc>>| {
+                time.map_or(0, |time| time.timestamp().max(0) as usize)
+            };
+            let claims = Claims {
+                user_id: identity.user.id,
+                user_name: identity.user.user_name,
+                exp: timestamp(identity.api_key.expire_time),
+                role_codes: identity.role_codes,
+                jti: format!("api_key:{}", identity.api_key.id),
+                iat: timestamp(identity.api_key.create_time),
+                iat_ms: identity
+                    .api_key
+                    .create_time
+                    .map_or(0, |time| time.timestamp_millis().max(0) as u64),
+            };
+            req.extensions_mut().insert(TokenData {
+                header: Header::default(),
+                claims,
+            });
+            req.extensions_mut().insert(ApiKeyScope {
+                api_key_id: identity.api_key.id,
+                permission_ids: identity.permission_ids,
+            });
+
+            service.call(req).await
+        })
+    }
+
     fn is_need_verification(&self, path: &str) -> bool {
         self.verification_path
             .iter()
@@ -54,7 +155,7 @@ where
 
 impl<S, B> Service<ServiceRequest> for JWTAuthHiMiddleware<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -96,18 +197,67 @@ where
         }
 
         let token = &authorization["Bearer ".len()..];
+        if token.starts_with(&globals::APP_CONFIG.api_key.prefix) {
+            let key = token.to_string();
+            return self.call_with_api_key(req, key);
+        }
         match common::auth::jwt::decode_jwt(token) {
             Ok(jwt_info) if !jwt_info.claims.is_expired() => {
-                debug!(
-                    "user auth success user_name: {} user_role: {:?}",
-                    jwt_info.claims.user_name, jwt_info.claims.role_codes
-                );
+                let app_state = req.app_data::<web::Data<globals::AppState>>().unwrap();
+                let redis_conn = app_state.redis_conn.clone();
+                let mysql_conn = app_state.mysql_conn.clone();
+                let service = self.service.clone();
+
+                Box::pin(async move {
+                    // 检查 token 是否已被吊销（登出或管理员强制下线）
+                    match sys_auth_services::is_access_token_revoked(&redis_conn, &jwt_info.claims)
+                        .await
+                    {
+                        Ok(false) => {}
+                        Ok(true) => {
+                            return Err(create_error_response(
+                                "Token revoked",
+                                StatusCode::UNAUTHORIZED,
+                            ))
+                        }
+                        Err(err) => {
+                            error!("failed to check token revocation: {}", err);
+                            return Err(create_error_response(
+                                "Authorization Failed",
+                                StatusCode::UNAUTHORIZED,
+                            ));
+                        }
+                    }
 
-                req.extensions_mut().insert(jwt_info);
+                    // 被禁用或已删除的用户，已签发的 token 立即失效
+                    match sys_user_services::is_user_enabled(&mysql_conn, jwt_info.claims.user_id)
+                        .await
+                    {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            return Err(create_error_response(
+                                "User disabled",
+                                StatusCode::UNAUTHORIZED,
+                            ))
+                        }
+                        Err(err) => {
+                            error!("failed to check user status: {}", err);
+                            return Err(create_error_response(
+                                "Authorization Failed",
+                                StatusCode::UNAUTHORIZED,
+                            ));
+                        }
+                    }
 
-                let fut = self.service.call(req);
+                    debug!(
+                        "user auth success user_name: {} user_role: {:?}",
+                        jwt_info.claims.user_name, jwt_info.claims.role_codes
+                    );
 
-                Box::pin(async move { fut.await })
+                    req.extensions_mut().insert(jwt_info);
+
+                    service.call(req).await
+                })
             }
             Ok(_) => Box::pin(async {
                 Err(create_error_response(
@@ -1,14 +1,16 @@
+use crate::common::auth::api_key::ApiKeyScope;
 use crate::common::auth::jwt::Claims;
 use crate::common::resp::create_error_response;
 use crate::config::globals;
-use crate::services::admin::sys_role_permission_services::get_menus_by_role_id;
-use crate::services::admin::sys_role_services::get_role_ids_by_role_codes;
+use crate::services::admin::sys_authorization_services::{is_api_allowed, is_api_key_allowed};
 use actix_service::{Service, Transform};
 use actix_web::http::StatusCode;
 use actix_web::{dev::ServiceRequest, dev::ServiceResponse, web, Error, HttpMessage};
 use futures::future::{ready, Ready};
 use futures::Future;
-use std::sync::Arc;
+use jsonwebtoken::TokenData;
+use log::error;
+use std::rc::Rc;
 use std::{
     pin::Pin,
     task::{Context, Poll},
@@ -17,7 +19,7 @@ use std::{
 pub struct PermissionCheck;
 
 pub struct PermissionCheckMiddleware<S> {
-    service: Arc<S>,
+    service: Rc<S>,
 }
 
 impl<S, B> Transform<S, ServiceRequest> for PermissionCheck
@@ -33,7 +35,7 @@ where
 
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(PermissionCheckMiddleware {
-            service: Arc::new(service),
+            service: Rc::new(service),
         }))
     }
 }
@@ -52,59 +54,77 @@ where
     }
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        // Clone the database connection or the relevant part of app_state here. Assuming app_state.mysql_conn is an Arc or similar.
-        let mysql_conn = req
-            .app_data::<web::Data<globals::AppState>>()
-            .unwrap()
-            .mysql_conn
-            .clone();
+        let app_state = req.app_data::<web::Data<globals::AppState>>().unwrap();
+        let mysql_conn = app_state.mysql_conn.clone();
+        let redis_conn = app_state.redis_conn.clone();
         let service = self.service.clone();
 
         let path = req.path().to_string();
+        let method = req.method().clone();
 
-        let roles = req
+        let subject = req
             .extensions()
-            .get::<Claims>()
-            .unwrap_or(&Claims::new())
-            .role_codes
-            .clone();
+            .get::<TokenData<Claims>>()
+            .map(|token_data| {
+                (
+                    token_data.claims.user_id,
+                    token_data.claims.role_codes.clone(),
+                )
+            });
+        let api_key_scope = req.extensions().get::<ApiKeyScope>().cloned();
 
         Box::pin(async move {
-            if roles.is_empty() {
+            let Some((user_id, roles)) = subject else {
                 return Err(create_error_response(
-                    "Authorization Failed: No Roles Found",
-                    StatusCode::UNAUTHORIZED,
+                    "Authorization Failed: No Claims Found",
+                    StatusCode::FORBIDDEN,
                 ));
-            }
-            // 首先根据角色代码获取角色ID
-            match get_role_ids_by_role_codes(&*mysql_conn, roles).await {
-                Ok(role_ids) => {
-                    // 然后根据角色ID获取菜单信息
-                    match get_menus_by_role_id(&*mysql_conn, role_ids).await {
-                        Ok(permissions) => {
-                            // 检查请求路径是否在用户的权限内
-                            let allowed =
-                                permissions.iter().any(|perm| path.starts_with(&perm.route));
-                            if allowed {
-                                let fut = service.call(req);
-                                fut.await
-                            } else {
-                                Err(create_error_response(
-                                    "Authorization Failed",
-                                    StatusCode::UNAUTHORIZED,
-                                ))
-                            }
-                        }
-                        Err(_) => Err(create_error_response(
-                            "Authorization Failed",
-                            StatusCode::UNAUTHORIZED,
-                        )),
+            };
+            // API Key 只能访问其权限范围内的接口，超级管理员的 Key 也不例外
+            if let Some(scope) = api_key_scope {
+                return match is_api_key_allowed(
+                    &mysql_conn,
+                    user_id,
+                    &roles,
+                    &scope.permission_ids,
+                    &method,
+                    &path,
+                )
+                .await
+                {
+                    Ok(true) => service.call(req).await,
+                    Ok(false) => Err(create_error_response(
+                        "Permission Denied",
+                        StatusCode::FORBIDDEN,
+                    )),
+                    Err(err) => {
+                        error!(
+                            "failed to load api key grants {}: {}",
+                            scope.api_key_id, err
+                        );
+                        Err(create_error_response(
+                            "Permission Denied",
+                            StatusCode::FORBIDDEN,
+                        ))
                     }
-                }
-                Err(_) => Err(create_error_response(
-                    "Authorization Failed",
-                    StatusCode::UNAUTHORIZED,
+                };
+            }
+            // 任一角色或用户直接授权拒绝该请求即拒绝，否则任一授权覆盖该请求即放行
+            // 已启用的超级管理员角色拥有全部接口权限，但仍受拒绝授权约束，该身份随角色授权一起缓存
+            // roles 为空时不直接拒绝，用户仍可能有直接授权
+            match is_api_allowed(&mysql_conn, &redis_conn, user_id, &roles, &method, &path).await {
+                Ok(true) => service.call(req).await,
+                Ok(false) => Err(create_error_response(
+                    "Permission Denied",
+                    StatusCode::FORBIDDEN,
                 )),
+                Err(err) => {
+                    error!("failed to load api grants: {}", err);
+                    Err(create_error_response(
+                        "Permission Denied",
+                        StatusCode::FORBIDDEN,
+                    ))
+                }
             }
         })
     }
@@ -1,5 +1,7 @@
 use crate::common::error::MyError;
-use crate::schemas::admin::sea_orm_active_enums::{ActionCode, Gender, TargetType, Type};
+use crate::schemas::admin::sea_orm_active_enums::{
+    ActionCode, ApiMethod, DataScope, Gender, LoginResult, PermissionEffect, TargetType, Type,
+};
 use std::str::FromStr;
 
 impl Type {
@@ -63,6 +65,83 @@ impl ActionCode {
     }
 }
 
+impl ApiMethod {
+    pub fn from_string(s: &str) -> Result<Self, MyError> {
+        match s {
+            "GET" => Ok(ApiMethod::Get),
+            "POST" => Ok(ApiMethod::Post),
+            "PUT" => Ok(ApiMethod::Put),
+            "DELETE" => Ok(ApiMethod::Delete),
+            _ => Err(MyError::InvalidTypeError(format!("invalid ApiMethod {}", s))),
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiMethod::Get => "GET",
+            ApiMethod::Post => "POST",
+            ApiMethod::Put => "PUT",
+            ApiMethod::Delete => "DELETE",
+        }
+    }
+}
+
+impl LoginResult {
+    pub fn from_string(s: &str) -> Result<Self, MyError> {
+        match s {
+            "SUCCESS" => Ok(LoginResult::Success),
+            "FAILURE" => Ok(LoginResult::Failure),
+            _ => Err(MyError::InvalidTypeError(format!("invalid LoginResult {}", s))),
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginResult::Success => "SUCCESS",
+            LoginResult::Failure => "FAILURE",
+        }
+    }
+}
+
+impl DataScope {
+    pub fn from_string(s: &str) -> Result<Self, MyError> {
+        match s {
+            "ALL" => Ok(DataScope::All),
+            "CUSTOM" => Ok(DataScope::Custom),
+            "DEPT" => Ok(DataScope::Dept),
+            "DEPT_AND_CHILD" => Ok(DataScope::DeptAndChild),
+            "SELF_ONLY" => Ok(DataScope::SelfOnly),
+            _ => Err(MyError::InvalidTypeError(format!("invalid DataScope {}", s))),
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataScope::All => "ALL",
+            DataScope::Custom => "CUSTOM",
+            DataScope::Dept => "DEPT",
+            DataScope::DeptAndChild => "DEPT_AND_CHILD",
+            DataScope::SelfOnly => "SELF_ONLY",
+        }
+    }
+}
+
+impl PermissionEffect {
+    pub fn from_string(s: &str) -> Result<Self, MyError> {
+        match s {
+            "ALLOW" => Ok(PermissionEffect::Allow),
+            "DENY" => Ok(PermissionEffect::Deny),
+            _ => Err(MyError::InvalidTypeError(format!(
+                "invalid PermissionEffect {}",
+                s
+            ))),
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PermissionEffect::Allow => "ALLOW",
+            PermissionEffect::Deny => "DENY",
+        }
+    }
+}
+
 impl FromStr for Gender {
     type Err = ();
 
@@ -4,12 +4,19 @@ pub mod prelude;
 
 pub mod sea_orm_active_enums;
 pub mod sys_api;
+pub mod sys_api_key;
+pub mod sys_api_key_permission;
+pub mod sys_dept;
+pub mod sys_login_log;
 pub mod sys_menu;
 pub mod sys_permission;
 pub mod sys_permission_action;
 pub mod sys_permission_target;
 pub mod sys_role;
+pub mod sys_role_dept;
 pub mod sys_role_permission;
 pub mod sys_user;
+pub mod sys_user_identity;
+pub mod sys_user_permission;
 pub mod sys_user_role;
 pub mod impl_for;
@@ -1,11 +1,18 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
 
 pub use super::sys_api::Entity as SysApi;
+pub use super::sys_api_key::Entity as SysApiKey;
+pub use super::sys_api_key_permission::Entity as SysApiKeyPermission;
+pub use super::sys_dept::Entity as SysDept;
+pub use super::sys_login_log::Entity as SysLoginLog;
 pub use super::sys_menu::Entity as SysMenu;
 pub use super::sys_permission::Entity as SysPermission;
 pub use super::sys_permission_action::Entity as SysPermissionAction;
 pub use super::sys_permission_target::Entity as SysPermissionTarget;
 pub use super::sys_role::Entity as SysRole;
+pub use super::sys_role_dept::Entity as SysRoleDept;
 pub use super::sys_role_permission::Entity as SysRolePermission;
 pub use super::sys_user::Entity as SysUser;
+pub use super::sys_user_identity::Entity as SysUserIdentity;
+pub use super::sys_user_permission::Entity as SysUserPermission;
 pub use super::sys_user_role::Entity as SysUserRole;
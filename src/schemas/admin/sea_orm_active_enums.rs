@@ -28,6 +28,20 @@ pub enum ApiMethod {
     Delete,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "data_scope")]
+pub enum DataScope {
+    #[sea_orm(string_value = "ALL")]
+    All,
+    #[sea_orm(string_value = "CUSTOM")]
+    Custom,
+    #[sea_orm(string_value = "DEPT")]
+    Dept,
+    #[sea_orm(string_value = "DEPT_AND_CHILD")]
+    DeptAndChild,
+    #[sea_orm(string_value = "SELF_ONLY")]
+    SelfOnly,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "gender")]
 pub enum Gender {
     #[sea_orm(string_value = "1")]
@@ -38,6 +52,22 @@ pub enum Gender {
     _3,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "result")]
+pub enum LoginResult {
+    #[sea_orm(string_value = "SUCCESS")]
+    Success,
+    #[sea_orm(string_value = "FAILURE")]
+    Failure,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "effect")]
+pub enum PermissionEffect {
+    #[sea_orm(string_value = "ALLOW")]
+    Allow,
+    #[sea_orm(string_value = "DENY")]
+    Deny,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "target_type")]
 pub enum TargetType {
     #[sea_orm(string_value = "MENU")]
@@ -18,6 +18,7 @@ pub struct Model {
     pub create_time: Option<DateTimeUtc>,
     pub update_user: Option<String>,
     pub update_time: Option<DateTimeUtc>,
+    pub deprecated: i8,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
 
+use super::sea_orm_active_enums::PermissionEffect;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -18,16 +19,27 @@ pub struct Model {
     pub update_user: Option<String>,
     pub update_time: Option<DateTimeUtc>,
     pub status: i8,
+    pub effect: PermissionEffect,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::sys_api_key_permission::Entity")]
+    SysApiKeyPermission,
     #[sea_orm(has_many = "super::sys_permission_action::Entity")]
     SysPermissionAction,
     #[sea_orm(has_many = "super::sys_permission_target::Entity")]
     SysPermissionTarget,
     #[sea_orm(has_many = "super::sys_role_permission::Entity")]
     SysRolePermission,
+    #[sea_orm(has_many = "super::sys_user_permission::Entity")]
+    SysUserPermission,
+}
+
+impl Related<super::sys_api_key_permission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SysApiKeyPermission.def()
+    }
 }
 
 impl Related<super::sys_permission_action::Entity> for Entity {
@@ -48,4 +60,10 @@ impl Related<super::sys_role_permission::Entity> for Entity {
     }
 }
 
+impl Related<super::sys_user_permission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SysUserPermission.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
 
+use super::sea_orm_active_enums::DataScope;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +14,10 @@ pub struct Model {
     #[sea_orm(unique)]
     pub role_name: String,
     pub description: Option<String>,
+    pub home: Option<String>,
+    pub require_two_factor: i8,
+    pub parent_role_id: Option<i32>,
+    pub data_scope: DataScope,
     pub create_user: String,
     pub create_time: Option<DateTimeUtc>,
     pub update_user: Option<String>,
@@ -22,12 +27,28 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentRoleId",
+        to = "Column::Id",
+        on_update = "Restrict",
+        on_delete = "SetNull"
+    )]
+    SelfRef,
+    #[sea_orm(has_many = "super::sys_role_dept::Entity")]
+    SysRoleDept,
     #[sea_orm(has_many = "super::sys_role_permission::Entity")]
     SysRolePermission,
     #[sea_orm(has_many = "super::sys_user_role::Entity")]
     SysUserRole,
 }
 
+impl Related<super::sys_role_dept::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SysRoleDept.def()
+    }
+}
+
 impl Related<super::sys_role_permission::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::SysRolePermission.def()
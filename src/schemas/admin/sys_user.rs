@@ -25,14 +25,56 @@ pub struct Model {
     pub update_time: Option<DateTimeUtc>,
     pub last_login: Option<DateTimeUtc>,
     pub status: i8,
+    pub totp_secret: Option<String>,
+    pub totp_enabled: i8,
+    pub recovery_codes: Option<String>,
+    pub dept_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sys_dept::Entity",
+        from = "Column::DeptId",
+        to = "super::sys_dept::Column::Id",
+        on_update = "Restrict",
+        on_delete = "SetNull"
+    )]
+    SysDept,
+    #[sea_orm(has_many = "super::sys_api_key::Entity")]
+    SysApiKey,
+    #[sea_orm(has_many = "super::sys_user_identity::Entity")]
+    SysUserIdentity,
+    #[sea_orm(has_many = "super::sys_user_permission::Entity")]
+    SysUserPermission,
     #[sea_orm(has_many = "super::sys_user_role::Entity")]
     SysUserRole,
 }
 
+impl Related<super::sys_api_key::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SysApiKey.def()
+    }
+}
+
+impl Related<super::sys_dept::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SysDept.def()
+    }
+}
+
+impl Related<super::sys_user_identity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SysUserIdentity.def()
+    }
+}
+
+impl Related<super::sys_user_permission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SysUserPermission.def()
+    }
+}
+
 impl Related<super::sys_user_role::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::SysUserRole.def()
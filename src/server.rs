@@ -47,23 +47,29 @@ pub async fn start_server(shutdown_rx: oneshot::Receiver<()>) -> std::io::Result
             .app_data(Data::new(AppState {
                 redis_conn: app_state.redis_conn.clone(),
                 mysql_conn: app_state.mysql_conn.clone(),
+                mail_sender: app_state.mail_sender.clone(),
             }))
             .service(
-                web::scope("/auth").configure(handlers::admin::sys_auth_handler::api_config),
+                web::scope("/.well-known")
+                    .configure(handlers::admin::sys_jwks_handler::api_config),
             )
             .service(
-                web::scope("/system-manage")
-                    .configure(handlers::admin::sys_role_handler::api_config)
-                    .configure(handlers::admin::sys_menu_handler::api_config)
-                    .configure(handlers::admin::sys_permission_handler::api_config)
-                    .configure(handlers::admin::sys_user_role_handler::api_config)
-                    .configure(handlers::admin::sys_role_permission_handler::api_config)
-                    .configure(handlers::admin::sys_user_handler::api_config)
+                web::scope("/auth")
+                    .configure(handlers::admin::sys_auth_handler::api_config)
+                    .configure(handlers::admin::sys_two_factor_handler::api_config)
+                    .configure(handlers::admin::sys_oidc_handler::api_config)
+                    .wrap(middleware::jwt_auth_middleware::JWTAuth),
+            )
+            .service(
+                web::scope(handlers::admin::SYSTEM_MANAGE_SCOPE)
+                    .configure(handlers::admin::system_manage_config)
+                    .wrap(middleware::permission_check_middleware::PermissionCheck)
                     .wrap(middleware::jwt_auth_middleware::JWTAuth),
             )
             .service(
                 web::scope("/route")
                     .configure(handlers::admin::sys_route_handler::api_config)
+                    .wrap(middleware::jwt_auth_middleware::JWTAuth),
             )
             .wrap(Logger::new("%a %D ms %{User-Agent}i"))
     })
@@ -1,7 +1,18 @@
+pub mod sys_access_services;
+pub mod sys_api_key_services;
+pub mod sys_api_services;
+pub mod sys_auth_services;
+pub mod sys_authorization_services;
+pub mod sys_data_scope_services;
+pub mod sys_dept_services;
+pub mod sys_login_log_services;
 pub mod sys_menu_services;
+pub mod sys_oidc_services;
 pub mod sys_permission_services;
 pub mod sys_role_permission_services;
 pub(crate) mod sys_role_services;
+pub mod sys_two_factor_services;
+pub mod sys_user_permission_services;
 pub mod sys_user_role_services;
 pub mod sys_user_services;
 pub mod sys_route_services;
@@ -0,0 +1,742 @@
+use crate::common::auth::role_hierarchy::RoleHierarchy;
+use crate::common::error::MyError;
+use crate::config::globals;
+use crate::dto::admin::sys_access_dto::{
+    AccessExplainQueryDto, AccessExplanationDto, EffectivePermissionsRespDto, EffectiveTargetDto,
+    GrantChainDto, TargetExplanationDto,
+};
+use crate::schemas::admin::prelude::{
+    SysApi, SysMenu, SysPermission, SysRole, SysRolePermission, SysUser, SysUserPermission,
+    SysUserRole,
+};
+use crate::schemas::admin::sea_orm_active_enums::{
+    ActionCode, ApiMethod, PermissionEffect, TargetType, Type,
+};
+use crate::schemas::admin::{
+    sys_api, sys_menu, sys_permission, sys_permission_action, sys_permission_target, sys_role,
+    sys_role_permission, sys_user_permission, sys_user_role,
+};
+use crate::services::admin::sys_authorization_services::{
+    self, evaluate_subject_grants, get_subject_grants, match_api_path,
+};
+use crate::services::admin::sys_route_services::Status;
+use crate::services::admin::{sys_role_services, sys_user_role_services};
+use actix::Addr;
+use actix_redis::RedisActor;
+use actix_web::http::Method;
+use sea_orm::{
+    ActiveEnum, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+// 权限的状态、效果和允许的操作
+#[derive(Debug, Clone)]
+pub struct PermissionInfo {
+    pub code: String,
+    pub enabled: bool,
+    pub effect: PermissionEffect,
+    pub actions: Vec<ActionCode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrantSource {
+    Role,
+    User,
+}
+
+// 一条从用户到权限的授权链路，blocked_by 不为空时该链路不生效
+#[derive(Debug, Clone)]
+pub struct GrantChain {
+    pub source: GrantSource,
+    pub roles: Vec<String>,
+    pub permission_id: i32,
+    pub permission_code: String,
+    pub effect: PermissionEffect,
+    pub actions: Vec<ActionCode>,
+    pub blocked_by: Option<String>,
+}
+
+impl GrantChain {
+    // 链路的可读描述
+    fn describe(&self) -> String {
+        match self.source {
+            GrantSource::Role => format!(
+                "permission {} via role {}",
+                self.permission_code,
+                self.roles.join(" > ")
+            ),
+            GrantSource::User => {
+                format!("permission {} via direct user grant", self.permission_code)
+            }
+        }
+    }
+}
+
+impl From<&GrantChain> for GrantChainDto {
+    fn from(chain: &GrantChain) -> Self {
+        GrantChainDto {
+            source: match chain.source {
+                GrantSource::Role => "ROLE".to_string(),
+                GrantSource::User => "USER".to_string(),
+            },
+            roles: chain.roles.clone(),
+            permission_id: chain.permission_id,
+            permission_code: chain.permission_code.clone(),
+            effect: chain.effect.as_str().to_string(),
+            actions: chain
+                .actions
+                .iter()
+                .map(|action| action.to_value())
+                .collect(),
+            blocked_by: chain.blocked_by.clone(),
+        }
+    }
+}
+
+// 用户授权相关数据的快照，包含禁用的角色和权限，用于解释授权结果
+#[derive(Debug, Clone, Default)]
+pub struct AccessSnapshot {
+    pub user_enabled: bool,
+    pub is_super: bool,
+    pub user_role_ids: Vec<i32>, // 用户直接分配的角色
+    pub hierarchy: RoleHierarchy,
+    pub role_codes: HashMap<i32, String>,
+    pub role_permissions: HashMap<i32, Vec<i32>>,
+    pub user_permissions: Vec<(i32, PermissionEffect)>, // 用户直接授权及授权本身的效果
+    pub permissions: HashMap<i32, PermissionInfo>,
+}
+
+impl AccessSnapshot {
+    //grant_chains 列出用户经由角色（含继承）和直接授权到达的全部权限链路，包含未生效的链路
+    pub fn grant_chains(&self) -> Vec<GrantChain> {
+        let mut chains = Vec::new();
+        for role_id in &self.user_role_ids {
+            let mut roles = Vec::new();
+            let mut blocked_by = None;
+            for id in self.hierarchy.lineage(*role_id) {
+                let role_code = self
+                    .role_codes
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| id.to_string());
+                roles.push(role_code.clone());
+                // 禁用的角色不授予权限，也不再传递其继承的权限
+                if blocked_by.is_none() && !self.hierarchy.get(id).is_some_and(|node| node.enabled)
+                {
+                    blocked_by = Some(format!("Role {} is disabled", role_code));
+                }
+                for permission_id in self.role_permissions.get(&id).into_iter().flatten() {
+                    chains.extend(self.chain(
+                        GrantSource::Role,
+                        roles.clone(),
+                        *permission_id,
+                        None,
+                        blocked_by.clone(),
+                    ));
+                }
+            }
+        }
+        for (permission_id, effect) in &self.user_permissions {
+            chains.extend(self.chain(
+                GrantSource::User,
+                Vec::new(),
+                *permission_id,
+                Some(effect),
+                None,
+            ));
+        }
+        chains
+    }
+
+    //token_grant_chains 与鉴权一致只采用 token 中的角色，不在 role_codes 内的角色的链路不生效
+    pub fn token_grant_chains(&self, role_codes: &[String]) -> Vec<GrantChain> {
+        self.grant_chains()
+            .into_iter()
+            .map(|mut chain| {
+                if chain.source == GrantSource::Role && chain.blocked_by.is_none() {
+                    // roles 的第一个是用户直接分配的角色
+                    if let Some(role_code) = chain
+                        .roles
+                        .first()
+                        .filter(|code| !role_codes.contains(code))
+                    {
+                        chain.blocked_by =
+                            Some(format!("Role {} is not in the user's token", role_code));
+                    }
+                }
+                chain
+            })
+            .collect()
+    }
+
+    fn chain(
+        &self,
+        source: GrantSource,
+        roles: Vec<String>,
+        permission_id: i32,
+        grant_effect: Option<&PermissionEffect>,
+        blocked_by: Option<String>,
+    ) -> Option<GrantChain> {
+        let permission = self.permissions.get(&permission_id)?;
+        // 用户直接授权与权限本身任一为 DENY 即为拒绝
+        let effect = if grant_effect == Some(&PermissionEffect::Deny) {
+            PermissionEffect::Deny
+        } else {
+            permission.effect.clone()
+        };
+        let blocked_by = blocked_by.or_else(|| {
+            (!permission.enabled).then(|| format!("Permission {} is disabled", permission.code))
+        });
+        Some(GrantChain {
+            source,
+            roles,
+            permission_id,
+            permission_code: permission.code.clone(),
+            effect,
+            actions: permission.actions.clone(),
+            blocked_by,
+        })
+    }
+}
+
+// 对一个目标的授权结论
+#[derive(Debug, Clone, Default)]
+pub struct AccessVerdict {
+    pub allowed: bool,
+    pub granted_by: Vec<GrantChain>,
+    pub denied_by: Vec<GrantChain>,
+    pub blocked: Vec<GrantChain>,
+}
+
+//evaluate_grant_chains 按鉴权规则评估指向同一目标的链路：action 为空时（菜单、按钮）不校验操作，拒绝优先，超级管理员只对接口生效
+pub fn evaluate_grant_chains(
+    chains: Vec<GrantChain>,
+    action: Option<&ActionCode>,
+    is_super: bool,
+) -> AccessVerdict {
+    let mut verdict = AccessVerdict::default();
+    for mut chain in chains {
+        if chain.blocked_by.is_none() {
+            if let Some(action) = action {
+                // 拒绝的权限未配置操作时拒绝全部操作
+                let covers = chain.actions.contains(action)
+                    || (chain.effect == PermissionEffect::Deny && chain.actions.is_empty());
+                if !covers {
+                    chain.blocked_by = Some(format!(
+                        "Permission {} does not include action {}",
+                        chain.permission_code,
+                        action.to_value()
+                    ));
+                }
+            }
+        }
+        match (&chain.blocked_by, &chain.effect) {
+            (Some(_), _) => verdict.blocked.push(chain),
+            (None, PermissionEffect::Deny) => verdict.denied_by.push(chain),
+            (None, PermissionEffect::Allow) => verdict.granted_by.push(chain),
+        }
+    }
+    verdict.allowed = verdict.denied_by.is_empty() && (is_super || !verdict.granted_by.is_empty());
+    verdict
+}
+
+// 解释时匹配到的菜单、按钮或接口
+struct MatchedTarget {
+    kind: &'static str, // MENU、BUTTON 或 API
+    id: i32,
+    name: String,
+    action: Option<ActionCode>,  // 接口鉴权时校验的操作
+    unavailable: Option<String>, // 目标自身不可用的原因
+    constant: bool,              // 常量路由对所有人可见
+}
+
+// 接口的请求方法对应鉴权时校验的操作
+fn action_of_api_method(api_method: &ApiMethod) -> ActionCode {
+    match api_method {
+        ApiMethod::Get => ActionCode::Read,
+        ApiMethod::Post => ActionCode::Create,
+        ApiMethod::Put => ActionCode::Update,
+        ApiMethod::Delete => ActionCode::Delete,
+    }
+}
+
+//load_access_snapshot 加载用户授权相关的数据，包含禁用的角色和权限
+pub async fn load_access_snapshot(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<AccessSnapshot, MyError> {
+    let user = SysUser::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or(MyError::NotFound("User not found".to_string()))?;
+    let user_role_ids: Vec<i32> = SysUserRole::find()
+        .select_only()
+        .column(sys_user_role::Column::RoleId)
+        .filter(sys_user_role::Column::UserId.eq(user_id))
+        .order_by_asc(sys_user_role::Column::RoleId)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let hierarchy = sys_role_services::load_role_hierarchy(db).await?;
+    let role_codes: HashMap<i32, String> = SysRole::find()
+        .select_only()
+        .column(sys_role::Column::Id)
+        .column(sys_role::Column::RoleCode)
+        .into_tuple::<(i32, String)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    // 用户角色及其全部祖先角色的权限，包含禁用的角色
+    let lineage_role_ids: HashSet<i32> = user_role_ids
+        .iter()
+        .flat_map(|role_id| hierarchy.lineage(*role_id))
+        .collect();
+    let mut role_permissions: HashMap<i32, Vec<i32>> = HashMap::new();
+    if !lineage_role_ids.is_empty() {
+        for (role_id, permission_id) in SysRolePermission::find()
+            .select_only()
+            .column(sys_role_permission::Column::RoleId)
+            .column(sys_role_permission::Column::PermissionId)
+            .filter(sys_role_permission::Column::RoleId.is_in(lineage_role_ids))
+            .order_by_asc(sys_role_permission::Column::PermissionId)
+            .into_tuple::<(i32, i32)>()
+            .all(db)
+            .await?
+        {
+            role_permissions
+                .entry(role_id)
+                .or_default()
+                .push(permission_id);
+        }
+    }
+    let user_permissions: Vec<(i32, PermissionEffect)> = SysUserPermission::find()
+        .select_only()
+        .column(sys_user_permission::Column::PermissionId)
+        .column(sys_user_permission::Column::Effect)
+        .filter(sys_user_permission::Column::UserId.eq(user_id))
+        .order_by_asc(sys_user_permission::Column::PermissionId)
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let permission_ids: HashSet<i32> = role_permissions
+        .values()
+        .flatten()
+        .copied()
+        .chain(
+            user_permissions
+                .iter()
+                .map(|(permission_id, _)| *permission_id),
+        )
+        .collect();
+    let permissions = load_permission_infos(db, permission_ids).await?;
+
+    let enabled_role_codes: Vec<String> = user_role_ids
+        .iter()
+        .filter(|role_id| hierarchy.get(**role_id).is_some_and(|node| node.enabled))
+        .filter_map(|role_id| role_codes.get(role_id).cloned())
+        .collect();
+    let is_super = sys_authorization_services::has_enabled_super_role(
+        db,
+        &enabled_role_codes,
+        &globals::APP_CONFIG.permission.super_role_codes,
+    )
+    .await?;
+
+    Ok(AccessSnapshot {
+        user_enabled: user.status == Status::Enable as i8,
+        is_super,
+        user_role_ids,
+        hierarchy,
+        role_codes,
+        role_permissions,
+        user_permissions,
+        permissions,
+    })
+}
+
+// 加载权限的状态、效果和操作
+async fn load_permission_infos(
+    db: &DatabaseConnection,
+    permission_ids: HashSet<i32>,
+) -> Result<HashMap<i32, PermissionInfo>, MyError> {
+    if permission_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let mut actions: HashMap<i32, Vec<ActionCode>> = HashMap::new();
+    for action in sys_permission_action::Entity::find()
+        .filter(sys_permission_action::Column::PermissionId.is_in(permission_ids.clone()))
+        .all(db)
+        .await?
+    {
+        actions
+            .entry(action.permission_id)
+            .or_default()
+            .push(action.action_code);
+    }
+    Ok(SysPermission::find()
+        .filter(sys_permission::Column::Id.is_in(permission_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|permission| {
+            (
+                permission.id,
+                PermissionInfo {
+                    code: permission.permission_code,
+                    enabled: permission.status == Status::Enable as i8,
+                    effect: permission.effect,
+                    actions: actions.remove(&permission.id).unwrap_or_default(),
+                },
+            )
+        })
+        .collect())
+}
+
+// 获取指向给定目标的权限ID，按目标ID分组
+async fn get_target_permission_ids(
+    db: &DatabaseConnection,
+    target_type: TargetType,
+    target_ids: Option<Vec<i32>>,
+    permission_ids: Option<Vec<i32>>,
+) -> Result<BTreeMap<i32, Vec<i32>>, MyError> {
+    let mut condition =
+        Condition::all().add(sys_permission_target::Column::TargetType.eq(target_type));
+    if let Some(target_ids) = target_ids {
+        condition = condition.add(sys_permission_target::Column::TargetId.is_in(target_ids));
+    }
+    if let Some(permission_ids) = permission_ids {
+        condition =
+            condition.add(sys_permission_target::Column::PermissionId.is_in(permission_ids));
+    }
+    let mut targets: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    for target in sys_permission_target::Entity::find()
+        .filter(condition)
+        .all(db)
+        .await?
+    {
+        targets
+            .entry(target.target_id)
+            .or_default()
+            .push(target.permission_id);
+    }
+    Ok(targets)
+}
+
+// 筛选指向目标的链路
+fn chains_for(chains: &[GrantChain], permission_ids: &[i32]) -> Vec<GrantChain> {
+    chains
+        .iter()
+        .filter(|chain| permission_ids.contains(&chain.permission_id))
+        .cloned()
+        .collect()
+}
+
+// 用户 token 中的角色：签发 token 时按用户当前已启用的角色写入，角色变更后 token 会被吊销，
+// 所以有效 token 中的角色与这里读取的一致
+async fn get_token_role_codes(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<String>, MyError> {
+    Ok(sys_user_role_services::get_role_codes_by_user_id(db, user_id).await?)
+}
+
+//get_effective_permissions 获取用户可访问的全部菜单、按钮和接口，以及授予它们的链路，角色与 explain_access 一样取 token 中的角色
+pub async fn get_effective_permissions(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<EffectivePermissionsRespDto, MyError> {
+    let snapshot = load_access_snapshot(db, user_id).await?;
+    let role_codes = get_token_role_codes(db, user_id).await?;
+    let chains = snapshot.token_grant_chains(&role_codes);
+    let permission_ids: Vec<i32> = chains
+        .iter()
+        .map(|chain| chain.permission_id)
+        .collect::<BTreeSet<i32>>()
+        .into_iter()
+        .collect();
+
+    // 菜单和按钮
+    let menu_permissions =
+        get_target_permission_ids(db, TargetType::Menu, None, Some(permission_ids.clone())).await?;
+    let menus = if menu_permissions.is_empty() {
+        Vec::new()
+    } else {
+        SysMenu::find()
+            .filter(sys_menu::Column::Id.is_in(menu_permissions.keys().copied()))
+            .filter(sys_menu::Column::Status.eq(Status::Enable as i8))
+            .order_by_asc(sys_menu::Column::Order)
+            .order_by_asc(sys_menu::Column::Id)
+            .all(db)
+            .await?
+    };
+    let mut menu_dtos = Vec::new();
+    let mut button_dtos = Vec::new();
+    for menu in menus {
+        let verdict = evaluate_grant_chains(
+            chains_for(&chains, &menu_permissions[&menu.id]),
+            None,
+            false,
+        );
+        if !verdict.allowed {
+            continue;
+        }
+        let is_button = menu.r#type == Type::Button;
+        let dto = EffectiveTargetDto {
+            id: menu.id,
+            name: menu.menu_name.unwrap_or_default(),
+            path: if is_button {
+                menu.route_name.unwrap_or_default()
+            } else {
+                menu.route_path.unwrap_or_default()
+            },
+            method: None,
+            actions: Vec::new(),
+            grants: verdict.granted_by.iter().map(GrantChainDto::from).collect(),
+        };
+        if is_button {
+            button_dtos.push(dto);
+        } else {
+            menu_dtos.push(dto);
+        }
+    }
+
+    // 接口，超级管理员可访问全部未被拒绝的接口
+    let api_permissions =
+        get_target_permission_ids(db, TargetType::ApiGroup, None, Some(permission_ids)).await?;
+    let mut api_query = SysApi::find();
+    if !snapshot.is_super {
+        api_query = api_query.filter(sys_api::Column::Id.is_in(api_permissions.keys().copied()));
+    }
+    let apis = api_query
+        .order_by_asc(sys_api::Column::ApiPath)
+        .order_by_asc(sys_api::Column::Id)
+        .all(db)
+        .await?;
+    let mut api_dtos = Vec::new();
+    for api in apis {
+        let action = action_of_api_method(&api.api_method);
+        let api_chains = api_permissions
+            .get(&api.id)
+            .map(|permission_ids| chains_for(&chains, permission_ids))
+            .unwrap_or_default();
+        let verdict = evaluate_grant_chains(api_chains, Some(&action), snapshot.is_super);
+        if !verdict.allowed {
+            continue;
+        }
+        api_dtos.push(EffectiveTargetDto {
+            id: api.id,
+            name: api.api_name,
+            path: api.api_path,
+            method: Some(api.api_method.as_str().to_string()),
+            actions: vec![action.to_value()],
+            grants: verdict.granted_by.iter().map(GrantChainDto::from).collect(),
+        });
+    }
+
+    Ok(EffectivePermissionsRespDto {
+        user_id,
+        user_enabled: snapshot.user_enabled,
+        super_admin: snapshot.is_super,
+        menus: menu_dtos,
+        buttons: button_dtos,
+        apis: api_dtos,
+    })
+}
+
+//explain_access 解释用户能否访问某个接口（给出 method 时）或菜单、按钮，并指出缺失或被禁用的环节
+//角色取 token 中的角色，接口的结论与鉴权中间件使用同一套授权数据和判断
+pub async fn explain_access(
+    db: &DatabaseConnection,
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+    query: AccessExplainQueryDto,
+) -> Result<AccessExplanationDto, MyError> {
+    let snapshot = load_access_snapshot(db, user_id).await?;
+    let role_codes = get_token_role_codes(db, user_id).await?;
+    let chains = snapshot.token_grant_chains(&role_codes);
+    let mut reasons = Vec::new();
+    if !snapshot.user_enabled {
+        reasons.push("User is disabled".to_string());
+    }
+    if snapshot.user_role_ids.is_empty() && snapshot.user_permissions.is_empty() {
+        reasons.push("User has no roles or direct permissions".to_string());
+    }
+
+    // 匹配请求对应的目标，接口按鉴权的规则得出结论
+    let mut targets: Vec<MatchedTarget> = Vec::new();
+    let mut enforced = None;
+    let mut is_super = false;
+    let target_type = match &query.method {
+        Some(method) => {
+            let api_method = ApiMethod::from_string(&method.to_uppercase())?;
+            let action = action_of_api_method(&api_method);
+            let subjects = get_subject_grants(db, redis, user_id, &role_codes).await?;
+            is_super = subjects.iter().any(|subject| subject.super_role);
+            let method = Method::from_bytes(api_method.as_str().as_bytes())
+                .map_err(|e| MyError::InvalidTypeError(e.to_string()))?;
+            enforced = Some(evaluate_subject_grants(&subjects, &method, &query.path));
+            for api in SysApi::find()
+                .filter(sys_api::Column::ApiMethod.eq(api_method.clone()))
+                .all(db)
+                .await?
+                .into_iter()
+                .filter(|api| match_api_path(&api.api_path, &query.path))
+            {
+                targets.push(MatchedTarget {
+                    kind: "API",
+                    id: api.id,
+                    name: api.api_name,
+                    action: Some(action.clone()),
+                    unavailable: None,
+                    constant: false,
+                });
+            }
+            if targets.is_empty() {
+                reasons.push(format!(
+                    "No API is registered for {} {}",
+                    api_method.as_str(),
+                    query.path
+                ));
+            }
+            TargetType::ApiGroup
+        }
+        None => {
+            for menu in SysMenu::find()
+                .filter(
+                    Condition::any()
+                        .add(sys_menu::Column::RoutePath.eq(query.path.as_str()))
+                        .add(sys_menu::Column::RouteName.eq(query.path.as_str())),
+                )
+                .all(db)
+                .await?
+            {
+                let name = menu.menu_name.unwrap_or_default();
+                let unavailable = if menu.status != Status::Enable as i8 {
+                    Some(format!("Menu {} is disabled", name))
+                } else {
+                    None
+                };
+                let target_type = if menu.r#type == Type::Button {
+                    "BUTTON"
+                } else {
+                    "MENU"
+                };
+                targets.push(MatchedTarget {
+                    kind: target_type,
+                    id: menu.id,
+                    name,
+                    action: None,
+                    unavailable,
+                    constant: menu.constant != 0,
+                });
+            }
+            if targets.is_empty() {
+                reasons.push(format!("No menu or button matches {}", query.path));
+            }
+            TargetType::Menu
+        }
+    };
+
+    let target_permissions = get_target_permission_ids(
+        db,
+        target_type.clone(),
+        Some(targets.iter().map(|target| target.id).collect()),
+        None,
+    )
+    .await?;
+    let reachable: HashSet<i32> = chains.iter().map(|chain| chain.permission_id).collect();
+    let unassigned_ids: HashSet<i32> = target_permissions
+        .values()
+        .flatten()
+        .filter(|permission_id| !reachable.contains(permission_id))
+        .copied()
+        .collect();
+    let unassigned_codes: HashMap<i32, String> = load_permission_infos(db, unassigned_ids)
+        .await?
+        .into_iter()
+        .map(|(id, permission)| (id, permission.code))
+        .collect();
+
+    let is_api = target_type == TargetType::ApiGroup;
+    let mut explanations = Vec::new();
+    for target in targets {
+        let MatchedTarget {
+            kind,
+            id,
+            name,
+            action,
+            unavailable,
+            constant,
+        } = target;
+        let permission_ids = target_permissions.get(&id).cloned().unwrap_or_default();
+        let mut verdict = evaluate_grant_chains(
+            chains_for(&chains, &permission_ids),
+            action.as_ref(),
+            is_api && is_super,
+        );
+        if let Some(unavailable) = unavailable {
+            verdict.allowed = false;
+            reasons.push(unavailable);
+        } else if constant && verdict.denied_by.is_empty() {
+            // 常量路由无需授权
+            verdict.allowed = true;
+            reasons.push(format!(
+                "Menu {} is a constant route visible to everyone",
+                name
+            ));
+        }
+        let unassigned_permissions: Vec<String> = permission_ids
+            .iter()
+            .filter_map(|permission_id| unassigned_codes.get(permission_id).cloned())
+            .collect();
+
+        if permission_ids.is_empty() && !constant {
+            reasons.push(format!("No permission is linked to {}", name));
+        }
+        for chain in &verdict.denied_by {
+            reasons.push(format!("{} is denied by {}", name, chain.describe()));
+        }
+        if verdict.granted_by.is_empty() {
+            for chain in &verdict.blocked {
+                if let Some(blocked_by) = &chain.blocked_by {
+                    reasons.push(format!("{} ({})", blocked_by, chain.describe()));
+                }
+            }
+            for code in &unassigned_permissions {
+                reasons.push(format!(
+                    "Permission {} grants {} but is not assigned to the user or the user's roles",
+                    code, name
+                ));
+            }
+            if verdict.allowed && is_api && is_super {
+                reasons.push(format!("{} is allowed by the super admin role", name));
+            }
+        }
+        explanations.push(TargetExplanationDto {
+            target_type: kind.to_string(),
+            id,
+            name,
+            allowed: verdict.allowed,
+            granted_by: verdict.granted_by.iter().map(GrantChainDto::from).collect(),
+            denied_by: verdict.denied_by.iter().map(GrantChainDto::from).collect(),
+            blocked: verdict.blocked.iter().map(GrantChainDto::from).collect(),
+            unassigned_permissions,
+        });
+    }
+
+    // 接口直接采用鉴权的结论，菜单和按钮任一可见即可见
+    let allowed = snapshot.user_enabled
+        && match enforced {
+            Some(enforced) => enforced,
+            None => explanations.iter().any(|target| target.allowed),
+        };
+    Ok(AccessExplanationDto {
+        allowed,
+        reasons,
+        targets: explanations,
+    })
+}
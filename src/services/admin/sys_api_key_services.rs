@@ -0,0 +1,283 @@
+use crate::common::auth::api_key;
+use crate::common::error::MyError;
+use crate::config::globals;
+use crate::dto::admin::common_dto::PaginationResponseDto;
+use crate::dto::admin::sys_api_key_dto::{
+    ApiKeyCreateDto, ApiKeyCreateRespDto, ApiKeyDto, ApiKeyQueryDto,
+};
+use crate::schemas::admin::prelude::{SysApiKey, SysApiKeyPermission};
+use crate::schemas::admin::{sys_api_key, sys_api_key_permission, sys_user};
+use crate::services::admin::sys_route_services::Status;
+use crate::services::admin::{
+    sys_authorization_services, sys_user_role_services, sys_user_services,
+};
+use chrono::{Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+const LAST_USED_IP_MAX_LEN: usize = 64;
+const ALLOWED_IPS_MAX_LEN: usize = 1024;
+
+// 通过 API Key 认证得到的身份
+#[derive(Debug, Clone)]
+pub struct ApiKeyIdentity {
+    pub api_key: sys_api_key::Model,
+    pub user: sys_user::Model,
+    pub role_codes: Vec<String>,
+    pub permission_ids: Vec<i32>,
+}
+
+fn invalid_api_key() -> MyError {
+    MyError::AuthError("Invalid API key".to_string())
+}
+
+// 校验并规范化 IP 白名单，返回逗号分隔的字符串
+fn normalize_allowed_ips(allowed_ips: Option<Vec<String>>) -> Result<Option<String>, MyError> {
+    let allowed_ips: Vec<String> = allowed_ips
+        .unwrap_or_default()
+        .into_iter()
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty())
+        .collect();
+    if allowed_ips.is_empty() {
+        return Ok(None);
+    }
+    for ip in &allowed_ips {
+        if api_key::IpRule::parse(ip).is_none() {
+            return Err(MyError::BadRequestError(format!(
+                "Invalid IP or CIDR: {}",
+                ip
+            )));
+        }
+    }
+    let allowed_ips = allowed_ips.join(",");
+    if allowed_ips.len() > ALLOWED_IPS_MAX_LEN {
+        return Err(MyError::BadRequestError(
+            "IP allowlist is too long".to_string(),
+        ));
+    }
+    Ok(Some(allowed_ips))
+}
+
+// 获取 API Key 的权限ID
+async fn get_permission_ids_by_api_key_ids(
+    db: &DatabaseConnection,
+    api_key_ids: Vec<i32>,
+) -> Result<HashMap<i32, Vec<i32>>, MyError> {
+    let mut permission_ids: HashMap<i32, Vec<i32>> = HashMap::new();
+    if api_key_ids.is_empty() {
+        return Ok(permission_ids);
+    }
+    for key_permission in SysApiKeyPermission::find()
+        .filter(sys_api_key_permission::Column::ApiKeyId.is_in(api_key_ids))
+        .all(db)
+        .await?
+    {
+        permission_ids
+            .entry(key_permission.api_key_id)
+            .or_default()
+            .push(key_permission.permission_id);
+    }
+    Ok(permission_ids)
+}
+
+//create_api_key 为当前用户创建 API Key，权限只能是用户当前拥有的权限的子集
+pub async fn create_api_key(
+    db: &DatabaseConnection,
+    user_id: i32,
+    user_name: String,
+    role_codes: &[String],
+    create_dto: ApiKeyCreateDto,
+) -> Result<ApiKeyCreateRespDto, MyError> {
+    let config = &globals::APP_CONFIG.api_key;
+    let expire_days = create_dto.expire_days.unwrap_or(config.default_expire_days);
+    if expire_days > config.max_expire_days {
+        return Err(MyError::BadRequestError(format!(
+            "API key expiry cannot exceed {} days",
+            config.max_expire_days
+        )));
+    }
+    let allowed_ips = normalize_allowed_ips(create_dto.allowed_ips)?;
+
+    let grantable: HashSet<i32> =
+        sys_authorization_services::get_grantable_permission_ids(db, user_id, role_codes)
+            .await?
+            .into_iter()
+            .collect();
+    let mut permission_ids = create_dto.permission_ids;
+    permission_ids.sort_unstable();
+    permission_ids.dedup();
+    if let Some(id) = permission_ids.iter().find(|id| !grantable.contains(id)) {
+        return Err(MyError::PermissionDeniedError(format!(
+            "Permission {} is not granted to the current user",
+            id
+        )));
+    }
+
+    let active_keys = SysApiKey::find()
+        .filter(sys_api_key::Column::UserId.eq(user_id))
+        .filter(sys_api_key::Column::Revoked.eq(0))
+        .filter(sys_api_key::Column::ExpireTime.gt(Utc::now()))
+        .count(db)
+        .await?;
+    if active_keys >= config.max_keys_per_user {
+        return Err(MyError::BadRequestError(format!(
+            "A user can have at most {} active API keys",
+            config.max_keys_per_user
+        )));
+    }
+
+    let (key, key_prefix) = api_key::generate_api_key(&config.prefix);
+    let txn = db.begin().await?;
+    let model = sys_api_key::ActiveModel {
+        user_id: Set(user_id),
+        name: Set(create_dto.name.trim().to_string()),
+        key_prefix: Set(key_prefix),
+        key_hash: Set(api_key::hash_api_key(&key)),
+        allowed_ips: Set(allowed_ips),
+        expire_time: Set(Some(Utc::now() + Duration::days(i64::from(expire_days)))),
+        revoked: Set(0),
+        create_user: Set(user_name),
+        create_time: Set(Some(Utc::now())),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    let key_permissions: Vec<sys_api_key_permission::ActiveModel> = permission_ids
+        .iter()
+        .map(|&permission_id| sys_api_key_permission::ActiveModel {
+            api_key_id: Set(model.id),
+            permission_id: Set(permission_id),
+            ..Default::default()
+        })
+        .collect();
+    SysApiKeyPermission::insert_many(key_permissions)
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+
+    Ok(ApiKeyCreateRespDto {
+        base: ApiKeyDto::from_model(model, permission_ids),
+        key,
+    })
+}
+
+//get_paginated_api_keys 分页获取用户的 API Key，按创建时间倒序
+pub async fn get_paginated_api_keys(
+    db: &DatabaseConnection,
+    user_id: i32,
+    query_dto: ApiKeyQueryDto,
+) -> Result<PaginationResponseDto<ApiKeyDto>, MyError> {
+    let current = query_dto.current.unwrap_or(1);
+    let size = query_dto.size.unwrap_or(10);
+
+    let mut query = SysApiKey::find().filter(sys_api_key::Column::UserId.eq(user_id));
+    if let Some(revoked) = query_dto.revoked {
+        query = query.filter(sys_api_key::Column::Revoked.eq(revoked as i8));
+    }
+
+    let total = query.clone().count(db).await?;
+    let api_keys = query
+        .order_by_desc(sys_api_key::Column::CreateTime)
+        .order_by_desc(sys_api_key::Column::Id)
+        .limit(size)
+        .offset(current.saturating_sub(1) * size)
+        .all(db)
+        .await?;
+    let mut permission_ids =
+        get_permission_ids_by_api_key_ids(db, api_keys.iter().map(|key| key.id).collect()).await?;
+    let records = api_keys
+        .into_iter()
+        .map(|key| {
+            let ids = permission_ids.remove(&key.id).unwrap_or_default();
+            ApiKeyDto::from_model(key, ids)
+        })
+        .collect();
+
+    Ok(PaginationResponseDto::new(current, size, total, records))
+}
+
+//revoke_api_key 吊销用户的 API Key，返回影响的行数
+pub async fn revoke_api_key(
+    db: &DatabaseConnection,
+    user_id: i32,
+    api_key_id: i32,
+    update_user: String,
+) -> Result<u64, MyError> {
+    let result = SysApiKey::update_many()
+        .col_expr(sys_api_key::Column::Revoked, Expr::value(1))
+        .col_expr(sys_api_key::Column::UpdateUser, Expr::value(update_user))
+        .col_expr(sys_api_key::Column::UpdateTime, Expr::value(Utc::now()))
+        .filter(sys_api_key::Column::Id.eq(api_key_id))
+        .filter(sys_api_key::Column::UserId.eq(user_id))
+        .filter(sys_api_key::Column::Revoked.eq(0))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+//authenticate_api_key 校验 API Key 的哈希、有效期、吊销状态、IP 白名单和所属用户状态
+pub async fn authenticate_api_key(
+    db: &DatabaseConnection,
+    key: &str,
+    client_ip: Option<IpAddr>,
+) -> Result<ApiKeyIdentity, MyError> {
+    let config = &globals::APP_CONFIG.api_key;
+    let key_prefix = api_key::split_key_prefix(key, &config.prefix).ok_or_else(invalid_api_key)?;
+    let model = SysApiKey::find()
+        .filter(sys_api_key::Column::KeyPrefix.eq(key_prefix))
+        .one(db)
+        .await?
+        .filter(|model| api_key::verify_api_key(key, &model.key_hash))
+        .ok_or_else(invalid_api_key)?;
+
+    if model.revoked != 0 {
+        return Err(MyError::AuthError("API key revoked".to_string()));
+    }
+    if model.expire_time.is_none_or(|time| time <= Utc::now()) {
+        return Err(MyError::AuthError("API key expired".to_string()));
+    }
+    if !api_key::is_ip_allowed(model.allowed_ips.as_deref(), client_ip) {
+        return Err(MyError::AuthError(
+            "Client IP is not allowed for this API key".to_string(),
+        ));
+    }
+
+    let user = sys_user_services::get_user_by_id_pure(db, model.user_id)
+        .await?
+        .filter(|user| user.status == Status::Enable as i8)
+        .ok_or(MyError::AuthError("User disabled".to_string()))?;
+    let role_codes = sys_user_role_services::get_role_codes_by_user_id(db, user.id).await?;
+    let permission_ids = get_permission_ids_by_api_key_ids(db, vec![model.id])
+        .await?
+        .remove(&model.id)
+        .unwrap_or_default();
+
+    SysApiKey::update_many()
+        .col_expr(sys_api_key::Column::LastUsedTime, Expr::value(Utc::now()))
+        .col_expr(
+            sys_api_key::Column::LastUsedIp,
+            Expr::value(client_ip.map(|ip| {
+                ip.to_string()
+                    .chars()
+                    .take(LAST_USED_IP_MAX_LEN)
+                    .collect::<String>()
+            })),
+        )
+        .filter(sys_api_key::Column::Id.eq(model.id))
+        .exec(db)
+        .await?;
+
+    Ok(ApiKeyIdentity {
+        api_key: model,
+        user,
+        role_codes,
+        permission_ids,
+    })
+}
@@ -0,0 +1,243 @@
+use crate::common::api_route::ApiRoute;
+use crate::common::error::MyError;
+use crate::dto::admin::common_dto::PaginationResponseDto;
+use crate::dto::admin::sys_api_dto::{ApiCreateDto, ApiDto, ApiSyncDiffDto, ApiUpdateDto};
+use crate::schemas::admin::prelude::SysApi;
+use crate::schemas::admin::sea_orm_active_enums::{ApiMethod, TargetType};
+use crate::schemas::admin::{sys_api, sys_permission_target};
+use crate::services::admin::sys_authorization_services;
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+
+//create_api 创建接口
+pub async fn create_api(
+    db: &DatabaseConnection,
+    api_create_req: ApiCreateDto,
+    create_user: String,
+) -> Result<sys_api::Model, MyError> {
+    let api = sys_api::ActiveModel {
+        api_name: Set(api_create_req.api_name),
+        api_group: Set(api_create_req.api_group),
+        api_path: Set(api_create_req.api_path),
+        api_method: Set(ApiMethod::from_string(&api_create_req.api_method)?),
+        description: Set(api_create_req.description),
+        create_user: Set(create_user),
+        create_time: Set(Some(Utc::now())),
+        ..Default::default()
+    };
+    Ok(api.insert(db).await?)
+}
+
+//get_paginated_apis 分页获取接口列表，可按分组和请求方法过滤
+pub async fn get_paginated_apis(
+    db: &DatabaseConnection,
+    current: u64,
+    size: u64,
+    api_group: Option<String>,
+    api_method: Option<String>,
+) -> Result<PaginationResponseDto<ApiDto>, MyError> {
+    let mut query = SysApi::find();
+    if let Some(api_group) = api_group.filter(|group| !group.is_empty()) {
+        query = query.filter(sys_api::Column::ApiGroup.eq(api_group));
+    }
+    if let Some(api_method) = api_method.filter(|method| !method.is_empty()) {
+        query = query.filter(sys_api::Column::ApiMethod.eq(ApiMethod::from_string(&api_method)?));
+    }
+
+    let total = query.clone().count(db).await?;
+    let apis = query
+        .order_by_asc(sys_api::Column::ApiGroup)
+        .order_by_asc(sys_api::Column::ApiPath)
+        .limit(size)
+        .offset(current.saturating_sub(1) * size)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(ApiDto::from)
+        .collect();
+
+    Ok(PaginationResponseDto::new(current, size, total, apis))
+}
+
+//get_api_by_id 获取单个接口
+pub async fn get_api_by_id(
+    db: &DatabaseConnection,
+    api_id: i32,
+) -> Result<Option<sys_api::Model>, MyError> {
+    Ok(SysApi::find_by_id(api_id).one(db).await?)
+}
+
+//update_api 更新接口
+pub async fn update_api(
+    db: &DatabaseConnection,
+    api_id: i32,
+    api_update_req: ApiUpdateDto,
+    update_user: String,
+) -> Result<sys_api::Model, MyError> {
+    let api = SysApi::find_by_id(api_id)
+        .one(db)
+        .await?
+        .ok_or(MyError::NotFound("Api not found".to_string()))?;
+
+    let mut active_api: sys_api::ActiveModel = api.into();
+    if let Some(api_name) = api_update_req.api_name {
+        active_api.api_name = Set(api_name);
+    }
+    if let Some(api_group) = api_update_req.api_group {
+        active_api.api_group = Set(api_group);
+    }
+    if let Some(api_path) = api_update_req.api_path {
+        active_api.api_path = Set(api_path);
+    }
+    if let Some(api_method) = api_update_req.api_method {
+        active_api.api_method = Set(ApiMethod::from_string(&api_method)?);
+    }
+    if let Some(description) = api_update_req.description {
+        active_api.description = Set(Some(description));
+    }
+    active_api.update_user = Set(Some(update_user));
+    active_api.update_time = Set(Some(Utc::now()));
+
+    let updated_api = active_api.update(db).await?;
+    // 路径或请求方法可能变化，角色授权需要重新计算
+    sys_authorization_services::invalidate_permission_cache().await;
+    Ok(updated_api)
+}
+
+//delete_apis 删除接口及其权限关联
+pub async fn delete_apis(db: &DatabaseConnection, api_ids: Vec<i32>) -> Result<u64, MyError> {
+    let txn = db.begin().await?;
+
+    sys_permission_target::Entity::delete_many()
+        .filter(sys_permission_target::Column::TargetId.is_in(api_ids.clone()))
+        .filter(sys_permission_target::Column::TargetType.eq(TargetType::ApiGroup))
+        .exec(&txn)
+        .await?;
+
+    let rows_affected = SysApi::delete_many()
+        .filter(sys_api::Column::Id.is_in(api_ids))
+        .exec(&txn)
+        .await?
+        .rows_affected;
+
+    txn.commit().await?;
+    sys_authorization_services::invalidate_permission_cache().await;
+    Ok(rows_affected)
+}
+
+//delete_api 删除单个接口
+pub async fn delete_api(db: &DatabaseConnection, api_id: i32) -> Result<u64, MyError> {
+    delete_apis(db, vec![api_id]).await
+}
+
+//diff_api_routes 对比路由清单和 sys_api，只有 scope 下的接口会被标记为废弃
+pub fn diff_api_routes(
+    existing: &[sys_api::Model],
+    routes: &[ApiRoute],
+    scope: &str,
+) -> ApiSyncDiffDto {
+    let existing_map: HashMap<(&str, &str), &sys_api::Model> = existing
+        .iter()
+        .map(|api| ((api.api_path.as_str(), api.api_method.as_str()), api))
+        .collect();
+
+    let mut diff = ApiSyncDiffDto::default();
+    let mut route_keys = HashSet::new();
+    for route in routes {
+        let key = (route.api_path.as_str(), route.api_method.as_str());
+        if !route_keys.insert(key) {
+            continue;
+        }
+        match existing_map.get(&key) {
+            None => diff.created.push(route.clone()),
+            Some(api) if api.deprecated == 1 => diff.restored.push(route.clone()),
+            Some(api) if api.api_group != route.api_group => diff.regrouped.push(route.clone()),
+            Some(_) => {}
+        }
+    }
+
+    let scope_prefix = format!("{}/", scope);
+    diff.deprecated = existing
+        .iter()
+        .filter(|api| api.deprecated == 0 && api.api_path.starts_with(&scope_prefix))
+        .filter(|api| !route_keys.contains(&(api.api_path.as_str(), api.api_method.as_str())))
+        .cloned()
+        .map(ApiDto::from)
+        .collect();
+    diff
+}
+
+//sync_api_routes 将路由清单同步到 sys_api，dry_run 时只返回差异不落库
+pub async fn sync_api_routes(
+    db: &DatabaseConnection,
+    routes: Vec<ApiRoute>,
+    scope: &str,
+    dry_run: bool,
+    operator: String,
+) -> Result<ApiSyncDiffDto, MyError> {
+    let existing = SysApi::find().all(db).await?;
+    let diff = diff_api_routes(&existing, &routes, scope);
+    if dry_run {
+        return Ok(diff);
+    }
+
+    let existing_ids: HashMap<(&str, &str), i32> = existing
+        .iter()
+        .map(|api| ((api.api_path.as_str(), api.api_method.as_str()), api.id))
+        .collect();
+    let txn = db.begin().await?;
+
+    for route in &diff.created {
+        sys_api::ActiveModel {
+            api_name: Set(route.api_name.clone()),
+            api_group: Set(route.api_group.clone()),
+            api_path: Set(route.api_path.clone()),
+            api_method: Set(ApiMethod::from_string(&route.api_method)?),
+            create_user: Set(operator.clone()),
+            create_time: Set(Some(Utc::now())),
+            deprecated: Set(0),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+    }
+
+    // 重新出现或分组变化的路由，只更新分组和废弃标记，保留手工维护的名称和描述
+    for route in diff.restored.iter().chain(diff.regrouped.iter()) {
+        let Some(&api_id) = existing_ids.get(&(route.api_path.as_str(), route.api_method.as_str()))
+        else {
+            continue;
+        };
+        sys_api::ActiveModel {
+            id: Set(api_id),
+            api_group: Set(route.api_group.clone()),
+            deprecated: Set(0),
+            update_user: Set(Some(operator.clone())),
+            update_time: Set(Some(Utc::now())),
+            ..Default::default()
+        }
+        .update(&txn)
+        .await?;
+    }
+
+    let deprecated_ids: Vec<i32> = diff.deprecated.iter().map(|api| api.id).collect();
+    if !deprecated_ids.is_empty() {
+        SysApi::update_many()
+            .col_expr(sys_api::Column::Deprecated, Expr::value(1))
+            .col_expr(sys_api::Column::UpdateUser, Expr::value(operator.clone()))
+            .col_expr(sys_api::Column::UpdateTime, Expr::value(Utc::now()))
+            .filter(sys_api::Column::Id.is_in(deprecated_ids))
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await?;
+    sys_authorization_services::invalidate_permission_cache().await;
+    Ok(diff)
+}
@@ -0,0 +1,440 @@
+use crate::common::auth::crypto::generate_random_token;
+use crate::common::auth::jwt::{now_timestamp, now_timestamp_millis, Claims};
+use crate::common::error::MyError;
+use crate::common::mail::{Mail, MailSender};
+use crate::common::redis::utils::RedisOps;
+use crate::config::cfg::SecurityConfig;
+use crate::config::globals;
+use crate::schemas::admin::sys_user;
+use actix::Addr;
+use actix_redis::RedisActor;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+const REFRESH_TOKEN_KEY_PREFIX: &str = "auth:refresh_token:";
+const REFRESH_FAMILY_KEY_PREFIX: &str = "auth:refresh_family:";
+const ACCESS_TOKEN_DENYLIST_KEY_PREFIX: &str = "auth:jwt_denylist:";
+const USER_REVOKED_AT_KEY_PREFIX: &str = "auth:user_revoked_at:";
+const LOGIN_FAILURE_KEY_PREFIX: &str = "auth:login_failure:";
+const LOGIN_LOCK_KEY_PREFIX: &str = "auth:login_lock:";
+const PASSWORD_RESET_KEY_PREFIX: &str = "auth:password_reset:";
+const PASSWORD_RESET_USER_KEY_PREFIX: &str = "auth:password_reset_user:";
+const PASSWORD_RESET_COOLDOWN_KEY_PREFIX: &str = "auth:password_reset_cooldown:";
+const LOGIN_SUBJECT_USER: &str = "user";
+const LOGIN_SUBJECT_IP: &str = "ip";
+const REFRESH_TOKEN_LENGTH: usize = 64;
+const FAMILY_ID_LENGTH: usize = 32;
+const PASSWORD_RESET_TOKEN_LENGTH: usize = 64;
+
+// 保存在 Redis 中的 refresh token 信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshTokenInfo {
+    pub user_id: i32,
+    pub user_name: String,
+    pub family_id: String, // 同一次登录轮换出来的 token 属于同一个 family
+    #[serde(default)]
+    pub issued_at_ms: u64, // family 的创建时间（毫秒），即登录时间
+}
+
+fn refresh_token_key(token: &str) -> String {
+    format!("{}{}", REFRESH_TOKEN_KEY_PREFIX, token)
+}
+
+fn refresh_family_key(family_id: &str) -> String {
+    format!("{}{}", REFRESH_FAMILY_KEY_PREFIX, family_id)
+}
+
+fn access_token_denylist_key(jti: &str) -> String {
+    format!("{}{}", ACCESS_TOKEN_DENYLIST_KEY_PREFIX, jti)
+}
+
+fn user_revoked_at_key(user_id: i32) -> String {
+    format!("{}{}", USER_REVOKED_AT_KEY_PREFIX, user_id)
+}
+
+fn login_failure_key(subject: &str, value: &str) -> String {
+    format!("{}{}:{}", LOGIN_FAILURE_KEY_PREFIX, subject, value)
+}
+
+fn login_lock_key(subject: &str, value: &str) -> String {
+    format!("{}{}:{}", LOGIN_LOCK_KEY_PREFIX, subject, value)
+}
+
+fn password_reset_key(token: &str) -> String {
+    format!("{}{}", PASSWORD_RESET_KEY_PREFIX, token)
+}
+
+fn password_reset_user_key(user_id: i32) -> String {
+    format!("{}{}", PASSWORD_RESET_USER_KEY_PREFIX, user_id)
+}
+
+fn password_reset_cooldown_key(email: &str) -> String {
+    format!(
+        "{}{}",
+        PASSWORD_RESET_COOLDOWN_KEY_PREFIX,
+        email.trim().to_lowercase()
+    )
+}
+
+async fn get_refresh_token_info(
+    redis: &Addr<RedisActor>,
+    refresh_token: &str,
+) -> Result<Option<RefreshTokenInfo>, MyError> {
+    match RedisOps::get(redis, &refresh_token_key(refresh_token)).await? {
+        Some(value) => serde_json::from_str(&value)
+            .map(Some)
+            .map_err(|_| MyError::AuthError("Invalid refresh token".to_string())),
+        None => Ok(None),
+    }
+}
+
+// 用户最近一次被吊销全部 token 的时间（毫秒），在此之前签发的 token 都无效
+async fn get_user_revoked_at(
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+) -> Result<Option<u64>, MyError> {
+    Ok(RedisOps::get(redis, &user_revoked_at_key(user_id))
+        .await?
+        .and_then(|value| value.parse().ok()))
+}
+
+// 保存 token 记录，并把 family 的当前有效 token 指向它
+async fn store_refresh_token(
+    redis: &Addr<RedisActor>,
+    info: &RefreshTokenInfo,
+    token: &str,
+) -> Result<(), MyError> {
+    let ttl = globals::APP_CONFIG.jwt.refresh_expire_time;
+    let value = serde_json::to_string(info).map_err(|e| MyError::RedisError(e.to_string()))?;
+    RedisOps::set_ex(redis, &refresh_token_key(token), &value, ttl).await?;
+    RedisOps::expire(redis, &refresh_family_key(&info.family_id), ttl).await?;
+    Ok(())
+}
+
+//issue_refresh_token 登录时签发新的 refresh token（开启一个新的 family）
+pub async fn issue_refresh_token(
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+    user_name: String,
+) -> Result<String, MyError> {
+    let info = RefreshTokenInfo {
+        user_id,
+        user_name,
+        family_id: generate_random_token(FAMILY_ID_LENGTH),
+        issued_at_ms: now_timestamp_millis(),
+    };
+    let token = generate_random_token(REFRESH_TOKEN_LENGTH);
+    RedisOps::set_ex(
+        redis,
+        &refresh_family_key(&info.family_id),
+        &token,
+        globals::APP_CONFIG.jwt.refresh_expire_time,
+    )
+    .await?;
+    store_refresh_token(redis, &info, &token).await?;
+    Ok(token)
+}
+
+//rotate_refresh_token 用旧的 refresh token 换取新的 refresh token
+// 旧 token 被再次使用时视为泄露，整个 family 都会被吊销
+pub async fn rotate_refresh_token(
+    redis: &Addr<RedisActor>,
+    refresh_token: &str,
+) -> Result<(RefreshTokenInfo, String), MyError> {
+    let info = get_refresh_token_info(redis, refresh_token)
+        .await?
+        .ok_or(MyError::AuthError("Invalid refresh token".to_string()))?;
+
+    if let Some(revoked_at) = get_user_revoked_at(redis, info.user_id).await? {
+        if info.issued_at_ms < revoked_at {
+            revoke_token_family(redis, &info.family_id).await?;
+            return Err(MyError::AuthError(
+                "Refresh token has been revoked".to_string(),
+            ));
+        }
+    }
+
+    // 原子地替换 family 当前的 token，并拿到替换前的值
+    let new_token = generate_random_token(REFRESH_TOKEN_LENGTH);
+    let current = RedisOps::getset(redis, &refresh_family_key(&info.family_id), &new_token).await?;
+    if current.as_deref() != Some(refresh_token) {
+        warn!(
+            "refresh token reuse detected, revoking token family of user: {}",
+            info.user_name
+        );
+        revoke_token_family(redis, &info.family_id).await?;
+        return Err(MyError::AuthError(
+            "Refresh token has been revoked".to_string(),
+        ));
+    }
+
+    store_refresh_token(redis, &info, &new_token).await?;
+    Ok((info, new_token))
+}
+
+//revoke_token_family 吊销整个 token family
+pub async fn revoke_token_family(redis: &Addr<RedisActor>, family_id: &str) -> Result<(), MyError> {
+    RedisOps::del(redis, &refresh_family_key(family_id)).await?;
+    Ok(())
+}
+
+//revoke_refresh_token 吊销 refresh token 所在的 family
+pub async fn revoke_refresh_token(
+    redis: &Addr<RedisActor>,
+    refresh_token: &str,
+) -> Result<(), MyError> {
+    if let Some(info) = get_refresh_token_info(redis, refresh_token).await? {
+        revoke_token_family(redis, &info.family_id).await?;
+    }
+    Ok(())
+}
+
+//revoke_access_token 将 access token 加入黑名单，直到它自然过期
+pub async fn revoke_access_token(redis: &Addr<RedisActor>, claims: &Claims) -> Result<(), MyError> {
+    let ttl = claims.exp.saturating_sub(now_timestamp());
+    if ttl == 0 {
+        return Ok(());
+    }
+    RedisOps::set_ex(redis, &access_token_denylist_key(&claims.jti), "1", ttl as u64).await?;
+    Ok(())
+}
+
+//revoke_user_tokens 吊销用户在此刻之前签发的所有 access token 和 refresh token
+// 按用户ID记录，用户改名后旧 token 同样失效
+pub async fn revoke_user_tokens(redis: &Addr<RedisActor>, user_id: i32) -> Result<(), MyError> {
+    let jwt_config = &globals::APP_CONFIG.jwt;
+    let ttl = jwt_config.expire_time.max(jwt_config.refresh_expire_time);
+    RedisOps::set_ex(
+        redis,
+        &user_revoked_at_key(user_id),
+        &now_timestamp_millis().to_string(),
+        ttl,
+    )
+    .await?;
+    Ok(())
+}
+
+//is_access_token_revoked 检查 access token 是否已被吊销
+pub async fn is_access_token_revoked(
+    redis: &Addr<RedisActor>,
+    claims: &Claims,
+) -> Result<bool, MyError> {
+    if RedisOps::get(redis, &access_token_denylist_key(&claims.jti))
+        .await?
+        .is_some()
+    {
+        return Ok(true);
+    }
+    Ok(matches!(
+        get_user_revoked_at(redis, claims.user_id).await?,
+        Some(revoked_at) if claims.iat_ms < revoked_at
+    ))
+}
+
+// 一次登录失败的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoginFailure {
+    pub delay_ms: u64, // 返回响应前需要等待的时间
+    pub locked: bool,  // 本次失败是否触发了锁定
+}
+
+//login_delay_ms 根据失败次数计算逐步增加的响应延迟
+pub fn login_delay_ms(failures: u32, config: &SecurityConfig) -> u64 {
+    if failures < config.login_delay_after {
+        return 0;
+    }
+    let steps = u64::from(failures - config.login_delay_after + 1);
+    steps
+        .saturating_mul(config.login_delay_step_ms)
+        .min(config.login_max_delay_ms)
+}
+
+//get_login_lock_ttl 检查用户名或 IP 是否被锁定，返回剩余锁定时间（秒）
+pub async fn get_login_lock_ttl(
+    redis: &Addr<RedisActor>,
+    user_name: &str,
+    client_ip: Option<&str>,
+) -> Result<Option<u64>, MyError> {
+    let mut keys = vec![login_lock_key(LOGIN_SUBJECT_USER, user_name)];
+    if let Some(client_ip) = client_ip {
+        keys.push(login_lock_key(LOGIN_SUBJECT_IP, client_ip));
+    }
+
+    let mut remaining = None;
+    for key in keys {
+        let ttl = RedisOps::ttl(redis, &key).await?;
+        if ttl > 0 {
+            remaining = remaining.max(Some(ttl as u64));
+        }
+    }
+    Ok(remaining)
+}
+
+// 失败计数加一，达到阈值时写入锁定标记并清空计数
+async fn incr_login_failure(
+    redis: &Addr<RedisActor>,
+    subject: &str,
+    value: &str,
+    max_failures: u32,
+    config: &SecurityConfig,
+) -> Result<(u32, bool), MyError> {
+    let key = login_failure_key(subject, value);
+    let failures = RedisOps::incr(redis, &key).await?.max(0) as u32;
+    if failures == 1 {
+        RedisOps::expire(redis, &key, config.login_failure_window).await?;
+    }
+    if failures < max_failures {
+        return Ok((failures, false));
+    }
+
+    warn!("too many failed logins, locking {}: {}", subject, value);
+    RedisOps::set_ex(
+        redis,
+        &login_lock_key(subject, value),
+        &now_timestamp().to_string(),
+        config.login_lockout_duration,
+    )
+    .await?;
+    RedisOps::del(redis, &key).await?;
+    Ok((failures, true))
+}
+
+//record_login_failure 记录一次登录失败，用户名或 IP 达到阈值时锁定
+pub async fn record_login_failure(
+    redis: &Addr<RedisActor>,
+    user_name: &str,
+    client_ip: Option<&str>,
+) -> Result<LoginFailure, MyError> {
+    let config = &globals::APP_CONFIG.security;
+    let (mut failures, mut locked) = incr_login_failure(
+        redis,
+        LOGIN_SUBJECT_USER,
+        user_name,
+        config.login_max_failures,
+        config,
+    )
+    .await?;
+    if let Some(client_ip) = client_ip {
+        let (ip_failures, ip_locked) = incr_login_failure(
+            redis,
+            LOGIN_SUBJECT_IP,
+            client_ip,
+            config.login_ip_max_failures,
+            config,
+        )
+        .await?;
+        failures = failures.max(ip_failures);
+        locked |= ip_locked;
+    }
+
+    Ok(LoginFailure {
+        delay_ms: login_delay_ms(failures, config),
+        locked,
+    })
+}
+
+//clear_login_failures 登录成功后清空用户名的失败计数
+pub async fn clear_login_failures(redis: &Addr<RedisActor>, user_name: &str) -> Result<(), MyError> {
+    RedisOps::del(redis, &login_failure_key(LOGIN_SUBJECT_USER, user_name)).await?;
+    Ok(())
+}
+
+//unlock_user 解除用户名的登录锁定并清空失败计数
+pub async fn unlock_user(redis: &Addr<RedisActor>, user_name: &str) -> Result<bool, MyError> {
+    let unlocked = RedisOps::del(redis, &login_lock_key(LOGIN_SUBJECT_USER, user_name)).await?;
+    clear_login_failures(redis, user_name).await?;
+    Ok(unlocked > 0)
+}
+
+//get_user_lock_ttl 获取用户名剩余的锁定时间（秒），未锁定时返回 None
+pub async fn get_user_lock_ttl(
+    redis: &Addr<RedisActor>,
+    user_name: &str,
+) -> Result<Option<u64>, MyError> {
+    get_login_lock_ttl(redis, user_name, None).await
+}
+
+//issue_password_reset_token 签发重置密码 token，同一用户之前未使用的 token 随即失效
+pub async fn issue_password_reset_token(
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+) -> Result<String, MyError> {
+    let ttl = globals::APP_CONFIG.security.password_reset_expire_time;
+    let token = generate_random_token(PASSWORD_RESET_TOKEN_LENGTH);
+    RedisOps::set_ex(redis, &password_reset_key(&token), &user_id.to_string(), ttl).await?;
+
+    let user_key = password_reset_user_key(user_id);
+    if let Some(previous) = RedisOps::getset(redis, &user_key, &token).await? {
+        RedisOps::del(redis, &password_reset_key(&previous)).await?;
+    }
+    RedisOps::expire(redis, &user_key, ttl).await?;
+    Ok(token)
+}
+
+fn invalid_reset_token() -> MyError {
+    MyError::AuthError("Invalid or expired reset token".to_string())
+}
+
+//get_password_reset_user_id 获取重置密码 token 对应的用户ID，不消耗 token
+pub async fn get_password_reset_user_id(
+    redis: &Addr<RedisActor>,
+    token: &str,
+) -> Result<i32, MyError> {
+    RedisOps::get(redis, &password_reset_key(token))
+        .await?
+        .and_then(|value| value.parse::<i32>().ok())
+        .ok_or_else(invalid_reset_token)
+}
+
+//consume_password_reset_token 使用重置密码 token，返回对应的用户ID，每个 token 只能使用一次
+pub async fn consume_password_reset_token(
+    redis: &Addr<RedisActor>,
+    token: &str,
+) -> Result<i32, MyError> {
+    let user_id = get_password_reset_user_id(redis, token).await?;
+    // 并发使用同一个 token 时只有删除成功的一方有效
+    if RedisOps::del(redis, &password_reset_key(token)).await? == 0 {
+        return Err(invalid_reset_token());
+    }
+    RedisOps::del(redis, &password_reset_user_key(user_id)).await?;
+    Ok(user_id)
+}
+
+//acquire_password_reset_cooldown 同一邮箱在冷却时间内只允许发送一封重置邮件，返回 false 表示仍在冷却中
+pub async fn acquire_password_reset_cooldown(
+    redis: &Addr<RedisActor>,
+    email: &str,
+) -> Result<bool, MyError> {
+    Ok(RedisOps::set_nx_ex(
+        redis,
+        &password_reset_cooldown_key(email),
+        &now_timestamp().to_string(),
+        globals::APP_CONFIG.security.password_reset_cooldown,
+    )
+    .await?)
+}
+
+//send_password_reset_mail 签发重置密码 token 并将重置链接发送到用户邮箱
+pub async fn send_password_reset_mail(
+    redis: &Addr<RedisActor>,
+    mail_sender: &dyn MailSender,
+    user: &sys_user::Model,
+) -> Result<(), MyError> {
+    if user.email.is_empty() {
+        return Err(MyError::BadRequestError("User has no email".to_string()));
+    }
+    let security = &globals::APP_CONFIG.security;
+    let token = issue_password_reset_token(redis, user.id).await?;
+    let mail = Mail {
+        to: user.email.clone(),
+        subject: "重置密码".to_string(),
+        body: format!(
+            "{}，您好：\n\n请在 {} 分钟内打开以下链接重置密码，链接只能使用一次：\n{}{}\n\n如果不是您本人的操作，请忽略本邮件。",
+            user.nick_name,
+            security.password_reset_expire_time / 60,
+            security.password_reset_url,
+            token
+        ),
+    };
+    mail_sender.send(mail).await
+}
@@ -0,0 +1,581 @@
+use crate::common::error::MyError;
+use crate::common::redis::utils::RedisOps;
+use crate::config::globals;
+use crate::schemas::admin::sea_orm_active_enums::{
+    ActionCode, ApiMethod, PermissionEffect, TargetType,
+};
+use crate::schemas::admin::prelude::SysPermission;
+use crate::schemas::admin::{sys_api, sys_permission, sys_permission_action, sys_permission_target};
+use crate::services::admin::sys_route_services::Status;
+use crate::services::admin::{
+    sys_role_permission_services, sys_role_services, sys_user_permission_services,
+};
+use actix::Addr;
+use actix_redis::RedisActor;
+use actix_web::http::Method;
+use log::{error, warn};
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, Iterable, QueryFilter, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+// 权限变更时递增，Redis 中的缓存 key 带上该版本号，旧版本的缓存自然失效
+const PERMISSION_CACHE_GEN_KEY: &str = "perm_cache:gen";
+const PERMISSION_CACHE_KEY_PREFIX: &str = "perm_cache:";
+
+// 本地缓存的角色或用户授权
+struct CachedGrants {
+    grants: Arc<SubjectGrants>,
+    expires_at: Instant,
+}
+
+// 缓存的角色或用户授权，super_role 表示该角色是已启用的超级管理员角色，用户授权恒为 false
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubjectGrants {
+    pub grants: ApiGrants,
+    pub super_role: bool,
+}
+
+// 缓存授权的对象：角色的授权来自角色权限，用户的授权来自用户直接授权
+#[derive(Debug, Clone, Copy)]
+enum GrantSubject<'a> {
+    Role(&'a str),
+    User(i32),
+}
+
+impl GrantSubject<'_> {
+    fn cache_key(&self) -> String {
+        match self {
+            GrantSubject::Role(role_code) => format!("role:{}", role_code),
+            GrantSubject::User(user_id) => format!("user:{}", user_id),
+        }
+    }
+}
+
+static LOCAL_PERMISSION_CACHE: Lazy<Mutex<LruCache<String, CachedGrants>>> = Lazy::new(|| {
+    let capacity = NonZeroUsize::new(globals::APP_CONFIG.permission.local_cache_capacity)
+        .unwrap_or(NonZeroUsize::MIN);
+    Mutex::new(LruCache::new(capacity))
+});
+
+// 本地缓存的版本号，避免失效前发起的加载把旧数据写回本地缓存
+static LOCAL_PERMISSION_CACHE_GEN: AtomicU64 = AtomicU64::new(0);
+
+// 角色通过权限获得的接口授权
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiGrant {
+    pub api_path: String,
+    pub api_method: ApiMethod,
+    pub actions: Vec<ActionCode>, // 该接口所属权限允许的操作
+}
+
+impl ApiGrant {
+    // 判断授权是否覆盖该请求
+    pub fn allows(&self, method: &Method, path: &str) -> bool {
+        let (Some(api_method), Some(action)) = (api_method_of(method), action_code_of(method))
+        else {
+            return false;
+        };
+        self.api_method == api_method
+            && self.actions.contains(&action)
+            && match_api_path(&self.api_path, path)
+    }
+}
+
+// 一组权限的接口授权，deny 来自 DENY 权限或被用户直接拒绝的权限
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiGrants {
+    pub allow: Vec<ApiGrant>,
+    pub deny: Vec<ApiGrant>,
+}
+
+impl ApiGrants {
+    //decide 判断该组授权对请求的结论，拒绝优先，未覆盖该请求时返回 None
+    pub fn decide(&self, method: &Method, path: &str) -> Option<PermissionEffect> {
+        if self.deny.iter().any(|grant| grant.allows(method, path)) {
+            Some(PermissionEffect::Deny)
+        } else if self.allow.iter().any(|grant| grant.allows(method, path)) {
+            Some(PermissionEffect::Allow)
+        } else {
+            None
+        }
+    }
+}
+
+// 用户最终生效的权限
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionSet {
+    pub allow_ids: Vec<i32>, // 已去掉被拒绝的权限
+    pub deny_ids: Vec<i32>,
+}
+
+//resolve_permission_effects 合并角色权限和用户直接授权，同一权限既被允许又被拒绝时以拒绝为准
+pub fn resolve_permission_effects(permissions: &[(i32, PermissionEffect)]) -> PermissionSet {
+    let deny_ids: BTreeSet<i32> = permissions
+        .iter()
+        .filter(|(_, effect)| *effect == PermissionEffect::Deny)
+        .map(|(permission_id, _)| *permission_id)
+        .collect();
+    let allow_ids: BTreeSet<i32> = permissions
+        .iter()
+        .filter(|(permission_id, effect)| {
+            *effect == PermissionEffect::Allow && !deny_ids.contains(permission_id)
+        })
+        .map(|(permission_id, _)| *permission_id)
+        .collect();
+    PermissionSet {
+        allow_ids: allow_ids.into_iter().collect(),
+        deny_ids: deny_ids.into_iter().collect(),
+    }
+}
+
+//evaluate_api_access 拒绝优先：任一授权拒绝该请求即拒绝，否则任一授权允许（或为超级管理员）即允许
+pub fn evaluate_api_access(
+    grants: &[&ApiGrants],
+    is_super: bool,
+    method: &Method,
+    path: &str,
+) -> bool {
+    let decisions: Vec<Option<PermissionEffect>> = grants
+        .iter()
+        .map(|grants| grants.decide(method, path))
+        .collect();
+    if decisions.contains(&Some(PermissionEffect::Deny)) {
+        return false;
+    }
+    is_super || decisions.contains(&Some(PermissionEffect::Allow))
+}
+
+//api_method_of 将 HTTP 方法转换为 sys_api 中的请求方法
+pub fn api_method_of(method: &Method) -> Option<ApiMethod> {
+    match *method {
+        Method::GET => Some(ApiMethod::Get),
+        Method::POST => Some(ApiMethod::Post),
+        Method::PUT => Some(ApiMethod::Put),
+        Method::DELETE => Some(ApiMethod::Delete),
+        _ => None,
+    }
+}
+
+//action_code_of 将 HTTP 方法映射为权限操作
+pub fn action_code_of(method: &Method) -> Option<ActionCode> {
+    match *method {
+        Method::GET => Some(ActionCode::Read),
+        Method::POST => Some(ActionCode::Create),
+        Method::PUT => Some(ActionCode::Update),
+        Method::DELETE => Some(ActionCode::Delete),
+        _ => None,
+    }
+}
+
+//match_api_path 按路径段匹配，`{xxx}` 形式的段匹配任意非空值
+pub fn match_api_path(pattern: &str, path: &str) -> bool {
+    let pattern_segments: Vec<&str> = pattern.trim_end_matches('/').split('/').collect();
+    let path_segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    if pattern_segments.len() != path_segments.len() {
+        return false;
+    }
+    pattern_segments
+        .iter()
+        .zip(path_segments.iter())
+        .all(|(pattern_segment, path_segment)| {
+            if pattern_segment.starts_with('{') && pattern_segment.ends_with('}') {
+                !path_segment.is_empty()
+            } else {
+                pattern_segment == path_segment
+            }
+        })
+}
+
+//is_super_role 是否包含超级管理员角色
+pub fn is_super_role(role_codes: &[String], super_role_codes: &[String]) -> bool {
+    role_codes.iter().any(|code| super_role_codes.contains(code))
+}
+
+//has_enabled_super_role 是否包含处于启用状态的超级管理员角色，超级管理员身份不通过角色继承传递
+pub async fn has_enabled_super_role(
+    db: &DatabaseConnection,
+    role_codes: &[String],
+    super_role_codes: &[String],
+) -> Result<bool, MyError> {
+    if !is_super_role(role_codes, super_role_codes) {
+        return Ok(false);
+    }
+    let super_roles: Vec<String> = role_codes
+        .iter()
+        .filter(|code| super_role_codes.contains(code))
+        .cloned()
+        .collect();
+    Ok(!sys_role_services::get_role_ids_by_role_codes(db, super_roles)
+        .await?
+        .is_empty())
+}
+
+//get_api_grants_by_role_codes 获取已启用角色（含继承的角色）通过已启用权限获得的接口授权
+pub async fn get_api_grants_by_role_codes(
+    db: &DatabaseConnection,
+    role_codes: Vec<String>,
+) -> Result<ApiGrants, MyError> {
+    // 步骤1: 获取已启用角色及其继承的角色ID
+    let role_ids = sys_role_services::get_effective_role_ids_by_role_codes(db, role_codes).await?;
+    if role_ids.is_empty() {
+        return Ok(ApiGrants::default());
+    }
+
+    // 步骤2: 获取角色关联的已启用权限及其效果
+    let permissions =
+        sys_role_permission_services::get_enabled_permissions_by_role_ids(db, role_ids).await?;
+    get_api_grants_by_permission_set(db, &resolve_permission_effects(&permissions)).await
+}
+
+//get_api_grants_by_user_id 获取用户直接授权获得的接口授权，不含角色的授权
+pub async fn get_api_grants_by_user_id(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<ApiGrants, MyError> {
+    let permissions =
+        sys_user_permission_services::get_enabled_user_permissions(db, user_id).await?;
+    get_api_grants_by_permission_set(db, &resolve_permission_effects(&permissions)).await
+}
+
+//get_api_grants_by_permission_set 获取允许和拒绝的权限分别关联的接口授权
+pub async fn get_api_grants_by_permission_set(
+    db: &DatabaseConnection,
+    permission_set: &PermissionSet,
+) -> Result<ApiGrants, MyError> {
+    Ok(ApiGrants {
+        allow: load_api_grants(db, permission_set.allow_ids.clone(), false).await?,
+        deny: load_api_grants(db, permission_set.deny_ids.clone(), true).await?,
+    })
+}
+
+// 加载权限关联的接口授权，拒绝的权限未配置操作时拒绝全部操作
+async fn load_api_grants(
+    db: &DatabaseConnection,
+    permission_ids: Vec<i32>,
+    deny: bool,
+) -> Result<Vec<ApiGrant>, MyError> {
+    if permission_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // 步骤3: 获取权限允许的操作
+    let mut actions: HashMap<i32, Vec<ActionCode>> = HashMap::new();
+    for action in sys_permission_action::Entity::find()
+        .filter(sys_permission_action::Column::PermissionId.is_in(permission_ids.clone()))
+        .all(db)
+        .await?
+    {
+        actions
+            .entry(action.permission_id)
+            .or_default()
+            .push(action.action_code);
+    }
+
+    // 步骤4: 获取权限关联的接口
+    let targets = sys_permission_target::Entity::find()
+        .filter(sys_permission_target::Column::PermissionId.is_in(permission_ids))
+        .filter(sys_permission_target::Column::TargetType.eq(TargetType::ApiGroup))
+        .all(db)
+        .await?;
+    let api_ids: HashSet<i32> = targets.iter().map(|pt| pt.target_id).collect();
+    let apis: HashMap<i32, sys_api::Model> = sys_api::Entity::find()
+        .filter(sys_api::Column::Id.is_in(api_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|api| (api.id, api))
+        .collect();
+
+    let grants = targets
+        .into_iter()
+        .filter_map(|target| {
+            let api = apis.get(&target.target_id)?;
+            let actions = match actions.get(&target.permission_id) {
+                Some(actions) => actions.clone(),
+                None if deny => ActionCode::iter().collect(),
+                None => return None,
+            };
+            Some(ApiGrant {
+                api_path: api.api_path.clone(),
+                api_method: api.api_method.clone(),
+                actions,
+            })
+        })
+        .collect();
+    Ok(grants)
+}
+
+fn local_permission_cache() -> MutexGuard<'static, LruCache<String, CachedGrants>> {
+    LOCAL_PERMISSION_CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn permission_cache_key(generation: i64, subject: &str) -> String {
+    format!("{}{}:{}", PERMISSION_CACHE_KEY_PREFIX, generation, subject)
+}
+
+fn get_local_grants(subject: &str) -> Option<Arc<SubjectGrants>> {
+    let mut cache = local_permission_cache();
+    match cache.get(subject) {
+        Some(cached) if cached.expires_at > Instant::now() => Some(cached.grants.clone()),
+        Some(_) => {
+            cache.pop(subject);
+            None
+        }
+        None => None,
+    }
+}
+
+// 从 Redis 读取授权，未命中时查库并回写 Redis
+async fn load_grants(
+    db: &DatabaseConnection,
+    redis: &Addr<RedisActor>,
+    subject: GrantSubject<'_>,
+) -> Result<SubjectGrants, MyError> {
+    let generation = RedisOps::get(redis, PERMISSION_CACHE_GEN_KEY)
+        .await?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0);
+    let key = permission_cache_key(generation, &subject.cache_key());
+    if let Some(value) = RedisOps::get(redis, &key).await? {
+        match serde_json::from_str(&value) {
+            Ok(grants) => return Ok(grants),
+            Err(err) => warn!("invalid permission cache {}: {}", key, err),
+        }
+    }
+
+    let grants = match subject {
+        GrantSubject::Role(role_code) => {
+            let role_codes = vec![role_code.to_string()];
+            SubjectGrants {
+                super_role: has_enabled_super_role(
+                    db,
+                    &role_codes,
+                    &globals::APP_CONFIG.permission.super_role_codes,
+                )
+                .await?,
+                grants: get_api_grants_by_role_codes(db, role_codes).await?,
+            }
+        }
+        GrantSubject::User(user_id) => SubjectGrants {
+            grants: get_api_grants_by_user_id(db, user_id).await?,
+            super_role: false,
+        },
+    };
+    let value = serde_json::to_string(&grants).map_err(|e| MyError::RedisError(e.to_string()))?;
+    RedisOps::set_ex(
+        redis,
+        &key,
+        &value,
+        globals::APP_CONFIG.permission.cache_ttl,
+    )
+    .await?;
+    Ok(grants)
+}
+
+// 依次查本地 LRU、Redis、数据库
+async fn get_cached_grants(
+    db: &DatabaseConnection,
+    redis: &Addr<RedisActor>,
+    subject: GrantSubject<'_>,
+) -> Result<Arc<SubjectGrants>, MyError> {
+    let cache_key = subject.cache_key();
+    if let Some(grants) = get_local_grants(&cache_key) {
+        return Ok(grants);
+    }
+
+    let local_generation = LOCAL_PERMISSION_CACHE_GEN.load(Ordering::SeqCst);
+    let grants = Arc::new(load_grants(db, redis, subject).await?);
+    let mut cache = local_permission_cache();
+    if LOCAL_PERMISSION_CACHE_GEN.load(Ordering::SeqCst) == local_generation {
+        cache.put(
+            cache_key,
+            CachedGrants {
+                grants: grants.clone(),
+                expires_at: Instant::now()
+                    + Duration::from_secs(globals::APP_CONFIG.permission.local_cache_ttl),
+            },
+        );
+    }
+    Ok(grants)
+}
+
+//get_cached_role_grants 获取角色的接口授权及其是否为启用的超级管理员角色，依次查本地 LRU、Redis、数据库
+pub async fn get_cached_role_grants(
+    db: &DatabaseConnection,
+    redis: &Addr<RedisActor>,
+    role_code: &str,
+) -> Result<Arc<SubjectGrants>, MyError> {
+    get_cached_grants(db, redis, GrantSubject::Role(role_code)).await
+}
+
+//get_cached_user_grants 获取用户直接授权的接口授权，依次查本地 LRU、Redis、数据库
+pub async fn get_cached_user_grants(
+    db: &DatabaseConnection,
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+) -> Result<Arc<SubjectGrants>, MyError> {
+    get_cached_grants(db, redis, GrantSubject::User(user_id)).await
+}
+
+//get_subject_grants 获取令牌中各角色和用户直接授权的缓存授权，鉴权和授权解释共用
+pub async fn get_subject_grants(
+    db: &DatabaseConnection,
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+    role_codes: &[String],
+) -> Result<Vec<Arc<SubjectGrants>>, MyError> {
+    let mut subjects = Vec::with_capacity(role_codes.len() + 1);
+    for role_code in role_codes {
+        subjects.push(get_cached_role_grants(db, redis, role_code).await?);
+    }
+    subjects.push(get_cached_user_grants(db, redis, user_id).await?);
+    Ok(subjects)
+}
+
+//evaluate_subject_grants 汇总各角色和直接授权的结论，任一拒绝即拒绝，已启用的超级管理员角色同样受拒绝约束
+pub fn evaluate_subject_grants(
+    subjects: &[Arc<SubjectGrants>],
+    method: &Method,
+    path: &str,
+) -> bool {
+    let is_super = subjects.iter().any(|subject| subject.super_role);
+    let grants: Vec<&ApiGrants> = subjects.iter().map(|subject| &subject.grants).collect();
+    evaluate_api_access(&grants, is_super, method, path)
+}
+
+//is_api_allowed 判断用户能否访问接口，role_codes 取自令牌
+pub async fn is_api_allowed(
+    db: &DatabaseConnection,
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+    role_codes: &[String],
+    method: &Method,
+    path: &str,
+) -> Result<bool, MyError> {
+    let subjects = get_subject_grants(db, redis, user_id, role_codes).await?;
+    Ok(evaluate_subject_grants(&subjects, method, path))
+}
+
+//get_permission_set 获取角色（调用方传入已展开继承的角色ID）和用户直接授权合并后的权限
+pub async fn get_permission_set(
+    db: &DatabaseConnection,
+    user_id: i32,
+    role_ids: Vec<i32>,
+) -> Result<PermissionSet, DbErr> {
+    let mut permissions =
+        sys_role_permission_services::get_enabled_permissions_by_role_ids(db, role_ids).await?;
+    permissions
+        .extend(sys_user_permission_services::get_enabled_user_permissions(db, user_id).await?);
+    Ok(resolve_permission_effects(&permissions))
+}
+
+//get_menu_ids_by_permission_set 获取允许的权限关联的菜单ID，去掉拒绝的权限关联的菜单
+pub async fn get_menu_ids_by_permission_set(
+    db: &DatabaseConnection,
+    permission_set: &PermissionSet,
+) -> Result<HashSet<i32>, DbErr> {
+    let mut permission_ids = permission_set.allow_ids.clone();
+    permission_ids.extend(permission_set.deny_ids.iter().copied());
+    if permission_ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let targets = sys_permission_target::Entity::find()
+        .filter(sys_permission_target::Column::PermissionId.is_in(permission_ids))
+        .filter(sys_permission_target::Column::TargetType.eq(TargetType::Menu))
+        .all(db)
+        .await?;
+    let denied: HashSet<i32> = targets
+        .iter()
+        .filter(|target| permission_set.deny_ids.contains(&target.permission_id))
+        .map(|target| target.target_id)
+        .collect();
+    Ok(targets
+        .into_iter()
+        .filter(|target| permission_set.allow_ids.contains(&target.permission_id))
+        .map(|target| target.target_id)
+        .filter(|menu_id| !denied.contains(menu_id))
+        .collect())
+}
+
+//get_user_permission_set 获取用户当前生效的权限，超级管理员拥有全部已启用的允许权限，但仍受拒绝约束
+pub async fn get_user_permission_set(
+    db: &DatabaseConnection,
+    user_id: i32,
+    role_codes: &[String],
+) -> Result<PermissionSet, MyError> {
+    let role_ids =
+        sys_role_services::get_effective_role_ids_by_role_codes(db, role_codes.to_vec()).await?;
+    let mut permission_set = get_permission_set(db, user_id, role_ids).await?;
+    if has_enabled_super_role(
+        db,
+        role_codes,
+        &globals::APP_CONFIG.permission.super_role_codes,
+    )
+    .await?
+    {
+        let all_ids: Vec<i32> = SysPermission::find()
+            .select_only()
+            .column(sys_permission::Column::Id)
+            .filter(sys_permission::Column::Status.eq(Status::Enable as i8))
+            .filter(sys_permission::Column::Effect.eq(PermissionEffect::Allow))
+            .into_tuple()
+            .all(db)
+            .await?;
+        permission_set.allow_ids = all_ids
+            .into_iter()
+            .filter(|id| !permission_set.deny_ids.contains(id))
+            .collect();
+    }
+    Ok(permission_set)
+}
+
+//get_grantable_permission_ids 用户当前拥有的已启用且未被拒绝的权限ID
+pub async fn get_grantable_permission_ids(
+    db: &DatabaseConnection,
+    user_id: i32,
+    role_codes: &[String],
+) -> Result<Vec<i32>, MyError> {
+    Ok(get_user_permission_set(db, user_id, role_codes)
+        .await?
+        .allow_ids)
+}
+
+//is_api_key_allowed API Key 只能使用其权限中所属用户当前仍拥有的部分，用户权限收回后 Key 随之失效，用户被拒绝的接口 Key 同样被拒绝
+pub async fn is_api_key_allowed(
+    db: &DatabaseConnection,
+    user_id: i32,
+    role_codes: &[String],
+    key_permission_ids: &[i32],
+    method: &Method,
+    path: &str,
+) -> Result<bool, MyError> {
+    let mut permission_set = get_user_permission_set(db, user_id, role_codes).await?;
+    permission_set
+        .allow_ids
+        .retain(|id| key_permission_ids.contains(id));
+    let grants = get_api_grants_by_permission_set(db, &permission_set).await?;
+    Ok(evaluate_api_access(&[&grants], false, method, path))
+}
+
+//invalidate_permission_cache 角色、权限、角色权限或用户直接授权变更后清空权限缓存
+pub async fn invalidate_permission_cache() {
+    LOCAL_PERMISSION_CACHE_GEN.fetch_add(1, Ordering::SeqCst);
+    local_permission_cache().clear();
+
+    // 递增 Redis 中的版本号，其他实例的本地缓存最迟在 local_cache_ttl 后失效
+    if let Some(app_state) = globals::APP_STATE.get() {
+        if let Err(err) = RedisOps::incr(&app_state.redis_conn, PERMISSION_CACHE_GEN_KEY).await {
+            error!("failed to invalidate permission cache: {}", err);
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::common::auth::current_user::CurrentUser;
+use crate::common::error::MyError;
+use crate::config::globals;
+use crate::schemas::admin::prelude::{SysRole, SysUser};
+use crate::schemas::admin::sea_orm_active_enums::DataScope;
+use crate::schemas::admin::{sys_role, sys_user};
+use crate::services::admin::{sys_authorization_services, sys_dept_services, sys_role_services};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect};
+use std::collections::BTreeSet;
+
+// 单个角色的数据权限设置
+#[derive(Debug, Clone)]
+pub struct RoleDataScope {
+    pub data_scope: DataScope,
+    pub dept_ids: Vec<i32>, // data_scope 为 CUSTOM 时可见的部门
+}
+
+// 用户最终可见的数据范围
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataScopeFilter {
+    // 不限制
+    All,
+    // 只能看到所属部门在 dept_ids 内的数据，user_id 不为空时还能看到本人的数据
+    Restricted {
+        dept_ids: Vec<i32>,
+        user_id: Option<i32>,
+    },
+}
+
+impl DataScopeFilter {
+    //condition 生成数据权限的查询条件，不限制时返回 None
+    pub fn condition<D: ColumnTrait, U: ColumnTrait>(
+        &self,
+        dept_column: D,
+        user_column: U,
+    ) -> Option<Condition> {
+        match self {
+            DataScopeFilter::All => None,
+            DataScopeFilter::Restricted { dept_ids, user_id } => {
+                // dept_ids 为空时 is_in 生成恒假条件
+                let mut condition = Condition::any().add(dept_column.is_in(dept_ids.clone()));
+                if let Some(user_id) = user_id {
+                    condition = condition.add(user_column.eq(*user_id));
+                }
+                Some(condition)
+            }
+        }
+    }
+
+    //contains 所属部门为 dept_id、ID 为 user_id 的数据是否在范围内，与 condition 生成的条件一致
+    pub fn contains(&self, dept_id: Option<i32>, user_id: i32) -> bool {
+        match self {
+            DataScopeFilter::All => true,
+            DataScopeFilter::Restricted {
+                user_id: own_user_id,
+                ..
+            } => self.contains_dept(dept_id) || *own_user_id == Some(user_id),
+        }
+    }
+
+    //contains_dept 部门是否在范围内，不属于任何部门（dept_id 为 None）的数据只在不限制时可见
+    pub fn contains_dept(&self, dept_id: Option<i32>) -> bool {
+        match self {
+            DataScopeFilter::All => true,
+            DataScopeFilter::Restricted { dept_ids, .. } => {
+                dept_id.is_some_and(|dept_id| dept_ids.contains(&dept_id))
+            }
+        }
+    }
+}
+
+// 为 sea-orm 查询追加数据权限条件
+pub trait DataScopeQuery: QueryFilter + Sized {
+    fn filter_data_scope<D: ColumnTrait, U: ColumnTrait>(
+        self,
+        data_scope: &DataScopeFilter,
+        dept_column: D,
+        user_column: U,
+    ) -> Self {
+        match data_scope.condition(dept_column, user_column) {
+            Some(condition) => self.filter(condition),
+            None => self,
+        }
+    }
+}
+
+impl<Q: QueryFilter> DataScopeQuery for Q {}
+
+//resolve_data_scope 合并用户所有角色的数据权限，取最宽的范围：任一角色为 ALL 时不限制，否则取各角色可见范围的并集
+pub fn resolve_data_scope(
+    role_scopes: &[RoleDataScope],
+    user_id: i32,
+    user_dept_id: Option<i32>,
+    depts: &[(i32, Option<i32>)],
+) -> DataScopeFilter {
+    let mut dept_ids = BTreeSet::new();
+    let mut own_data = false;
+    for role_scope in role_scopes {
+        match role_scope.data_scope {
+            DataScope::All => return DataScopeFilter::All,
+            DataScope::Custom => dept_ids.extend(role_scope.dept_ids.iter().copied()),
+            DataScope::Dept => dept_ids.extend(user_dept_id),
+            DataScope::DeptAndChild => {
+                if let Some(user_dept_id) = user_dept_id {
+                    dept_ids.extend(sys_dept_services::collect_subtree_ids(depts, user_dept_id));
+                }
+            }
+            DataScope::SelfOnly => own_data = true,
+        }
+    }
+    DataScopeFilter::Restricted {
+        dept_ids: dept_ids.into_iter().collect(),
+        user_id: own_data.then_some(user_id),
+    }
+}
+
+//get_data_scope 获取当前用户的数据权限，包含继承的角色，超级管理员不受限制
+pub async fn get_data_scope(
+    db: &DatabaseConnection,
+    current_user: &CurrentUser,
+) -> Result<DataScopeFilter, MyError> {
+    if sys_authorization_services::has_enabled_super_role(
+        db,
+        &current_user.role_codes,
+        &globals::APP_CONFIG.permission.super_role_codes,
+    )
+    .await?
+    {
+        return Ok(DataScopeFilter::All);
+    }
+
+    let role_ids = sys_role_services::get_effective_role_ids_by_role_codes(
+        db,
+        current_user.role_codes.clone(),
+    )
+    .await?;
+    let roles: Vec<(i32, DataScope)> = SysRole::find()
+        .select_only()
+        .column(sys_role::Column::Id)
+        .column(sys_role::Column::DataScope)
+        .filter(sys_role::Column::Id.is_in(role_ids))
+        .into_tuple()
+        .all(db)
+        .await?;
+    if roles
+        .iter()
+        .any(|(_, data_scope)| *data_scope == DataScope::All)
+    {
+        return Ok(DataScopeFilter::All);
+    }
+
+    let custom_role_ids = roles
+        .iter()
+        .filter(|(_, data_scope)| *data_scope == DataScope::Custom)
+        .map(|(role_id, _)| *role_id)
+        .collect();
+    let mut role_dept_ids = sys_role_services::get_role_dept_ids(db, custom_role_ids).await?;
+    let role_scopes: Vec<RoleDataScope> = roles
+        .into_iter()
+        .map(|(role_id, data_scope)| RoleDataScope {
+            data_scope,
+            dept_ids: role_dept_ids.remove(&role_id).unwrap_or_default(),
+        })
+        .collect();
+
+    let user_dept_id: Option<i32> = SysUser::find_by_id(current_user.user_id)
+        .select_only()
+        .column(sys_user::Column::DeptId)
+        .into_tuple::<Option<i32>>()
+        .one(db)
+        .await?
+        .flatten();
+    // 只有本部门及下级的范围需要部门树
+    let depts = if role_scopes
+        .iter()
+        .any(|role_scope| role_scope.data_scope == DataScope::DeptAndChild)
+    {
+        sys_dept_services::get_dept_parents(db).await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(resolve_data_scope(
+        &role_scopes,
+        current_user.user_id,
+        user_dept_id,
+        &depts,
+    ))
+}
+
+//ensure_users_in_scope 校验用户都在数据权限范围内，不存在的用户忽略，超出范围时返回 PermissionDeniedError
+pub async fn ensure_users_in_scope(
+    db: &DatabaseConnection,
+    data_scope: &DataScopeFilter,
+    user_ids: &[i32],
+) -> Result<(), MyError> {
+    if *data_scope == DataScopeFilter::All {
+        return Ok(());
+    }
+    let users: Vec<(i32, Option<i32>)> = SysUser::find()
+        .select_only()
+        .column(sys_user::Column::Id)
+        .column(sys_user::Column::DeptId)
+        .filter(sys_user::Column::Id.is_in(user_ids.to_vec()))
+        .into_tuple()
+        .all(db)
+        .await?;
+    if users
+        .iter()
+        .all(|(user_id, dept_id)| data_scope.contains(*dept_id, *user_id))
+    {
+        Ok(())
+    } else {
+        Err(MyError::PermissionDeniedError(
+            "User is out of your data scope".to_string(),
+        ))
+    }
+}
+
+//check_dept_in_scope 新建或调整用户的所属部门必须在数据权限范围内，dept_id 为 0 表示不属于任何部门，新建用户时 user_id 为 None
+pub fn check_dept_in_scope(
+    data_scope: &DataScopeFilter,
+    dept_id: i32,
+    user_id: Option<i32>,
+) -> Result<(), MyError> {
+    let dept_id = (dept_id != 0).then_some(dept_id);
+    let allowed = match user_id {
+        Some(user_id) => data_scope.contains(dept_id, user_id),
+        None => data_scope.contains_dept(dept_id),
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(MyError::PermissionDeniedError(
+            "Department is out of your data scope".to_string(),
+        ))
+    }
+}
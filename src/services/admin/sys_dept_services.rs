@@ -0,0 +1,291 @@
+use crate::common::error::MyError;
+use crate::dto::admin::sys_dept_dto::{
+    DeptCreateDto, DeptMoveDto, DeptRespDto, DeptTreeDto, DeptUpdateDto,
+};
+use crate::schemas::admin::prelude::{SysDept, SysUser};
+use crate::schemas::admin::{sys_dept, sys_user};
+use chrono::Utc;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect,
+};
+use std::collections::{HashMap, HashSet};
+
+//create_dept 创建部门
+pub async fn create_dept(
+    db: &DatabaseConnection,
+    dept_create_req: DeptCreateDto,
+    create_user: String,
+) -> Result<sys_dept::Model, MyError> {
+    let parent_id = if dept_create_req.parent_id == 0 {
+        None
+    } else {
+        Some(dept_create_req.parent_id)
+    };
+    if let Some(parent_id) = parent_id {
+        check_dept_exists(db, parent_id, "Parent department not found").await?;
+    }
+    if let Some(leader_id) = dept_create_req.leader_id {
+        check_leader_exists(db, leader_id).await?;
+    }
+
+    let dept = sys_dept::ActiveModel {
+        parent_id: Set(parent_id),
+        dept_name: Set(dept_create_req.dept_name),
+        leader_id: Set(dept_create_req.leader_id),
+        order: Set(dept_create_req.order.unwrap_or(0)),
+        status: Set(dept_create_req.status.parse::<i8>().unwrap_or(1)),
+        create_user: Set(create_user),
+        create_time: Set(Some(Utc::now())),
+        ..Default::default()
+    };
+    Ok(dept.insert(db).await?)
+}
+
+//get_depts 获取全部部门，按排序号和ID升序
+pub async fn get_depts(db: &DatabaseConnection) -> Result<Vec<sys_dept::Model>, DbErr> {
+    SysDept::find()
+        .order_by_asc(sys_dept::Column::Order)
+        .order_by_asc(sys_dept::Column::Id)
+        .all(db)
+        .await
+}
+
+//get_dept_by_id 获取单个部门
+pub async fn get_dept_by_id(
+    db: &DatabaseConnection,
+    dept_id: i32,
+) -> Result<Option<sys_dept::Model>, DbErr> {
+    SysDept::find_by_id(dept_id).one(db).await
+}
+
+//get_dept_tree 获取部门树
+pub async fn get_dept_tree(db: &DatabaseConnection) -> Result<Vec<DeptTreeDto>, DbErr> {
+    Ok(build_dept_tree(get_depts(db).await?))
+}
+
+//update_dept 更新部门，上级部门通过 move_dept 修改
+pub async fn update_dept(
+    db: &DatabaseConnection,
+    dept_id: i32,
+    dept_update_req: DeptUpdateDto,
+    update_user: String,
+) -> Result<sys_dept::Model, MyError> {
+    let mut dept: sys_dept::ActiveModel = SysDept::find_by_id(dept_id)
+        .one(db)
+        .await?
+        .ok_or(MyError::NotFound("Department not found".to_string()))?
+        .into();
+
+    if let Some(dept_name) = dept_update_req.dept_name {
+        dept.dept_name = Set(dept_name);
+    }
+    if let Some(leader_id) = dept_update_req.leader_id {
+        dept.leader_id = Set(if leader_id == 0 {
+            None
+        } else {
+            check_leader_exists(db, leader_id).await?;
+            Some(leader_id)
+        });
+    }
+    if let Some(order) = dept_update_req.order {
+        dept.order = Set(order);
+    }
+    if let Some(status) = dept_update_req.status {
+        dept.status = Set(status.parse::<i8>().unwrap_or(1));
+    }
+    dept.update_user = Set(Some(update_user));
+    dept.update_time = Set(Some(Utc::now()));
+    Ok(dept.update(db).await?)
+}
+
+//move_dept 将部门连同其下级部门移动到新的上级部门下
+pub async fn move_dept(
+    db: &DatabaseConnection,
+    dept_id: i32,
+    dept_move_req: DeptMoveDto,
+    update_user: String,
+) -> Result<sys_dept::Model, MyError> {
+    let mut dept: sys_dept::ActiveModel = SysDept::find_by_id(dept_id)
+        .one(db)
+        .await?
+        .ok_or(MyError::NotFound("Department not found".to_string()))?
+        .into();
+
+    let parent_id = if dept_move_req.parent_id == 0 {
+        None
+    } else {
+        Some(dept_move_req.parent_id)
+    };
+    if let Some(parent_id) = parent_id {
+        let parents = get_dept_parents(db).await?;
+        if !parents.iter().any(|(id, _)| *id == parent_id) {
+            return Err(MyError::BadRequestError(
+                "Parent department not found".to_string(),
+            ));
+        }
+        if collect_subtree_ids(&parents, dept_id).contains(&parent_id) {
+            return Err(MyError::BadRequestError(
+                "Cannot move a department under itself or its sub-departments".to_string(),
+            ));
+        }
+    }
+
+    dept.parent_id = Set(parent_id);
+    if let Some(order) = dept_move_req.order {
+        dept.order = Set(order);
+    }
+    dept.update_user = Set(Some(update_user));
+    dept.update_time = Set(Some(Utc::now()));
+    Ok(dept.update(db).await?)
+}
+
+//delete_dept 删除部门，存在下级部门或用户时不允许删除
+pub async fn delete_dept(db: &DatabaseConnection, dept_id: i32) -> Result<u64, MyError> {
+    let children = SysDept::find()
+        .filter(sys_dept::Column::ParentId.eq(dept_id))
+        .count(db)
+        .await?;
+    if children > 0 {
+        return Err(MyError::BadRequestError(
+            "Department has sub-departments".to_string(),
+        ));
+    }
+    let users = SysUser::find()
+        .filter(sys_user::Column::DeptId.eq(dept_id))
+        .count(db)
+        .await?;
+    if users > 0 {
+        return Err(MyError::BadRequestError(
+            "Department still has users".to_string(),
+        ));
+    }
+    Ok(SysDept::delete_by_id(dept_id).exec(db).await?.rows_affected)
+}
+
+//get_dept_subtree_ids 获取部门自身及其全部下级部门的ID
+pub async fn get_dept_subtree_ids(
+    db: &DatabaseConnection,
+    dept_id: i32,
+) -> Result<Vec<i32>, DbErr> {
+    Ok(collect_subtree_ids(&get_dept_parents(db).await?, dept_id))
+}
+
+//check_dept_exists 校验部门存在，不存在时返回给定的错误信息
+pub async fn check_dept_exists(
+    db: &DatabaseConnection,
+    dept_id: i32,
+    message: &str,
+) -> Result<(), MyError> {
+    match SysDept::find_by_id(dept_id).one(db).await? {
+        Some(_) => Ok(()),
+        None => Err(MyError::BadRequestError(message.to_string())),
+    }
+}
+
+// 校验部门负责人对应的用户存在
+async fn check_leader_exists(db: &DatabaseConnection, leader_id: i32) -> Result<(), MyError> {
+    match SysUser::find_by_id(leader_id).one(db).await? {
+        Some(_) => Ok(()),
+        None => Err(MyError::BadRequestError(
+            "Leader user not found".to_string(),
+        )),
+    }
+}
+
+//get_dept_parents 获取全部部门的 (ID, 上级部门ID)
+pub async fn get_dept_parents(db: &DatabaseConnection) -> Result<Vec<(i32, Option<i32>)>, DbErr> {
+    SysDept::find()
+        .select_only()
+        .column(sys_dept::Column::Id)
+        .column(sys_dept::Column::ParentId)
+        .into_tuple()
+        .all(db)
+        .await
+}
+
+//collect_subtree_ids 根据 (ID, 上级部门ID) 列表获取部门自身及其全部下级部门的ID，部门不存在时返回空
+pub fn collect_subtree_ids(depts: &[(i32, Option<i32>)], dept_id: i32) -> Vec<i32> {
+    if !depts.iter().any(|(id, _)| *id == dept_id) {
+        return Vec::new();
+    }
+    let mut children: HashMap<i32, Vec<i32>> = HashMap::new();
+    for (id, parent_id) in depts {
+        if let Some(parent_id) = parent_id {
+            children.entry(*parent_id).or_default().push(*id);
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let mut subtree = Vec::new();
+    let mut stack = vec![dept_id];
+    while let Some(id) = stack.pop() {
+        // 数据中已存在的环只遍历一次
+        if !visited.insert(id) {
+            continue;
+        }
+        subtree.push(id);
+        if let Some(child_ids) = children.get(&id) {
+            stack.extend(child_ids.iter().rev());
+        }
+    }
+    subtree
+}
+
+//build_dept_tree 构建部门树，上级部门不存在的部门作为顶级部门
+pub fn build_dept_tree(depts: Vec<sys_dept::Model>) -> Vec<DeptTreeDto> {
+    let ids: HashSet<i32> = depts.iter().map(|dept| dept.id).collect();
+    let mut sorted = depts;
+    sorted.sort_by_key(|dept| (dept.order, dept.id));
+
+    let mut roots = Vec::new();
+    let mut children: HashMap<i32, Vec<i32>> = HashMap::new();
+    for dept in &sorted {
+        match dept.parent_id {
+            Some(parent_id) if ids.contains(&parent_id) => {
+                children.entry(parent_id).or_default().push(dept.id)
+            }
+            _ => roots.push(dept.id),
+        }
+    }
+
+    let order: Vec<i32> = sorted.iter().map(|dept| dept.id).collect();
+    let mut dept_dtos: HashMap<i32, DeptRespDto> = sorted
+        .into_iter()
+        .map(|dept| (dept.id, DeptRespDto::from(dept)))
+        .collect();
+    let mut tree: Vec<DeptTreeDto> = roots
+        .into_iter()
+        .filter_map(|dept_id| build_dept_node(&children, &mut dept_dtos, dept_id))
+        .collect();
+    // 只能通过环到达的部门同样作为顶级部门返回，避免丢失数据
+    for dept_id in order {
+        if let Some(node) = build_dept_node(&children, &mut dept_dtos, dept_id) {
+            tree.push(node);
+        }
+    }
+    tree
+}
+
+// 递归组装部门树，每个部门只出现一次
+fn build_dept_node(
+    children: &HashMap<i32, Vec<i32>>,
+    dept_dtos: &mut HashMap<i32, DeptRespDto>,
+    dept_id: i32,
+) -> Option<DeptTreeDto> {
+    let base = dept_dtos.remove(&dept_id)?;
+    let children_nodes = children
+        .get(&dept_id)
+        .map(|child_ids| {
+            child_ids
+                .iter()
+                .filter_map(|child_id| build_dept_node(children, dept_dtos, *child_id))
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(DeptTreeDto {
+        base,
+        children: children_nodes,
+    })
+}
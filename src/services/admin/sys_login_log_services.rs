@@ -0,0 +1,91 @@
+use crate::common::error::MyError;
+use crate::dto::admin::common_dto::PaginationResponseDto;
+use crate::dto::admin::sys_login_log_dto::{LoginClientDto, LoginLogDto, LoginLogQueryDto};
+use crate::schemas::admin::prelude::SysLoginLog;
+use crate::schemas::admin::sea_orm_active_enums::LoginResult;
+use crate::schemas::admin::sys_login_log;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+
+const USER_NAME_MAX_LEN: usize = 64;
+const IP_MAX_LEN: usize = 64;
+const USER_AGENT_MAX_LEN: usize = 512;
+const FAILURE_REASON_MAX_LEN: usize = 255;
+
+// 按字符截断，避免超出字段长度导致写入失败
+fn truncate(value: &str, max_len: usize) -> String {
+    value.chars().take(max_len).collect()
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>, MyError> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .map(|time| time.and_utc())
+        .map_err(|_| MyError::BadRequestError(format!("invalid time {}", value)))
+}
+
+//record_login_log 记录一次登录
+pub async fn record_login_log(
+    db: &DatabaseConnection,
+    user_name: &str,
+    client: &LoginClientDto,
+    result: LoginResult,
+    failure_reason: Option<String>,
+) -> Result<sys_login_log::Model, MyError> {
+    let login_log = sys_login_log::ActiveModel {
+        user_name: Set(truncate(user_name, USER_NAME_MAX_LEN)),
+        ip: Set(client.ip.as_deref().map(|ip| truncate(ip, IP_MAX_LEN))),
+        user_agent: Set(client
+            .user_agent
+            .as_deref()
+            .map(|user_agent| truncate(user_agent, USER_AGENT_MAX_LEN))),
+        result: Set(result),
+        failure_reason: Set(failure_reason.map(|reason| truncate(&reason, FAILURE_REASON_MAX_LEN))),
+        login_time: Set(Some(Utc::now())),
+        ..Default::default()
+    };
+    Ok(login_log.insert(db).await?)
+}
+
+//get_paginated_login_logs 分页获取登录日志，按登录时间倒序
+pub async fn get_paginated_login_logs(
+    db: &DatabaseConnection,
+    query_dto: LoginLogQueryDto,
+) -> Result<PaginationResponseDto<LoginLogDto>, MyError> {
+    let current = query_dto.current.unwrap_or(1);
+    let size = query_dto.size.unwrap_or(10);
+
+    let mut query = SysLoginLog::find();
+    if let Some(user_name) = query_dto.user_name.filter(|name| !name.is_empty()) {
+        query = query.filter(sys_login_log::Column::UserName.eq(user_name));
+    }
+    if let Some(ip) = query_dto.ip.filter(|ip| !ip.is_empty()) {
+        query = query.filter(sys_login_log::Column::Ip.eq(ip));
+    }
+    if let Some(result) = query_dto.result.filter(|result| !result.is_empty()) {
+        query = query.filter(sys_login_log::Column::Result.eq(LoginResult::from_string(&result)?));
+    }
+    if let Some(start_time) = query_dto.start_time.filter(|time| !time.is_empty()) {
+        query = query.filter(sys_login_log::Column::LoginTime.gte(parse_time(&start_time)?));
+    }
+    if let Some(end_time) = query_dto.end_time.filter(|time| !time.is_empty()) {
+        query = query.filter(sys_login_log::Column::LoginTime.lte(parse_time(&end_time)?));
+    }
+
+    let total = query.clone().count(db).await?;
+    let login_logs = query
+        .order_by_desc(sys_login_log::Column::LoginTime)
+        .order_by_desc(sys_login_log::Column::Id)
+        .limit(size)
+        .offset(current.saturating_sub(1) * size)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(LoginLogDto::from)
+        .collect();
+
+    Ok(PaginationResponseDto::new(current, size, total, login_logs))
+}
@@ -1,5 +1,7 @@
 use crate::common::error::MyError;
-use crate::dto::admin::sys_menu_dto::{MenuCreateDto, MenuTreeResponseDto, MenuUpdateDto};
+use crate::dto::admin::sys_menu_dto::{
+    MenuBaseRespDto, MenuButton, MenuCreateDto, MenuTreeResponseDto, MenuUpdateDto,
+};
 use crate::schemas::admin;
 use crate::schemas::admin::prelude::SysMenu;
 use crate::schemas::admin::sys_menu;
@@ -8,12 +10,18 @@ use sea_orm::prelude::Expr;
 use sea_orm::ActiveValue::Set;
 use sea_orm::ColumnTrait;
 use sea_orm::QueryFilter;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait};
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, JoinType,
+    PaginatorTrait, QueryOrder, QuerySelect, RelationTrait, TransactionTrait,
+};
 use std::cell::{RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::{Rc};
 use crate::common::enums;
 use crate::schemas::admin::sys_menu::Model;
+use crate::schemas::admin::sea_orm_active_enums::{TargetType, Type};
+use crate::services::admin::{sys_authorization_services, sys_role_services};
+use crate::services::admin::sys_route_services::Status;
 
 //create_menu 创建菜单
 pub async fn create_menu(
@@ -56,7 +64,15 @@ pub async fn create_menu(
     }else{
         menu.icon = Set(menu_create_req.icon)
     }
-    menu.insert(db).await.map_err(MyError::from)
+
+    let buttons = menu_create_req.buttons.unwrap_or_default();
+    check_menu_buttons(db, None, &buttons).await?;
+
+    let txn = db.begin().await?;
+    let menu = menu.insert(&txn).await?;
+    save_menu_buttons(&txn, menu.id, buttons, &menu.create_user).await?;
+    txn.commit().await?;
+    Ok(menu)
 }
 
 
@@ -71,8 +87,10 @@ pub async fn get_menus_paged(
     page: u64,      // 当前页码，从1开始
     page_size: u64, // 每页条目数
 ) -> Result<(Vec<sys_menu::Model>, u64), MyError> {
-    // 使用.find()开始构建查询
-    let paginator = SysMenu::find().paginate(db, page_size); // 设置每页条目数
+    // 使用.find()开始构建查询，按钮挂在所属菜单下返回，不单独分页
+    let paginator = SysMenu::find()
+        .filter(sys_menu::Column::Type.ne(Type::Button))
+        .paginate(db, page_size); // 设置每页条目数
     let num_pages = paginator.num_pages().await?; // 获取总页数
     let menus = paginator.fetch_page(page - 1).await?; // 获取指定页的结果，页码从0开始，所以这里需要减1
     Ok((menus, num_pages))
@@ -92,6 +110,7 @@ pub async fn update_menu(
     db: &DatabaseConnection,
     menu_id: i32,
     menu_update_req: MenuUpdateDto,
+    update_user: String,
 ) -> Result<Option<Model>, MyError> {
     // 尝试检索现有菜单
     let mut menu: sys_menu::ActiveModel = SysMenu::find_by_id(menu_id)
@@ -186,49 +205,259 @@ pub async fn update_menu(
         }
     }
 
+    menu.update_user = Set(Some(update_user.clone()));
+    menu.update_time = Set(Some(Utc::now()));
+
+    // buttons 为 None 时保持原有按钮不变
+    if let Some(buttons) = &menu_update_req.buttons {
+        check_menu_buttons(db, Some(menu_id), buttons).await?;
+    }
+
     // 更新数据库中的菜单
-    menu.update(db).await.map(Some).map_err(MyError::from)
+    let txn = db.begin().await?;
+    let menu = menu.update(&txn).await?;
+    if let Some(buttons) = menu_update_req.buttons {
+        save_menu_buttons(&txn, menu_id, buttons, &update_user).await?;
+    }
+    txn.commit().await?;
+    Ok(Some(menu))
+}
+
+//check_menu_buttons 校验按钮编码：同一请求内不能重复，也不能被其他菜单的按钮占用
+async fn check_menu_buttons(
+    db: &DatabaseConnection,
+    menu_id: Option<i32>,
+    buttons: &[MenuButton],
+) -> Result<(), MyError> {
+    let mut codes = HashSet::new();
+    for button in buttons {
+        if !codes.insert(button.code.as_str()) {
+            return Err(MyError::BadRequestError(format!(
+                "Duplicate button code {}",
+                button.code
+            )));
+        }
+    }
+    if codes.is_empty() {
+        return Ok(());
+    }
+
+    let mut query = SysMenu::find()
+        .filter(sys_menu::Column::Type.eq(Type::Button))
+        .filter(sys_menu::Column::RouteName.is_in(codes));
+    if let Some(menu_id) = menu_id {
+        query = query.filter(sys_menu::Column::ParentId.ne(menu_id));
+    }
+    if let Some(used) = query.one(db).await? {
+        return Err(MyError::BadRequestError(format!(
+            "Button code {} is already used",
+            used.route_name.unwrap_or_default()
+        )));
+    }
+    Ok(())
+}
+
+//save_menu_buttons 按编码同步菜单下的按钮，编码不变的按钮保留原ID并更新名称和顺序，已授予的权限不受影响
+async fn save_menu_buttons<C: ConnectionTrait>(
+    db: &C,
+    menu_id: i32,
+    buttons: Vec<MenuButton>,
+    operator: &str,
+) -> Result<(), MyError> {
+    let mut existing: HashMap<String, sys_menu::Model> = SysMenu::find()
+        .filter(sys_menu::Column::ParentId.eq(menu_id))
+        .filter(sys_menu::Column::Type.eq(Type::Button))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|button| (button.route_name.clone().unwrap_or_default(), button))
+        .collect();
+
+    for (index, button) in buttons.into_iter().enumerate() {
+        let order = Some(index as i32);
+        match existing.remove(&button.code) {
+            Some(model)
+                if model.menu_name.as_deref() == Some(button.desc.as_str())
+                    && model.order == order =>
+            {
+                continue;
+            }
+            Some(model) => {
+                let mut active_button: sys_menu::ActiveModel = model.into();
+                active_button.menu_name = Set(Some(button.desc));
+                active_button.order = Set(order);
+                active_button.update_user = Set(Some(operator.to_string()));
+                active_button.update_time = Set(Some(Utc::now()));
+                active_button.update(db).await?;
+            }
+            None => {
+                sys_menu::ActiveModel {
+                    parent_id: Set(Some(menu_id)),
+                    r#type: Set(Type::Button),
+                    menu_name: Set(Some(button.desc)),
+                    route_name: Set(Some(button.code)),
+                    constant: Set(0),
+                    order: Set(order),
+                    hide_in_menu: Set(Some(1)),
+                    status: Set(Status::Enable as i8),
+                    create_user: Set(operator.to_string()),
+                    create_time: Set(Some(Utc::now())),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await?;
+            }
+        }
+    }
+
+    // 请求中不再包含的按钮连同其权限关联一并删除
+    let removed_ids: Vec<i32> = existing.into_values().map(|button| button.id).collect();
+    if !removed_ids.is_empty() {
+        delete_buttons_by_ids(db, removed_ids).await?;
+    }
+    Ok(())
+}
+
+//delete_buttons_by_ids 删除按钮及其菜单类型的权限关联
+async fn delete_buttons_by_ids<C: ConnectionTrait>(
+    db: &C,
+    button_ids: Vec<i32>,
+) -> Result<(), DbErr> {
+    admin::sys_permission_target::Entity::delete_many()
+        .filter(admin::sys_permission_target::Column::TargetId.is_in(button_ids.clone()))
+        .filter(admin::sys_permission_target::Column::TargetType.eq(TargetType::Menu))
+        .exec(db)
+        .await?;
+    SysMenu::delete_many()
+        .filter(sys_menu::Column::Id.is_in(button_ids))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+//delete_menu_buttons 删除菜单下的所有按钮
+async fn delete_menu_buttons<C: ConnectionTrait>(db: &C, menu_ids: Vec<i32>) -> Result<(), DbErr> {
+    let button_ids: Vec<i32> = SysMenu::find()
+        .select_only()
+        .column(sys_menu::Column::Id)
+        .filter(sys_menu::Column::ParentId.is_in(menu_ids))
+        .filter(sys_menu::Column::Type.eq(Type::Button))
+        .into_tuple()
+        .all(db)
+        .await?;
+    if button_ids.is_empty() {
+        return Ok(());
+    }
+    delete_buttons_by_ids(db, button_ids).await
+}
+
+//get_menu_buttons 获取菜单下的按钮，按菜单ID分组
+pub async fn get_menu_buttons(
+    db: &DatabaseConnection,
+    menu_ids: Vec<i32>,
+) -> Result<HashMap<i32, Vec<MenuButton>>, DbErr> {
+    let mut buttons: HashMap<i32, Vec<MenuButton>> = HashMap::new();
+    if menu_ids.is_empty() {
+        return Ok(buttons);
+    }
+    let models = SysMenu::find()
+        .filter(sys_menu::Column::ParentId.is_in(menu_ids))
+        .filter(sys_menu::Column::Type.eq(Type::Button))
+        .order_by_asc(sys_menu::Column::Order)
+        .order_by_asc(sys_menu::Column::Id)
+        .all(db)
+        .await?;
+    for model in models {
+        if let Some(parent_id) = model.parent_id {
+            buttons.entry(parent_id).or_default().push(MenuButton {
+                code: model.route_name.unwrap_or_default(),
+                desc: model.menu_name.unwrap_or_default(),
+            });
+        }
+    }
+    Ok(buttons)
+}
+
+//to_menu_resp_dtos 转换为菜单响应，按钮不作为独立菜单返回，而是挂在所属菜单的 buttons 下
+pub async fn to_menu_resp_dtos(
+    db: &DatabaseConnection,
+    menus: Vec<sys_menu::Model>,
+) -> Result<Vec<MenuBaseRespDto>, DbErr> {
+    let menus: Vec<sys_menu::Model> = menus
+        .into_iter()
+        .filter(|menu| menu.r#type != Type::Button)
+        .collect();
+    let mut buttons = get_menu_buttons(db, menus.iter().map(|menu| menu.id).collect()).await?;
+    Ok(menus
+        .into_iter()
+        .map(|menu| {
+            let menu_buttons = buttons.remove(&menu.id).unwrap_or_default();
+            let mut menu_dto = MenuBaseRespDto::from(menu);
+            menu_dto.buttons = Some(menu_buttons);
+            menu_dto
+        })
+        .collect())
+}
+
+//to_menu_resp_dto 转换单个菜单响应，附带其按钮
+pub async fn to_menu_resp_dto(
+    db: &DatabaseConnection,
+    menu: sys_menu::Model,
+) -> Result<MenuBaseRespDto, DbErr> {
+    let mut buttons = get_menu_buttons(db, vec![menu.id]).await?;
+    let menu_buttons = buttons.remove(&menu.id).unwrap_or_default();
+    let mut menu_dto = MenuBaseRespDto::from(menu);
+    menu_dto.buttons = Some(menu_buttons);
+    Ok(menu_dto)
 }
 
 
 
 //delete_menu 删除菜单
 pub async fn delete_menu(db: &DatabaseConnection, menu_id: i32) -> Result<u64, DbErr> {
-    // 首先，尝试更新所有引用该菜单ID作为parent_id的子菜单，
+    let txn = db.begin().await?;
+    // 先删除菜单下的按钮及其权限关联
+    delete_menu_buttons(&txn, vec![menu_id]).await?;
+
+    // 然后，尝试更新所有引用该菜单ID作为parent_id的子菜单，
     // 将它们的parent_id设置为NULL（或者您可以选择删除这些子菜单）
     let _ = SysMenu::update_many()
         .col_expr(sys_menu::Column::ParentId, Expr::value(None::<i32>))
         .filter(sys_menu::Column::ParentId.eq(menu_id))
-        .exec(db)
+        .exec(&txn)
         .await?;
 
-    // 然后，尝试删除目标菜单项
+    // 最后，尝试删除目标菜单项
     let menu = sys_menu::ActiveModel {
         id: Set(menu_id),
         ..Default::default()
     };
 
-    SysMenu::delete(menu)
-        .exec(db)
-        .await
-        .map(|res| res.rows_affected)
+    let rows_affected = SysMenu::delete(menu).exec(&txn).await?.rows_affected;
+    txn.commit().await?;
+    Ok(rows_affected)
 }
 
 pub async fn delete_menus(db: &DatabaseConnection, menu_ids: Vec<i32>) -> Result<u64, DbErr> {
-    // 步骤1: 更新所有引用这些菜单ID作为parent_id的子菜单
+    let txn = db.begin().await?;
+    // 步骤1: 删除这些菜单下的按钮及其权限关联
+    delete_menu_buttons(&txn, menu_ids.clone()).await?;
+
+    // 步骤2: 更新所有引用这些菜单ID作为parent_id的子菜单
     let update_children_result = SysMenu::update_many()
         .col_expr(sys_menu::Column::ParentId, Expr::value(None::<i32>))
         .filter(sys_menu::Column::ParentId.is_in(menu_ids.clone()))
-        .exec(db)
+        .exec(&txn)
         .await?;
     let children_updated = update_children_result.rows_affected;
 
-    // 步骤2: 删除这些菜单项
+    // 步骤3: 删除这些菜单项
     let delete_menus_result = SysMenu::delete_many()
         .filter(sys_menu::Column::Id.is_in(menu_ids))
-        .exec(db)
+        .exec(&txn)
         .await?;
     let menus_deleted = delete_menus_result.rows_affected;
+    txn.commit().await?;
 
     // 返回总共影响的行数
     Ok(children_updated + menus_deleted)
@@ -272,3 +501,53 @@ pub fn build_menu_tree(menus: Vec<sys_menu::Model>) -> Rc<RefCell<MenuTreeRespon
 
     root
 }
+
+//get_button_codes_by_user_id 获取用户已启用角色和直接授权关联到的按钮编码，被拒绝的按钮不返回
+pub async fn get_button_codes_by_user_id(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<String>, MyError> {
+    // 步骤1: 获取用户已启用角色的ID
+    let role_ids: Vec<i32> = admin::sys_user_role::Entity::find()
+        .select_only()
+        .column(admin::sys_user_role::Column::RoleId)
+        .join(
+            JoinType::InnerJoin,
+            admin::sys_user_role::Relation::SysRole.def(),
+        )
+        .filter(admin::sys_user_role::Column::UserId.eq(user_id))
+        .filter(admin::sys_role::Column::Status.eq(Status::Enable as i8))
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    // 步骤2: 获取角色及其继承的角色、用户直接授权合并后的已启用权限
+    let role_ids = sys_role_services::get_effective_role_ids(db, role_ids).await?;
+    let permission_set =
+        sys_authorization_services::get_permission_set(db, user_id, role_ids).await?;
+
+    // 步骤3: 获取权限对应的菜单ID，拒绝优先
+    let menu_ids =
+        sys_authorization_services::get_menu_ids_by_permission_set(db, &permission_set).await?;
+    if menu_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // 步骤4: 按钮类型菜单的 route_name 即按钮编码
+    let mut buttons: Vec<String> = SysMenu::find()
+        .select_only()
+        .column(sys_menu::Column::RouteName)
+        .filter(sys_menu::Column::Id.is_in(menu_ids))
+        .filter(sys_menu::Column::Type.eq(Type::Button))
+        .filter(sys_menu::Column::Status.eq(Status::Enable as i8))
+        .filter(sys_menu::Column::RouteName.is_not_null())
+        .into_tuple::<Option<String>>()
+        .all(db)
+        .await?
+        .into_iter()
+        .flatten()
+        .collect();
+    buttons.sort();
+    buttons.dedup();
+    Ok(buttons)
+}
@@ -0,0 +1,359 @@
+use crate::common::auth::crypto::{generate_random_token, hash_password};
+use crate::common::auth::oidc::{self, OidcClient};
+use crate::common::error::MyError;
+use crate::common::redis::utils::RedisOps;
+use crate::config::cfg::OidcProviderConfig;
+use crate::config::globals;
+use crate::dto::admin::sys_oidc_dto::{OidcAuthorizeRespDto, OidcProviderDto};
+use crate::schemas::admin::prelude::{SysRole, SysUser, SysUserIdentity, SysUserRole};
+use crate::schemas::admin::sea_orm_active_enums::Gender;
+use crate::schemas::admin::{sys_role, sys_user, sys_user_identity, sys_user_role};
+use crate::services::admin::sys_route_services::Status;
+use crate::services::admin::{sys_role_services, sys_user_services};
+use actix::Addr;
+use actix_redis::RedisActor;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::time::Duration;
+
+const STATE_KEY_PREFIX: &str = "auth:oidc_state:";
+const STATE_LENGTH: usize = 48;
+const NONCE_LENGTH: usize = 32;
+const UNUSABLE_PASSWORD_LENGTH: usize = 64;
+const USER_NAME_MAX_LEN: usize = 64;
+const NICK_NAME_MAX_LEN: usize = 64;
+const EMAIL_MAX_LEN: usize = 128;
+const SUBJECT_MAX_LEN: usize = 255;
+
+// 所有提供方共用的 OIDC 客户端，缓存 discovery 文档和 JWKS
+static OIDC_CLIENT: Lazy<OidcClient> = Lazy::new(|| {
+    let config = &globals::APP_CONFIG.oidc;
+    OidcClient::new(
+        Duration::from_secs(config.http_timeout),
+        Duration::from_secs(config.metadata_cache_ttl),
+    )
+});
+
+// 发起登录时保存在 Redis 中的状态，回调时取出一次后删除
+#[derive(Debug, Serialize, Deserialize)]
+struct OidcLoginState {
+    provider: String,
+    nonce: String,
+    code_verifier: String,
+}
+
+fn state_key(state: &str) -> String {
+    format!("{}{}", STATE_KEY_PREFIX, state)
+}
+
+fn invalid_state() -> MyError {
+    MyError::AuthError("Invalid or expired OIDC state".to_string())
+}
+
+//find_provider 按名称查找已配置的身份提供方
+pub fn find_provider(name: &str) -> Result<&'static OidcProviderConfig, MyError> {
+    globals::APP_CONFIG
+        .oidc
+        .providers
+        .iter()
+        .find(|provider| provider.name == name)
+        .ok_or_else(|| MyError::NotFound(format!("OIDC provider {} not found", name)))
+}
+
+//list_providers 列出已配置的身份提供方
+pub fn list_providers() -> Vec<OidcProviderDto> {
+    globals::APP_CONFIG
+        .oidc
+        .providers
+        .iter()
+        .map(|provider| OidcProviderDto {
+            name: provider.name.clone(),
+            display_name: provider.display_name.clone(),
+        })
+        .collect()
+}
+
+//start_login 生成 state、nonce 和 PKCE 参数并返回授权地址
+pub async fn start_login(
+    redis: &Addr<RedisActor>,
+    provider: &OidcProviderConfig,
+) -> Result<OidcAuthorizeRespDto, MyError> {
+    let metadata = OIDC_CLIENT.metadata(provider).await?;
+    let pkce = oidc::generate_pkce();
+    let state = generate_random_token(STATE_LENGTH);
+    let login_state = OidcLoginState {
+        provider: provider.name.clone(),
+        nonce: generate_random_token(NONCE_LENGTH),
+        code_verifier: pkce.verifier,
+    };
+    let authorization_url = oidc::build_authorization_url(
+        provider,
+        &metadata,
+        &state,
+        &login_state.nonce,
+        &pkce.challenge,
+    )?;
+    let value =
+        serde_json::to_string(&login_state).map_err(|e| MyError::ConversionError(e.to_string()))?;
+    RedisOps::set_ex(
+        redis,
+        &state_key(&state),
+        &value,
+        globals::APP_CONFIG.oidc.state_expire_time,
+    )
+    .await?;
+    Ok(OidcAuthorizeRespDto { authorization_url })
+}
+
+// 取出并删除登录状态，删除失败说明已被并发的回调使用
+async fn consume_state(
+    redis: &Addr<RedisActor>,
+    provider: &OidcProviderConfig,
+    state: &str,
+) -> Result<OidcLoginState, MyError> {
+    let key = state_key(state);
+    let value = RedisOps::get(redis, &key)
+        .await?
+        .ok_or_else(invalid_state)?;
+    if RedisOps::del(redis, &key).await? == 0 {
+        return Err(invalid_state());
+    }
+    serde_json::from_str::<OidcLoginState>(&value)
+        .ok()
+        .filter(|login_state| login_state.provider == provider.name)
+        .ok_or_else(invalid_state)
+}
+
+//complete_login 校验回调，换取并验证 id_token，返回对应的本地用户
+pub async fn complete_login(
+    db: &DatabaseConnection,
+    redis: &Addr<RedisActor>,
+    provider: &OidcProviderConfig,
+    code: &str,
+    state: &str,
+) -> Result<sys_user::Model, MyError> {
+    let login_state = consume_state(redis, provider, state).await?;
+    let metadata = OIDC_CLIENT.metadata(provider).await?;
+    let token = OIDC_CLIENT
+        .exchange_code(provider, &metadata, code, &login_state.code_verifier)
+        .await?;
+    let id_token = token
+        .id_token
+        .ok_or_else(|| MyError::AuthError("Missing id_token in token response".to_string()))?;
+    let claims = OIDC_CLIENT
+        .verify_id_token(provider, &metadata, &id_token, &login_state.nonce)
+        .await?;
+
+    let user = find_or_provision_user(db, provider, &claims).await?;
+    sync_mapped_roles(db, provider, user.id, &claims).await?;
+    Ok(user)
+}
+
+// 没有邮箱或邮箱未验证、已被占用时使用的占位邮箱
+fn placeholder_email(provider: &OidcProviderConfig, subject: &str) -> String {
+    let digest: String = Sha256::digest(format!("{}:{}", provider.name, subject).as_bytes())
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("{}@{}.oidc.invalid", digest, provider.name)
+}
+
+// 取出已验证的邮箱，未验证的邮箱可能被冒用，不写入用户表
+fn verified_email(claims: &Value) -> Option<&str> {
+    let verified = claims
+        .get("email_verified")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    oidc::claim_str(claims, "email")
+        .filter(|email| verified && !email.is_empty() && email.len() <= EMAIL_MAX_LEN)
+}
+
+// 按绑定关系查找用户，没有绑定时按配置自动创建
+async fn find_or_provision_user(
+    db: &DatabaseConnection,
+    provider: &OidcProviderConfig,
+    claims: &Value,
+) -> Result<sys_user::Model, MyError> {
+    let subject = oidc::claim_str(claims, "sub")
+        .filter(|sub| sub.len() <= SUBJECT_MAX_LEN)
+        .ok_or_else(|| MyError::AuthError("Invalid id_token sub".to_string()))?;
+
+    let identity = SysUserIdentity::find()
+        .filter(sys_user_identity::Column::Provider.eq(&provider.name))
+        .filter(sys_user_identity::Column::Subject.eq(subject))
+        .one(db)
+        .await?;
+    if let Some(identity) = identity {
+        return sys_user_services::get_user_by_id_pure(db, identity.user_id)
+            .await?
+            .ok_or_else(|| MyError::AuthError("User not found".to_string()));
+    }
+
+    if !provider.auto_create_user {
+        return Err(MyError::AuthError(
+            "No local user is linked to this identity".to_string(),
+        ));
+    }
+    let user_name = oidc::claim_str(claims, &provider.user_name_claim)
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && name.chars().count() <= USER_NAME_MAX_LEN)
+        .ok_or_else(|| {
+            MyError::AuthError(format!(
+                "Invalid id_token claim {}",
+                provider.user_name_claim
+            ))
+        })?;
+    // 不自动绑定已存在的同名用户，避免身份提供方的用户名接管本地账号
+    if sys_user_services::find_user_by_username(db, Some(user_name.to_string()))
+        .await?
+        .is_some()
+    {
+        return Err(MyError::AuthError(format!(
+            "User {} already exists and is not linked to this identity",
+            user_name
+        )));
+    }
+
+    let email = match verified_email(claims) {
+        Some(email)
+            if SysUser::find()
+                .filter(sys_user::Column::Email.eq(email))
+                .one(db)
+                .await?
+                .is_none() =>
+        {
+            email.to_string()
+        }
+        _ => placeholder_email(provider, subject),
+    };
+    let nick_name: String = oidc::claim_str(claims, "name")
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or(user_name)
+        .chars()
+        .take(NICK_NAME_MAX_LEN)
+        .collect();
+    // 单点登录用户不使用本地密码，写入无人知道的随机密码
+    let password_hash = hash_password(Some(generate_random_token(UNUSABLE_PASSWORD_LENGTH)))?;
+    let create_user = format!("oidc:{}", provider.name);
+    let default_role_ids =
+        sys_role_services::get_role_ids_by_role_codes(db, provider.default_role_codes.clone())
+            .await?;
+
+    let txn = db.begin().await?;
+    let user = sys_user::ActiveModel {
+        user_name: Set(user_name.to_string()),
+        nick_name: Set(nick_name),
+        password: Set(password_hash),
+        email: Set(email),
+        gender: Set(Gender::_3),
+        status: Set(Status::Enable as i8),
+        create_user: Set(create_user.clone()),
+        create_time: Set(Some(Utc::now())),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    sys_user_identity::ActiveModel {
+        user_id: Set(user.id),
+        provider: Set(provider.name.clone()),
+        subject: Set(subject.to_string()),
+        create_time: Set(Some(Utc::now())),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    if !default_role_ids.is_empty() {
+        let user_roles: Vec<sys_user_role::ActiveModel> = default_role_ids
+            .into_iter()
+            .map(|role_id| sys_user_role::ActiveModel {
+                user_id: Set(user.id),
+                role_id: Set(role_id),
+                create_user: Set(create_user.clone()),
+                create_time: Set(Some(Utc::now())),
+                ..Default::default()
+            })
+            .collect();
+        SysUserRole::insert_many(user_roles).exec(&txn).await?;
+    }
+    txn.commit().await?;
+    Ok(user)
+}
+
+// 按映射规则同步角色：只调整规则中出现的角色，手动分配的其它角色保持不变
+async fn sync_mapped_roles(
+    db: &DatabaseConnection,
+    provider: &OidcProviderConfig,
+    user_id: i32,
+    claims: &Value,
+) -> Result<(), MyError> {
+    if provider.role_mappings.is_empty() {
+        return Ok(());
+    }
+    let matched: HashSet<String> = oidc::map_roles(claims, &provider.role_mappings)
+        .into_iter()
+        .collect();
+    let managed: Vec<String> = provider
+        .role_mappings
+        .iter()
+        .map(|rule| rule.role_code.clone())
+        .collect();
+    let managed_roles = SysRole::find()
+        .filter(sys_role::Column::RoleCode.is_in(managed))
+        .all(db)
+        .await?;
+    let current: HashSet<i32> = SysUserRole::find()
+        .select_only()
+        .column(sys_user_role::Column::RoleId)
+        .filter(sys_user_role::Column::UserId.eq(user_id))
+        .into_tuple::<i32>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut to_add = Vec::new();
+    let mut to_remove = Vec::new();
+    for role in managed_roles {
+        let should_have = matched.contains(&role.role_code) && role.status == Status::Enable as i8;
+        match (should_have, current.contains(&role.id)) {
+            (true, false) => to_add.push(role.id),
+            (false, true) => to_remove.push(role.id),
+            _ => {}
+        }
+    }
+
+    let create_user = format!("oidc:{}", provider.name);
+    let txn = db.begin().await?;
+    if !to_remove.is_empty() {
+        SysUserRole::delete_many()
+            .filter(sys_user_role::Column::UserId.eq(user_id))
+            .filter(sys_user_role::Column::RoleId.is_in(to_remove))
+            .exec(&txn)
+            .await?;
+    }
+    if !to_add.is_empty() {
+        let user_roles: Vec<sys_user_role::ActiveModel> = to_add
+            .into_iter()
+            .map(|role_id| sys_user_role::ActiveModel {
+                user_id: Set(user_id),
+                role_id: Set(role_id),
+                create_user: Set(create_user.clone()),
+                create_time: Set(Some(Utc::now())),
+                ..Default::default()
+            })
+            .collect();
+        SysUserRole::insert_many(user_roles).exec(&txn).await?;
+    }
+    txn.commit().await?;
+    Ok(())
+}
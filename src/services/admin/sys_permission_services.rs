@@ -7,6 +7,7 @@ use crate::schemas::admin::{
     sea_orm_active_enums, sys_api, sys_menu, sys_permission, sys_permission_action,
     sys_permission_target, sys_role_permission,
 };
+use crate::services::admin::sys_authorization_services;
 use chrono::Utc;
 use sea_orm::prelude::Expr;
 use sea_orm::sea_query::{Alias, Query};
@@ -24,6 +25,10 @@ pub async fn create_permission(
     permission_creation_dto: PermissionCreationDto,
     create_user: String,
 ) -> Result<sys_permission::Model, MyError> {
+    let effect = match permission_creation_dto.effect.as_deref() {
+        Some(effect) => sea_orm_active_enums::PermissionEffect::from_string(effect)?,
+        None => sea_orm_active_enums::PermissionEffect::Allow,
+    };
     let transaction = db.begin().await?;
     let permission = sys_permission::ActiveModel {
         permission_name: Set(permission_creation_dto.permission_name),
@@ -32,14 +37,23 @@ pub async fn create_permission(
         create_user: Set(create_user),
         create_time: Set(Some(Utc::now())),
         status: Set(permission_creation_dto.status.parse().unwrap()),
+        effect: Set(effect),
         ..Default::default()
     };
     let inserted_permission = permission.insert(&transaction).await?;
 
-    insert_permission_targets_for_menus(
+    insert_permission_targets(
         &transaction,
         inserted_permission.id,
         permission_creation_dto.menus,
+        sea_orm_active_enums::TargetType::Menu,
+    )
+    .await?;
+    insert_permission_targets(
+        &transaction,
+        inserted_permission.id,
+        permission_creation_dto.apis,
+        sea_orm_active_enums::TargetType::ApiGroup,
     )
     .await?;
     insert_permission_action_codes(
@@ -53,23 +67,24 @@ pub async fn create_permission(
     Ok(inserted_permission)
 }
 
-async fn insert_permission_targets_for_menus(
+//insert_permission_targets 插入关联菜单或接口
+async fn insert_permission_targets(
     transaction: &DatabaseTransaction,
     permission_id: i32,
-    menu_ids: Option<Vec<i32>>,
+    target_ids: Option<Vec<i32>>,
+    target_type: sea_orm_active_enums::TargetType,
 ) -> Result<(), MyError> {
-    if let Some(menu_ids) = menu_ids {
-        let menu_targets: Vec<sys_permission_target::ActiveModel> = menu_ids
+    if let Some(target_ids) = target_ids.filter(|ids| !ids.is_empty()) {
+        let targets: Vec<sys_permission_target::ActiveModel> = target_ids
             .into_iter()
-            .map(|menu_id| sys_permission_target::ActiveModel {
+            .map(|target_id| sys_permission_target::ActiveModel {
                 permission_id: Set(permission_id),
-                target_id: Set(menu_id),
-                target_type: Set(sea_orm_active_enums::TargetType::Menu),
-                ..Default::default()
+                target_id: Set(target_id),
+                target_type: Set(target_type.clone()),
             })
             .collect();
 
-        sys_permission_target::Entity::insert_many(menu_targets)
+        sys_permission_target::Entity::insert_many(targets)
             .exec(transaction)
             .await?;
     }
@@ -150,6 +165,12 @@ pub async fn update_permission(
         active_permission.status = Set(status.parse().unwrap());
         is_modified = true;
     }
+    if let Some(effect) = permission_update_dto.effect {
+        active_permission.effect = Set(sea_orm_active_enums::PermissionEffect::from_string(
+            &effect,
+        )?);
+        is_modified = true;
+    }
 
     if is_modified {
         active_permission.update_user = Set(Some(update_user));
@@ -164,10 +185,27 @@ pub async fn update_permission(
             sea_orm_active_enums::TargetType::Menu,
         )
         .await?;
-        insert_permission_targets_for_menus(
+        insert_permission_targets(
             &transaction,
             permission_id,
             permission_update_dto.menus,
+            sea_orm_active_enums::TargetType::Menu,
+        )
+        .await?;
+    }
+
+    if permission_update_dto.apis.is_some() {
+        delete_permission_targets(
+            &transaction,
+            permission_id,
+            sea_orm_active_enums::TargetType::ApiGroup,
+        )
+        .await?;
+        insert_permission_targets(
+            &transaction,
+            permission_id,
+            permission_update_dto.apis,
+            sea_orm_active_enums::TargetType::ApiGroup,
         )
         .await?;
     }
@@ -185,6 +223,7 @@ pub async fn update_permission(
     }
 
     transaction.commit().await?;
+    sys_authorization_services::invalidate_permission_cache().await;
     Ok(())
 }
 
@@ -256,6 +295,7 @@ pub async fn delete_permission(db: &DatabaseConnection, permission_id: i32) -> R
 
     // 提交事务
     txn.commit().await?;
+    sys_authorization_services::invalidate_permission_cache().await;
 
     Ok(rows_affected)
 }
@@ -331,13 +371,14 @@ pub async fn get_paginated_permissions_with_menus_apis(
         (sys_permission::Entity, sys_permission::Column::PermissionCode),
         (sys_permission::Entity, sys_permission::Column::Description),
         (sys_permission::Entity, sys_permission::Column::Status),
+        (sys_permission::Entity, sys_permission::Column::Effect),
     ])
         .expr_as(
             Expr::cust("GROUP_CONCAT(DISTINCT sys_menu.id SEPARATOR ',')"),
             Alias::new("menus")
         )
         .expr_as(
-            Expr::cust("GROUP_CONCAT(DISTINCT CASE WHEN target_type = 'API' THEN CONCAT(sys_api.api_name, ':', sys_api.id) END SEPARATOR ',')"),
+            Expr::cust("GROUP_CONCAT(DISTINCT CASE WHEN target_type = 'API_GROUP' THEN CONCAT(sys_api.api_name, ':', sys_api.id) END SEPARATOR ',')"),
             Alias::new("apis")
         ).expr_as(
             Expr::cust("GROUP_CONCAT(DISTINCT sys_permission_action.action_code SEPARATOR ',')"),
@@ -379,6 +420,7 @@ pub async fn get_paginated_permissions_with_menus_apis(
         .map(|row| {
             let permission_id: i32 = row.try_get_by("id").unwrap_or_default();
             let status: i32 = row.try_get_by("status").unwrap_or_default();
+            let effect: String = row.try_get_by("effect").unwrap_or_default();
             let permission_name: String = row.try_get_by("permission_name").unwrap_or_default();
             let permission_code: String = row.try_get_by("permission_code").unwrap_or_default();
             let description: String = row.try_get_by("description").unwrap_or_default();
@@ -424,6 +466,7 @@ pub async fn get_paginated_permissions_with_menus_apis(
                 menus: menu_details,
                 apis: api_details,
                 status: status.to_string(),
+                effect,
             }
         })
         .collect();
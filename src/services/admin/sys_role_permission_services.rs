@@ -1,6 +1,9 @@
 use crate::common::auth::jwt::MenuInfo;
 use crate::schemas::admin::prelude::SysRolePermission;
-use crate::schemas::admin::{sys_menu, sys_role, sys_role_permission};
+use crate::schemas::admin::sea_orm_active_enums::PermissionEffect;
+use crate::schemas::admin::{sys_menu, sys_permission, sys_role, sys_role_permission};
+use crate::services::admin::{sys_authorization_services, sys_role_services};
+use crate::services::admin::sys_route_services::Status;
 use chrono::Utc;
 use sea_orm::ActiveValue::Set;
 use sea_orm::{
@@ -31,6 +34,7 @@ pub async fn assign_permissions_to_role(
     sys_role_permission::Entity::insert_many(role_permissions)
         .exec(db)
         .await?;
+    sys_authorization_services::invalidate_permission_cache().await;
 
     // Fetch the inserted records. This needs to be modified based on how your database and ORM handle it.
     let inserted_permissions = sys_role_permission::Entity::find()
@@ -64,6 +68,30 @@ pub async fn get_role_permissions(
         .await
 }
 
+//get_enabled_permissions_by_role_ids 获取角色关联的已启用权限ID及其效果，禁用的权限不参与授权
+pub async fn get_enabled_permissions_by_role_ids(
+    db: &DatabaseConnection,
+    role_ids: Vec<i32>,
+) -> Result<Vec<(i32, PermissionEffect)>, DbErr> {
+    if role_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    SysRolePermission::find()
+        .select_only()
+        .column(sys_role_permission::Column::PermissionId)
+        .column(sys_permission::Column::Effect)
+        .distinct()
+        .join(
+            JoinType::InnerJoin,
+            sys_role_permission::Relation::SysPermission.def(),
+        )
+        .filter(sys_role_permission::Column::RoleId.is_in(role_ids))
+        .filter(sys_permission::Column::Status.eq(Status::Enable as i8))
+        .into_tuple()
+        .all(db)
+        .await
+}
+
 //remove_permission_from_role 删除角色的权限
 
 pub async fn remove_permission_from_role(
@@ -72,18 +100,22 @@ pub async fn remove_permission_from_role(
     permission_id: i32,
 ) -> Result<u64, DbErr> {
     // 使用 delete_many 方法并结合过滤条件来删除记录
-    SysRolePermission::delete_many()
+    let rows_affected = SysRolePermission::delete_many()
         .filter(sys_role_permission::Column::RoleId.eq(role_id))
         .filter(sys_role_permission::Column::PermissionId.eq(permission_id))
         .exec(db)
-        .await
-        .map(|res| res.rows_affected)
+        .await?
+        .rows_affected;
+    sys_authorization_services::invalidate_permission_cache().await;
+    Ok(rows_affected)
 }
 
+//get_menus_by_role_id 获取角色及其继承的角色关联的菜单
 pub async fn get_menus_by_role_id(
     db: &DatabaseConnection,
     role_ids: Vec<i32>,
 ) -> Result<Vec<MenuInfo>, DbErr> {
+    let role_ids = sys_role_services::get_effective_role_ids(db, role_ids).await?;
     let menus = SysRolePermission::find()
         .filter(sys_role_permission::Column::RoleId.is_in(role_ids))
         .join(
@@ -1,12 +1,19 @@
+use crate::common::auth::role_hierarchy::{RoleHierarchy, RoleNode};
+use crate::common::error::MyError;
 use crate::dto::admin::common_dto::PaginationResponseDto;
-use crate::dto::admin::sys_role_dto::{ RoleCreationDto, RoleCreationResponseDto, RoleDto, RoleOptionDto, RoleUpdateDto};
-use crate::schemas::admin::prelude::SysRole;
-use crate::schemas::admin::{sys_role, sys_role_permission};
+use crate::dto::admin::sys_role_dto::{ RoleCreationDto, RoleCreationResponseDto, RoleDto, RoleOptionDto, RoleTreeDto, RoleUpdateDto};
+use crate::schemas::admin::prelude::{SysDept, SysRole, SysRoleDept};
+use crate::schemas::admin::sea_orm_active_enums::DataScope;
+use crate::schemas::admin::{sys_dept, sys_role, sys_role_dept, sys_role_permission};
+use crate::services::admin::sys_authorization_services;
+use crate::services::admin::sys_route_services::Status;
+use chrono::Utc;
 use sea_orm::sea_query::{MysqlQueryBuilder, Query};
 use sea_orm::ActiveValue::Set;
 use sea_orm::PaginatorTrait;
 use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait};
 use sea_orm::{ConnectionTrait, QueryFilter, QuerySelect, Statement, TransactionTrait};
+use std::collections::HashMap;
 
 
 //create_role 创建角色
@@ -14,17 +21,30 @@ pub async fn create_role(
     db: &DatabaseConnection,
     create_user: String,
     role_create_info: RoleCreationDto,
-) -> Result<RoleCreationResponseDto, DbErr> {
+) -> Result<RoleCreationResponseDto, MyError> {
     // Start a transaction
     let txn = db.begin().await?;
+    let parent_role_id = match role_create_info.parent_role_id {
+        Some(parent_role_id) => check_parent_role(&txn, None, parent_role_id).await?,
+        None => None,
+    };
+    let data_scope = match role_create_info.data_scope.as_deref() {
+        Some(data_scope) => DataScope::from_string(data_scope)?,
+        None => DataScope::All,
+    };
 
     // Insert the role
     let role = sys_role::ActiveModel {
         role_name: Set(role_create_info.role_name.clone()),
         description: Set(Some(role_create_info.role_desc.clone())),
+        home: Set(role_create_info.role_home.clone()),
+        require_two_factor: Set(role_create_info.require_two_factor.unwrap_or(false) as i8),
+        parent_role_id: Set(parent_role_id),
+        data_scope: Set(data_scope.clone()),
         role_code: Set(role_create_info.role_code.clone()),
         status: Set(role_create_info.status.parse().unwrap()),
         create_user: Set(create_user.clone()),
+        create_time: Set(Some(Utc::now())),
         ..Default::default()
     };
 
@@ -37,6 +57,7 @@ pub async fn create_role(
                 role_id: Set(inserted_role.id),
                 permission_id: Set(permission_id),
                 create_user: Set(create_user.clone()),
+                create_time: Set(Some(Utc::now())),
                 ..Default::default()
             };
 
@@ -44,8 +65,15 @@ pub async fn create_role(
         }
     }
 
+    // 自定义数据权限的部门
+    if data_scope == DataScope::Custom {
+        let dept_ids = role_create_info.data_scope_dept_ids.unwrap_or_default();
+        save_role_depts(&txn, inserted_role.id, dept_ids).await?;
+    }
+
     // Commit the transaction
     txn.commit().await?;
+    sys_authorization_services::invalidate_permission_cache().await;
 
     // Query inserted role with permissions
     let permissions = sys_role_permission::Entity::find()
@@ -58,14 +86,11 @@ pub async fn create_role(
         .map(|rp| rp.permission_id)
         .collect::<Vec<i32>>();
 
-    let role_dto = RoleDto {
-        id: Some(inserted_role.id),
-        role_code: Some(inserted_role.role_code),
-        role_name: Some(inserted_role.role_name),
-        permission_ids: Some(permission_ids),
-        role_desc: inserted_role.description,
-        status: inserted_role.status.to_string(),
-    };
+    let mut role_dto = RoleDto::from(inserted_role);
+    role_dto.permission_ids = Some(permission_ids);
+    let hierarchy = load_role_hierarchy(db).await?;
+    fill_inherited_permission_ids(db, &hierarchy, std::slice::from_mut(&mut role_dto)).await?;
+    fill_data_scope_dept_ids(db, std::slice::from_mut(&mut role_dto)).await?;
 
     Ok(RoleCreationResponseDto { base: role_dto })
 }
@@ -104,6 +129,9 @@ pub async fn get_roles(
 
         role_all_dto.push(role_dto);
     }
+    let hierarchy = load_role_hierarchy(db).await?;
+    fill_inherited_permission_ids(db, &hierarchy, &mut role_all_dto).await?;
+    fill_data_scope_dept_ids(db, &mut role_all_dto).await?;
 
     Ok(PaginationResponseDto::new(
         current as u64,
@@ -133,6 +161,10 @@ pub async fn get_role_by_id(
 
         let mut role_dto = RoleDto::from(role);
         role_dto.permission_ids = Some(permission_ids);
+        let hierarchy = load_role_hierarchy(db).await?;
+        fill_inherited_permission_ids(db, &hierarchy, std::slice::from_mut(&mut role_dto))
+            .await?;
+        fill_data_scope_dept_ids(db, std::slice::from_mut(&mut role_dto)).await?;
 
         Ok(Some(RoleCreationResponseDto { base: role_dto }))
     } else {
@@ -144,16 +176,18 @@ pub async fn update_role(
     db: &DatabaseConnection,
     role_id: i32,
     role_update_info: RoleUpdateDto,
-) -> Result<RoleCreationResponseDto, DbErr> {
+    update_user: String,
+) -> Result<RoleCreationResponseDto, MyError> {
     // Start a transaction
     let txn = db.begin().await?;
 
     // Find the role by ID
-    let role_opt = SysRole::find_by_id(role_id).one(&txn).await?;
-    let mut role: sys_role::ActiveModel = match role_opt {
-        Some(role) => role.into(),
-        None => return Err(DbErr::RecordNotFound("Role not found".to_string())),
-    };
+    let existing_role = SysRole::find_by_id(role_id)
+        .one(&txn)
+        .await?
+        .ok_or(MyError::NotFound("Role not found".to_string()))?;
+    let existing_data_scope = existing_role.data_scope.clone();
+    let mut role: sys_role::ActiveModel = existing_role.into();
 
     // Update the role fields
     if let Some(rn) = role_update_info.role_name {
@@ -162,12 +196,28 @@ pub async fn update_role(
     if let Some(dsc) = role_update_info.role_desc {
         role.description = Set(Some(dsc));
     }
+    if let Some(home) = role_update_info.role_home {
+        role.home = Set(Some(home));
+    }
+    if let Some(require_two_factor) = role_update_info.require_two_factor {
+        role.require_two_factor = Set(require_two_factor as i8);
+    }
+    if let Some(parent_role_id) = role_update_info.parent_role_id {
+        role.parent_role_id = Set(check_parent_role(&txn, Some(role_id), parent_role_id).await?);
+    }
+    let data_scope = match role_update_info.data_scope.as_deref() {
+        Some(data_scope) => DataScope::from_string(data_scope)?,
+        None => existing_data_scope,
+    };
+    role.data_scope = Set(data_scope.clone());
     if let Some(code) = role_update_info.role_code {
         role.role_code = Set(code);
     }
     if let Some(status) = role_update_info.status {
         role.status = Set(status.parse().unwrap());
     }
+    role.update_user = Set(Some(update_user.clone()));
+    role.update_time = Set(Some(Utc::now()));
     role.update(&txn).await?;
 
     // Update role permissions
@@ -183,7 +233,8 @@ pub async fn update_role(
             let role_permission = sys_role_permission::ActiveModel {
                 role_id: Set(role_id),
                 permission_id: Set(permission_id),
-                create_user: Set("update_user".to_string()), // Update this to the actual user if needed
+                create_user: Set(update_user.clone()),
+                create_time: Set(Some(Utc::now())),
                 ..Default::default()
             };
 
@@ -191,8 +242,16 @@ pub async fn update_role(
         }
     }
 
+    // 非自定义数据权限时清除部门关联，避免切换回 CUSTOM 时沿用旧数据
+    if data_scope != DataScope::Custom {
+        save_role_depts(&txn, role_id, Vec::new()).await?;
+    } else if let Some(dept_ids) = role_update_info.data_scope_dept_ids {
+        save_role_depts(&txn, role_id, dept_ids).await?;
+    }
+
     // Commit the transaction
     txn.commit().await?;
+    sys_authorization_services::invalidate_permission_cache().await;
 
     // Query the updated role with permissions
     let updated_role = SysRole::find_by_id(role_id).one(db).await?.unwrap();
@@ -208,6 +267,9 @@ pub async fn update_role(
 
     let mut role_dto = RoleDto::from(updated_role);
     role_dto.permission_ids = Some(permission_ids);
+    let hierarchy = load_role_hierarchy(db).await?;
+    fill_inherited_permission_ids(db, &hierarchy, std::slice::from_mut(&mut role_dto)).await?;
+    fill_data_scope_dept_ids(db, std::slice::from_mut(&mut role_dto)).await?;
 
     Ok(RoleCreationResponseDto { base: role_dto })
 }
@@ -218,10 +280,9 @@ pub async fn delete_role(db: &DatabaseConnection, role_id: i32) -> Result<u64, D
         id: Set(role_id),
         ..Default::default()
     };
-    SysRole::delete(role)
-        .exec(db)
-        .await
-        .map(|res| res.rows_affected)
+    let rows_affected = SysRole::delete(role).exec(db).await?.rows_affected;
+    sys_authorization_services::invalidate_permission_cache().await;
+    Ok(rows_affected)
 }
 
 // src/services/sys_role_services.rs
@@ -258,13 +319,14 @@ pub async fn delete_roles(db: &DatabaseConnection, role_ids: Vec<i32>) -> Result
             values,
         ))
         .await?;
+    sys_authorization_services::invalidate_permission_cache().await;
 
     // 返回影响的行数
     Ok(result.rows_affected())
 }
 
 
-// 根据 role_code 数组返回所有匹配的 id
+// 根据 role_code 数组返回所有匹配且已启用的角色 id
 pub async fn get_role_ids_by_role_codes(
     db: &DatabaseConnection,
     role_codes: Vec<String>, // role_code 数组
@@ -272,6 +334,7 @@ pub async fn get_role_ids_by_role_codes(
     // 假设 id 类型为 i32
     let roles = SysRole::find()
         .filter(sys_role::Column::RoleCode.is_in(role_codes)) // 使用 is_in 方法来过滤 role_code
+        .filter(sys_role::Column::Status.eq(Status::Enable as i8))
         .all(db)
         .await?;
 
@@ -282,3 +345,251 @@ pub async fn get_role_ids_by_role_codes(
 
     Ok(ids)
 }
+
+//load_role_hierarchy 加载全部角色的继承关系
+pub async fn load_role_hierarchy<C: ConnectionTrait>(db: &C) -> Result<RoleHierarchy, DbErr> {
+    let roles = SysRole::find()
+        .select_only()
+        .column(sys_role::Column::Id)
+        .column(sys_role::Column::ParentRoleId)
+        .column(sys_role::Column::Status)
+        .into_tuple::<(i32, Option<i32>, i8)>()
+        .all(db)
+        .await?;
+    Ok(RoleHierarchy::new(roles.into_iter().map(
+        |(id, parent_role_id, status)| RoleNode {
+            id,
+            parent_role_id,
+            enabled: status == Status::Enable as i8,
+        },
+    )))
+}
+
+//get_effective_role_ids 返回角色自身及其继承链上所有已启用角色的 id
+pub async fn get_effective_role_ids(
+    db: &DatabaseConnection,
+    role_ids: Vec<i32>,
+) -> Result<Vec<i32>, DbErr> {
+    if role_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(load_role_hierarchy(db).await?.effective_role_ids(&role_ids))
+}
+
+//get_effective_role_ids_by_role_codes 根据 role_code 数组返回已启用角色及其继承的角色 id
+pub async fn get_effective_role_ids_by_role_codes(
+    db: &DatabaseConnection,
+    role_codes: Vec<String>,
+) -> Result<Vec<i32>, DbErr> {
+    let role_ids = get_role_ids_by_role_codes(db, role_codes).await?;
+    get_effective_role_ids(db, role_ids).await
+}
+
+// 校验父角色必须存在且不能形成环，parent_role_id 为 0 表示不继承
+async fn check_parent_role<C: ConnectionTrait>(
+    db: &C,
+    role_id: Option<i32>,
+    parent_role_id: i32,
+) -> Result<Option<i32>, MyError> {
+    if parent_role_id == 0 {
+        return Ok(None);
+    }
+    let hierarchy = load_role_hierarchy(db).await?;
+    if hierarchy.get(parent_role_id).is_none() {
+        return Err(MyError::BadRequestError(
+            "Parent role not found".to_string(),
+        ));
+    }
+    if role_id.is_some_and(|role_id| hierarchy.would_create_cycle(role_id, parent_role_id)) {
+        return Err(MyError::BadRequestError(
+            "Role inheritance cannot contain a cycle".to_string(),
+        ));
+    }
+    Ok(Some(parent_role_id))
+}
+
+// 获取角色直接关联的权限ID
+async fn get_permission_ids_by_role_ids(
+    db: &DatabaseConnection,
+    role_ids: Vec<i32>,
+) -> Result<HashMap<i32, Vec<i32>>, DbErr> {
+    let mut permission_ids: HashMap<i32, Vec<i32>> = HashMap::new();
+    if role_ids.is_empty() {
+        return Ok(permission_ids);
+    }
+    for role_permission in sys_role_permission::Entity::find()
+        .filter(sys_role_permission::Column::RoleId.is_in(role_ids))
+        .all(db)
+        .await?
+    {
+        permission_ids
+            .entry(role_permission.role_id)
+            .or_default()
+            .push(role_permission.permission_id);
+    }
+    Ok(permission_ids)
+}
+
+// 填充从父角色及其祖先继承的权限，不包含角色自身的权限
+async fn fill_inherited_permission_ids(
+    db: &DatabaseConnection,
+    hierarchy: &RoleHierarchy,
+    role_dtos: &mut [RoleDto],
+) -> Result<(), DbErr> {
+    let chains: Vec<Vec<i32>> = role_dtos
+        .iter()
+        .map(|role_dto| {
+            role_dto
+                .parent_role_id
+                .map(|parent_role_id| hierarchy.inheritance_chain(parent_role_id))
+                .unwrap_or_default()
+        })
+        .collect();
+    let mut ancestor_ids: Vec<i32> = chains.iter().flatten().copied().collect();
+    ancestor_ids.sort_unstable();
+    ancestor_ids.dedup();
+    let permission_ids = get_permission_ids_by_role_ids(db, ancestor_ids).await?;
+
+    for (role_dto, chain) in role_dtos.iter_mut().zip(chains) {
+        let mut inherited: Vec<i32> = chain
+            .iter()
+            .filter_map(|role_id| permission_ids.get(role_id))
+            .flatten()
+            .copied()
+            .collect();
+        inherited.sort_unstable();
+        inherited.dedup();
+        role_dto.inherited_permission_ids = Some(inherited);
+    }
+    Ok(())
+}
+
+// 替换角色自定义数据权限的部门，部门必须存在
+async fn save_role_depts<C: ConnectionTrait>(
+    db: &C,
+    role_id: i32,
+    dept_ids: Vec<i32>,
+) -> Result<(), MyError> {
+    let mut dept_ids = dept_ids;
+    dept_ids.sort_unstable();
+    dept_ids.dedup();
+    if !dept_ids.is_empty() {
+        let found = SysDept::find()
+            .filter(sys_dept::Column::Id.is_in(dept_ids.clone()))
+            .count(db)
+            .await?;
+        if found != dept_ids.len() as u64 {
+            return Err(MyError::BadRequestError(
+                "Department not found".to_string(),
+            ));
+        }
+    }
+
+    SysRoleDept::delete_many()
+        .filter(sys_role_dept::Column::RoleId.eq(role_id))
+        .exec(db)
+        .await?;
+    if dept_ids.is_empty() {
+        return Ok(());
+    }
+    SysRoleDept::insert_many(dept_ids.into_iter().map(|dept_id| sys_role_dept::ActiveModel {
+        role_id: Set(role_id),
+        dept_id: Set(dept_id),
+        ..Default::default()
+    }))
+    .exec(db)
+    .await?;
+    Ok(())
+}
+
+//get_role_dept_ids 获取角色自定义数据权限的部门ID，按角色ID分组
+pub async fn get_role_dept_ids(
+    db: &DatabaseConnection,
+    role_ids: Vec<i32>,
+) -> Result<HashMap<i32, Vec<i32>>, DbErr> {
+    let mut dept_ids: HashMap<i32, Vec<i32>> = HashMap::new();
+    if role_ids.is_empty() {
+        return Ok(dept_ids);
+    }
+    for role_dept in SysRoleDept::find()
+        .filter(sys_role_dept::Column::RoleId.is_in(role_ids))
+        .all(db)
+        .await?
+    {
+        dept_ids
+            .entry(role_dept.role_id)
+            .or_default()
+            .push(role_dept.dept_id);
+    }
+    Ok(dept_ids)
+}
+
+// 填充自定义数据权限的部门，非 CUSTOM 的角色为空列表
+async fn fill_data_scope_dept_ids(
+    db: &DatabaseConnection,
+    role_dtos: &mut [RoleDto],
+) -> Result<(), DbErr> {
+    let role_ids = role_dtos
+        .iter()
+        .filter(|role_dto| role_dto.data_scope == DataScope::Custom.as_str())
+        .filter_map(|role_dto| role_dto.id)
+        .collect();
+    let mut dept_ids = get_role_dept_ids(db, role_ids).await?;
+    for role_dto in role_dtos.iter_mut() {
+        let mut role_dept_ids = role_dto
+            .id
+            .and_then(|role_id| dept_ids.remove(&role_id))
+            .unwrap_or_default();
+        role_dept_ids.sort_unstable();
+        role_dto.data_scope_dept_ids = Some(role_dept_ids);
+    }
+    Ok(())
+}
+
+//get_role_tree 获取角色继承树，子角色挂在其父角色下
+pub async fn get_role_tree(db: &DatabaseConnection) -> Result<Vec<RoleTreeDto>, DbErr> {
+    let roles = SysRole::find().all(db).await?;
+    let hierarchy = RoleHierarchy::new(roles.iter().map(|role| RoleNode {
+        id: role.id,
+        parent_role_id: role.parent_role_id,
+        enabled: role.status == Status::Enable as i8,
+    }));
+    let mut own_permission_ids =
+        get_permission_ids_by_role_ids(db, roles.iter().map(|role| role.id).collect()).await?;
+    let mut role_dtos: Vec<RoleDto> = roles
+        .into_iter()
+        .map(|role| {
+            let role_id = role.id;
+            let mut role_dto = RoleDto::from(role);
+            role_dto.permission_ids = Some(own_permission_ids.remove(&role_id).unwrap_or_default());
+            role_dto
+        })
+        .collect();
+    fill_inherited_permission_ids(db, &hierarchy, &mut role_dtos).await?;
+    fill_data_scope_dept_ids(db, &mut role_dtos).await?;
+
+    let mut role_dtos: HashMap<i32, RoleDto> = role_dtos
+        .into_iter()
+        .filter_map(|role_dto| Some((role_dto.id?, role_dto)))
+        .collect();
+    Ok(hierarchy
+        .roots()
+        .into_iter()
+        .filter_map(|role_id| build_role_tree(&hierarchy, &mut role_dtos, role_id))
+        .collect())
+}
+
+// 递归组装角色树，每个角色只出现一次
+fn build_role_tree(
+    hierarchy: &RoleHierarchy,
+    role_dtos: &mut HashMap<i32, RoleDto>,
+    role_id: i32,
+) -> Option<RoleTreeDto> {
+    let base = role_dtos.remove(&role_id)?;
+    let children = hierarchy
+        .children(role_id)
+        .into_iter()
+        .filter_map(|child_id| build_role_tree(hierarchy, role_dtos, child_id))
+        .collect();
+    Some(RoleTreeDto { base, children })
+}
@@ -1,13 +1,17 @@
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::rc::Rc;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryOrder};
 use crate::common::error::MyError;
 use crate::dto::admin::sys_route_dto::{RoleMenuResponseDto, RouteDto};
-use crate::schemas::admin::{sea_orm_active_enums, sys_menu, sys_permission_target, sys_role, sys_role_permission};
+use crate::schemas::admin::{sea_orm_active_enums, sys_menu, sys_role};
+use crate::services::admin::{sys_authorization_services, sys_role_services};
 use sea_orm::QueryFilter;
 use serde_json::json;
 
+// 角色未配置首页时使用的首页路由
+pub const DEFAULT_HOME: &str = "home";
+
 #[repr(i8)]
 pub enum Status {
     Enable = 1, // 将Enable与整数值1关联
@@ -64,47 +68,53 @@ fn build_menu_tree(menus: Vec<sys_menu::Model>) -> Vec<Rc<RefCell<RouteDto>>> {
     roots
 }
 
-// 根据角色代码获取菜单
+// 根据角色代码获取菜单，多个角色时取菜单的并集，并去掉被拒绝的菜单
 pub async fn get_menus_by_role_code(
     db: &DatabaseConnection,
-    role_code: &str
+    user_id: i32,
+    role_codes: &[String],
 ) -> Result<RoleMenuResponseDto, MyError> {
-    // 步骤1: 获取角色ID
-    let role = sys_role::Entity::find()
-        .filter(sys_role::Column::RoleCode.eq(role_code))
-        .one(db)
-        .await?
-        .ok_or(MyError::NotFound("Role not found".to_string()))?;
-
-    // 步骤2: 获取角色权限ID
-    let permissions = sys_role_permission::Entity::find()
-        .filter(sys_role_permission::Column::RoleId.eq(role.id))
-        .all(db)
-        .await?;
-
-    let permission_ids: HashSet<i32> = permissions.into_iter().map(|rp| rp.permission_id).collect();
-
-    // 步骤3: 获取权限对应的菜单ID
-    let permission_targets = sys_permission_target::Entity::find()
-        .filter(sys_permission_target::Column::PermissionId.is_in(permission_ids))
-        .filter(sys_permission_target::Column::TargetType.eq(sea_orm_active_enums::TargetType::Menu))
+    // 步骤1: 获取已启用的角色
+    let roles = sys_role::Entity::find()
+        .filter(sys_role::Column::RoleCode.is_in(role_codes.to_vec()))
+        .filter(sys_role::Column::Status.eq(Status::Enable as i8))
         .all(db)
         .await?;
 
-    let menu_ids: HashSet<i32> = permission_targets.into_iter().map(|pt| pt.target_id).collect();
+    // 首页取第一个配置了首页的角色（按 token 中的角色顺序）
+    let home = role_codes
+        .iter()
+        .filter_map(|code| roles.iter().find(|role| &role.role_code == code))
+        .find_map(|role| role.home.clone().filter(|home| !home.is_empty()))
+        .unwrap_or_else(|| DEFAULT_HOME.to_string());
+
+    // 步骤2: 获取角色及其继承的角色、用户直接授权合并后的已启用权限
+    let role_ids = sys_role_services::get_effective_role_ids(
+        db,
+        roles.iter().map(|role| role.id).collect(),
+    )
+    .await?;
+    let permission_set = sys_authorization_services::get_permission_set(db, user_id, role_ids).await?;
+
+    // 步骤3: 获取权限对应的菜单ID，拒绝优先
+    let menu_ids =
+        sys_authorization_services::get_menu_ids_by_permission_set(db, &permission_set).await?;
 
     // 步骤4: 获取菜单详情
     let menus = sys_menu::Entity::find()
         .filter(sys_menu::Column::Id.is_in(menu_ids))
         .filter(sys_menu::Column::Constant.eq(false))
         .filter(sys_menu::Column::Status.eq(Status::Enable as i8))
+        .filter(sys_menu::Column::Type.ne(sea_orm_active_enums::Type::Button))
+        .order_by_asc(sys_menu::Column::Order)
+        .order_by_asc(sys_menu::Column::Id)
         .all(db)
         .await?;
 
     let roots = build_menu_tree(menus);
 
     let role_menu_resp = RoleMenuResponseDto{
-        home: "home".to_string(),
+        home,
         routes: roots,
     };
     Ok(role_menu_resp)
@@ -117,14 +127,11 @@ pub async fn get_constant_menus(
 
     let menus = sys_menu::Entity::find()
         .filter(sys_menu::Column::Constant.eq(true))
+        .filter(sys_menu::Column::Type.ne(sea_orm_active_enums::Type::Button))
+        .order_by_asc(sys_menu::Column::Order)
+        .order_by_asc(sys_menu::Column::Id)
         .all(db)
         .await?;
 
-    let roots = build_menu_tree(menus);
-
-    let role_menu_resp = RoleMenuResponseDto{
-        home: "home".to_string(),
-        routes: roots,
-    };
-    Ok(role_menu_resp.routes)
+    Ok(build_menu_tree(menus))
 }
@@ -0,0 +1,372 @@
+use crate::common::auth::crypto::generate_random_token;
+use crate::common::auth::jwt::now_timestamp;
+use crate::common::auth::totp;
+use crate::common::error::MyError;
+use crate::common::redis::utils::RedisOps;
+use crate::config::globals;
+use crate::dto::admin::sys_two_factor_dto::TotpSetupDto;
+use crate::schemas::admin::prelude::{SysUser, SysUserRole};
+use crate::schemas::admin::{sys_role, sys_user, sys_user_role};
+use crate::services::admin::sys_route_services::Status;
+use actix::Addr;
+use actix_redis::RedisActor;
+use chrono::Utc;
+use log::warn;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, PaginatorTrait, QueryFilter,
+    QuerySelect, RelationTrait,
+};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+
+const CHALLENGE_KEY_PREFIX: &str = "auth:2fa_challenge:";
+const TWO_FACTOR_FAILURE_KEY_PREFIX: &str = "auth:2fa_failure:";
+const TWO_FACTOR_LOCK_KEY_PREFIX: &str = "auth:2fa_lock:";
+const TOTP_ENROLL_KEY_PREFIX: &str = "auth:totp_enroll:";
+const TOTP_USED_STEP_KEY_PREFIX: &str = "auth:totp_used:";
+const CHALLENGE_TOKEN_LENGTH: usize = 64;
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+fn challenge_key(token: &str) -> String {
+    format!("{}{}", CHALLENGE_KEY_PREFIX, token)
+}
+
+fn two_factor_failure_key(user_id: i32) -> String {
+    format!("{}{}", TWO_FACTOR_FAILURE_KEY_PREFIX, user_id)
+}
+
+fn two_factor_lock_key(user_id: i32) -> String {
+    format!("{}{}", TWO_FACTOR_LOCK_KEY_PREFIX, user_id)
+}
+
+fn totp_enroll_key(user_id: i32) -> String {
+    format!("{}{}", TOTP_ENROLL_KEY_PREFIX, user_id)
+}
+
+fn totp_used_step_key(user_id: i32, step: u64) -> String {
+    format!("{}{}:{}", TOTP_USED_STEP_KEY_PREFIX, user_id, step)
+}
+
+fn invalid_challenge() -> MyError {
+    MyError::AuthError("Invalid or expired two-factor challenge".to_string())
+}
+
+// 恢复码忽略大小写和分隔符后再哈希
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    Sha256::digest(normalized.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// 生成一组恢复码，返回明文和逗号分隔的哈希
+fn generate_recovery_codes() -> (Vec<String>, String) {
+    let codes: Vec<String> = (0..globals::APP_CONFIG.two_factor.recovery_code_count)
+        .map(|_| {
+            let code = generate_random_token(RECOVERY_CODE_LENGTH).to_lowercase();
+            format!(
+                "{}-{}",
+                &code[..RECOVERY_CODE_LENGTH / 2],
+                &code[RECOVERY_CODE_LENGTH / 2..]
+            )
+        })
+        .collect();
+    let hashes = codes
+        .iter()
+        .map(|code| hash_recovery_code(code))
+        .collect::<Vec<_>>()
+        .join(",");
+    (codes, hashes)
+}
+
+//is_two_factor_required_by_role 用户是否拥有要求两步验证的已启用角色
+pub async fn is_two_factor_required_by_role(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<bool, DbErr> {
+    let count = SysUserRole::find()
+        .join(JoinType::InnerJoin, sys_user_role::Relation::SysRole.def())
+        .filter(sys_user_role::Column::UserId.eq(user_id))
+        .filter(sys_role::Column::Status.eq(Status::Enable as i8))
+        .filter(sys_role::Column::RequireTwoFactor.eq(1))
+        .count(db)
+        .await?;
+    Ok(count > 0)
+}
+
+//issue_challenge 密码校验通过后签发两步验证的挑战 token
+pub async fn issue_challenge(redis: &Addr<RedisActor>, user_id: i32) -> Result<String, MyError> {
+    let token = generate_random_token(CHALLENGE_TOKEN_LENGTH);
+    RedisOps::set_ex(
+        redis,
+        &challenge_key(&token),
+        &user_id.to_string(),
+        globals::APP_CONFIG.two_factor.challenge_expire_time,
+    )
+    .await?;
+    Ok(token)
+}
+
+//get_challenge_user_id 获取挑战 token 对应的用户ID，不消耗 token
+pub async fn get_challenge_user_id(redis: &Addr<RedisActor>, token: &str) -> Result<i32, MyError> {
+    RedisOps::get(redis, &challenge_key(token))
+        .await?
+        .and_then(|value| value.parse::<i32>().ok())
+        .ok_or_else(invalid_challenge)
+}
+
+//get_two_factor_lock_ttl 用户的两步验证因输错次数过多被锁定时，返回剩余锁定时间（秒）
+pub async fn get_two_factor_lock_ttl(
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+) -> Result<Option<u64>, MyError> {
+    let ttl = RedisOps::ttl(redis, &two_factor_lock_key(user_id)).await?;
+    Ok((ttl > 0).then_some(ttl as u64))
+}
+
+//record_two_factor_failure 按用户记录一次验证码错误，窗口内达到次数上限时锁定该用户的两步验证，返回是否触发了锁定
+pub async fn record_two_factor_failure(
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+) -> Result<bool, MyError> {
+    let config = &globals::APP_CONFIG.two_factor;
+    let key = two_factor_failure_key(user_id);
+    let failures = RedisOps::incr(redis, &key).await?.max(0) as u32;
+    if failures == 1 {
+        RedisOps::expire(redis, &key, config.failure_window).await?;
+    }
+    if failures < config.max_failures {
+        return Ok(false);
+    }
+    warn!(
+        "too many invalid two-factor codes, locking user: {}",
+        user_id
+    );
+    RedisOps::set_ex(
+        redis,
+        &two_factor_lock_key(user_id),
+        &now_timestamp().to_string(),
+        config.lockout_duration,
+    )
+    .await?;
+    RedisOps::del(redis, &key).await?;
+    Ok(true)
+}
+
+// 校验失败按用户计数，不区分挑战 token 和调用的接口，重新登录也不会重置；锁定期间不再校验
+async fn verify_with_failure_limit(
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+    verify: impl Future<Output = Result<bool, MyError>>,
+) -> Result<bool, MyError> {
+    if let Some(ttl) = get_two_factor_lock_ttl(redis, user_id).await? {
+        return Err(MyError::TooManyRequestsError(format!(
+            "Too many invalid two-factor codes, try again in {} seconds",
+            ttl
+        )));
+    }
+    if verify.await? {
+        RedisOps::del(redis, &two_factor_failure_key(user_id)).await?;
+        return Ok(true);
+    }
+    if record_two_factor_failure(redis, user_id).await? {
+        return Err(MyError::TooManyRequestsError(
+            "Too many invalid two-factor codes, two-factor authentication is temporarily locked"
+                .to_string(),
+        ));
+    }
+    Ok(false)
+}
+
+//consume_challenge 两步验证通过后使挑战 token 失效，每个 token 只能使用一次
+pub async fn consume_challenge(redis: &Addr<RedisActor>, token: &str) -> Result<(), MyError> {
+    if RedisOps::del(redis, &challenge_key(token)).await? == 0 {
+        return Err(invalid_challenge());
+    }
+    Ok(())
+}
+
+//start_enrollment 生成待确认的 TOTP 密钥，确认前不会写入用户表
+pub async fn start_enrollment(
+    redis: &Addr<RedisActor>,
+    user: &sys_user::Model,
+) -> Result<TotpSetupDto, MyError> {
+    let config = &globals::APP_CONFIG.two_factor;
+    let secret = totp::generate_secret();
+    RedisOps::set_ex(
+        redis,
+        &totp_enroll_key(user.id),
+        &secret,
+        config.enroll_expire_time,
+    )
+    .await?;
+    Ok(TotpSetupDto {
+        otpauth_uri: totp::otpauth_uri(
+            &config.issuer,
+            &user.user_name,
+            &secret,
+            config.digits,
+            config.period,
+        ),
+        secret,
+    })
+}
+
+//verify_totp_code 校验验证码，同一时间步的验证码只能使用一次
+pub async fn verify_totp_code(
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+    secret: &str,
+    code: &str,
+) -> Result<bool, MyError> {
+    let config = &globals::APP_CONFIG.two_factor;
+    let Some(step) = totp::verify_code(
+        secret,
+        code,
+        now_timestamp() as u64,
+        config.period,
+        config.digits,
+        config.skew,
+    ) else {
+        return Ok(false);
+    };
+
+    let key = totp_used_step_key(user_id, step);
+    if RedisOps::incr(redis, &key).await? > 1 {
+        return Ok(false);
+    }
+    RedisOps::expire(redis, &key, config.period * (2 * config.skew + 1)).await?;
+    Ok(true)
+}
+
+//confirm_enrollment 用验证码确认绑定，启用两步验证并返回新的恢复码，输错同样计入用户的失败次数
+pub async fn confirm_enrollment(
+    db: &DatabaseConnection,
+    redis: &Addr<RedisActor>,
+    user_id: i32,
+    code: &str,
+) -> Result<Vec<String>, MyError> {
+    let secret = RedisOps::get(redis, &totp_enroll_key(user_id))
+        .await?
+        .ok_or(MyError::BadRequestError(
+            "Two-factor enrollment not started or expired".to_string(),
+        ))?;
+    if !verify_with_failure_limit(
+        redis,
+        user_id,
+        verify_totp_code(redis, user_id, &secret, code),
+    )
+    .await?
+    {
+        return Err(MyError::AuthError("Invalid two-factor code".to_string()));
+    }
+
+    let (codes, hashes) = generate_recovery_codes();
+    SysUser::update_many()
+        .col_expr(sys_user::Column::TotpSecret, Expr::value(secret))
+        .col_expr(sys_user::Column::TotpEnabled, Expr::value(1))
+        .col_expr(sys_user::Column::RecoveryCodes, Expr::value(hashes))
+        .col_expr(sys_user::Column::UpdateTime, Expr::value(Utc::now()))
+        .filter(sys_user::Column::Id.eq(user_id))
+        .exec(db)
+        .await?;
+    RedisOps::del(redis, &totp_enroll_key(user_id)).await?;
+    Ok(codes)
+}
+
+//use_recovery_code 使用恢复码，每个恢复码只能使用一次
+pub async fn use_recovery_code(
+    db: &DatabaseConnection,
+    user: &sys_user::Model,
+    recovery_code: &str,
+) -> Result<bool, MyError> {
+    let hash = hash_recovery_code(recovery_code);
+    let hashes: Vec<&str> = user
+        .recovery_codes
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .filter(|h| !h.is_empty())
+        .collect();
+    if !hashes.contains(&hash.as_str()) {
+        return Ok(false);
+    }
+
+    let remaining = hashes
+        .into_iter()
+        .filter(|h| *h != hash)
+        .collect::<Vec<_>>()
+        .join(",");
+    // 带上原值作为条件，并发使用同一个恢复码时只有一方成功
+    let rows_affected = SysUser::update_many()
+        .col_expr(sys_user::Column::RecoveryCodes, Expr::value(remaining))
+        .filter(sys_user::Column::Id.eq(user.id))
+        .filter(sys_user::Column::RecoveryCodes.eq(user.recovery_codes.clone()))
+        .exec(db)
+        .await?
+        .rows_affected;
+    Ok(rows_affected > 0)
+}
+
+//verify_second_factor 校验验证码或恢复码，同一用户输错次数过多时锁定，锁定期间返回 TooManyRequestsError
+pub async fn verify_second_factor(
+    db: &DatabaseConnection,
+    redis: &Addr<RedisActor>,
+    user: &sys_user::Model,
+    code: Option<&str>,
+    recovery_code: Option<&str>,
+) -> Result<bool, MyError> {
+    if user.totp_enabled != 1 {
+        return Ok(false);
+    }
+    verify_with_failure_limit(redis, user.id, async {
+        if let (Some(code), Some(secret)) = (code, user.totp_secret.as_deref()) {
+            return verify_totp_code(redis, user.id, secret, code).await;
+        }
+        if let Some(recovery_code) = recovery_code {
+            return use_recovery_code(db, user, recovery_code).await;
+        }
+        Ok(false)
+    })
+    .await
+}
+
+//regenerate_recovery_codes 重新生成恢复码，旧的恢复码全部失效
+pub async fn regenerate_recovery_codes(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<String>, MyError> {
+    let (codes, hashes) = generate_recovery_codes();
+    SysUser::update_many()
+        .col_expr(sys_user::Column::RecoveryCodes, Expr::value(hashes))
+        .col_expr(sys_user::Column::UpdateTime, Expr::value(Utc::now()))
+        .filter(sys_user::Column::Id.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(codes)
+}
+
+//disable_two_factor 关闭两步验证并清空密钥和恢复码
+pub async fn disable_two_factor(db: &DatabaseConnection, user_id: i32) -> Result<(), MyError> {
+    SysUser::update_many()
+        .col_expr(
+            sys_user::Column::TotpSecret,
+            Expr::value(Option::<String>::None),
+        )
+        .col_expr(sys_user::Column::TotpEnabled, Expr::value(0))
+        .col_expr(
+            sys_user::Column::RecoveryCodes,
+            Expr::value(Option::<String>::None),
+        )
+        .col_expr(sys_user::Column::UpdateTime, Expr::value(Utc::now()))
+        .filter(sys_user::Column::Id.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use crate::common::error::MyError;
+use crate::dto::admin::sys_user_permission_dto::UserPermissionItemDto;
+use crate::schemas::admin::prelude::{SysPermission, SysUser, SysUserPermission};
+use crate::schemas::admin::sea_orm_active_enums::PermissionEffect;
+use crate::schemas::admin::{sys_permission, sys_user_permission};
+use crate::services::admin::sys_authorization_services;
+use crate::services::admin::sys_route_services::Status;
+use chrono::Utc;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, PaginatorTrait, QueryFilter,
+    QuerySelect, RelationTrait, TransactionTrait,
+};
+use std::collections::HashSet;
+
+//assign_permissions_to_user 设置用户的直接授权，覆盖原有的直接授权
+pub async fn assign_permissions_to_user(
+    db: &DatabaseConnection,
+    user_id: i32,
+    permissions: Vec<UserPermissionItemDto>,
+    create_user: String,
+) -> Result<Vec<sys_user_permission::Model>, MyError> {
+    if SysUser::find_by_id(user_id).one(db).await?.is_none() {
+        return Err(MyError::NotFound("User not found".to_string()));
+    }
+    let permission_ids: HashSet<i32> = permissions.iter().map(|p| p.permission_id).collect();
+    if permission_ids.len() != permissions.len() {
+        return Err(MyError::BadRequestError(
+            "Duplicate permission in request".to_string(),
+        ));
+    }
+    let found = SysPermission::find()
+        .filter(sys_permission::Column::Id.is_in(permission_ids.clone()))
+        .count(db)
+        .await?;
+    if found != permission_ids.len() as u64 {
+        return Err(MyError::BadRequestError("Permission not found".to_string()));
+    }
+
+    let user_permissions = permissions
+        .into_iter()
+        .map(|permission| {
+            Ok(sys_user_permission::ActiveModel {
+                user_id: Set(user_id),
+                permission_id: Set(permission.permission_id),
+                effect: Set(PermissionEffect::from_string(&permission.effect)?),
+                create_user: Set(create_user.clone()),
+                create_time: Set(Some(Utc::now())),
+                ..Default::default()
+            })
+        })
+        .collect::<Result<Vec<_>, MyError>>()?;
+
+    let transaction = db.begin().await?;
+    SysUserPermission::delete_many()
+        .filter(sys_user_permission::Column::UserId.eq(user_id))
+        .exec(&transaction)
+        .await?;
+    if !user_permissions.is_empty() {
+        SysUserPermission::insert_many(user_permissions)
+            .exec(&transaction)
+            .await?;
+    }
+    transaction.commit().await?;
+    sys_authorization_services::invalidate_permission_cache().await;
+
+    Ok(get_user_permissions(db, user_id).await?)
+}
+
+//get_user_permissions 获取用户的直接授权
+pub async fn get_user_permissions(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<sys_user_permission::Model>, DbErr> {
+    SysUserPermission::find()
+        .filter(sys_user_permission::Column::UserId.eq(user_id))
+        .all(db)
+        .await
+}
+
+//remove_permission_from_user 删除用户的一条直接授权
+pub async fn remove_permission_from_user(
+    db: &DatabaseConnection,
+    user_id: i32,
+    permission_id: i32,
+) -> Result<u64, DbErr> {
+    let rows_affected = SysUserPermission::delete_many()
+        .filter(sys_user_permission::Column::UserId.eq(user_id))
+        .filter(sys_user_permission::Column::PermissionId.eq(permission_id))
+        .exec(db)
+        .await?
+        .rows_affected;
+    sys_authorization_services::invalidate_permission_cache().await;
+    Ok(rows_affected)
+}
+
+//get_enabled_user_permissions 获取用户直接授权中已启用的权限及最终效果，授权或权限本身任一为 DENY 即为拒绝
+pub async fn get_enabled_user_permissions(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<(i32, PermissionEffect)>, DbErr> {
+    let rows: Vec<(i32, PermissionEffect, PermissionEffect)> = SysUserPermission::find()
+        .select_only()
+        .column(sys_user_permission::Column::PermissionId)
+        .column(sys_user_permission::Column::Effect)
+        .column_as(sys_permission::Column::Effect, "permission_effect")
+        .join(
+            JoinType::InnerJoin,
+            sys_user_permission::Relation::SysPermission.def(),
+        )
+        .filter(sys_user_permission::Column::UserId.eq(user_id))
+        .filter(sys_permission::Column::Status.eq(Status::Enable as i8))
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(permission_id, effect, permission_effect)| {
+            if effect == PermissionEffect::Deny || permission_effect == PermissionEffect::Deny {
+                (permission_id, PermissionEffect::Deny)
+            } else {
+                (permission_id, PermissionEffect::Allow)
+            }
+        })
+        .collect())
+}
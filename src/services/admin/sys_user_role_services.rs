@@ -1,10 +1,13 @@
 use crate::schemas::admin::prelude::SysUserRole;
-use crate::schemas::admin::{sys_user, sys_user_role};
+use crate::schemas::admin::{sys_role, sys_user, sys_user_role};
+use crate::services::admin::sys_route_services::Status;
+use chrono::Utc;
 use sea_orm::ActiveValue::Set;
 use sea_orm::{
     ColumnTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, QueryFilter, QuerySelect,
     RelationTrait,
 };
+use std::collections::BTreeSet;
 
 //assign_roles_to_user，用于给用户分配角色
 pub async fn assign_roles_to_user(
@@ -24,6 +27,7 @@ pub async fn assign_roles_to_user(
             user_id: Set(user_id),
             role_id: Set(role_id),
             create_user: Set(create_user.clone()),
+            create_time: Set(Some(Utc::now())),
             ..Default::default()
         })
         .collect();
@@ -40,6 +44,27 @@ pub async fn assign_roles_to_user(
     Ok(assigned_roles)
 }
 
+//replace_user_roles 替换用户的角色，返回角色是否有变化，有变化时调用方需要吊销用户已签发的 token
+pub async fn replace_user_roles(
+    db: &DatabaseConnection,
+    user_id: i32,
+    role_ids: Vec<i32>,
+    create_user: String,
+) -> Result<bool, DbErr> {
+    let before: BTreeSet<i32> = SysUserRole::find()
+        .select_only()
+        .column(sys_user_role::Column::RoleId)
+        .filter(sys_user_role::Column::UserId.eq(user_id))
+        .into_tuple::<i32>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+    let after: BTreeSet<i32> = role_ids.iter().copied().collect();
+    assign_roles_to_user(db, user_id, role_ids, create_user).await?;
+    Ok(before != after)
+}
+
 //get_user_roles 获取用户的角色
 pub async fn get_user_roles(
     db: &DatabaseConnection,
@@ -74,3 +99,19 @@ pub async fn remove_role_from_user(
         .await
         .map(|res| res.rows_affected)
 }
+
+//get_role_codes_by_user_id 获取用户所有已启用角色的 role_code
+pub async fn get_role_codes_by_user_id(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<String>, DbErr> {
+    SysUserRole::find()
+        .select_only()
+        .column(sys_role::Column::RoleCode)
+        .join(JoinType::InnerJoin, sys_user_role::Relation::SysRole.def())
+        .filter(sys_user_role::Column::UserId.eq(user_id))
+        .filter(sys_role::Column::Status.eq(Status::Enable as i8))
+        .into_tuple::<String>()
+        .all(db)
+        .await
+}
@@ -1,9 +1,14 @@
 use crate::common::auth;
+use crate::common::auth::password_policy;
+use crate::config::globals;
 use crate::common::error::MyError;
-use crate::dto::admin::sys_user_dto::{UserCreateDto, UserUpdateDto, UserWithRolesDto};
+use crate::dto::admin::sys_user_dto::{UserCreateDto, UserInfo, UserUpdateDto, UserWithRolesDto};
 use crate::schemas::admin::prelude::SysUser;
 use crate::schemas::admin::sea_orm_active_enums::Gender;
 use crate::schemas::admin::{sys_user, sys_user_role};
+use crate::services::admin::{sys_dept_services, sys_menu_services, sys_user_role_services};
+use crate::services::admin::sys_data_scope_services::{DataScopeFilter, DataScopeQuery};
+use crate::services::admin::sys_route_services::Status;
 use chrono::{DateTime, Utc};
 use sea_orm::sea_query::Expr;
 use sea_orm::ActiveValue::Set;
@@ -17,12 +22,14 @@ pub async fn create_user(
     db: &DatabaseConnection,
     user_create_req: UserCreateDto,
     create_user: String,
-) -> Result<sys_user::Model, DbErr> {
-    let password_hash = auth::crypto::hash_password(Some(user_create_req.password)).unwrap(); // 假设这是一个外部函数，用于安全地散列密码
+) -> Result<sys_user::Model, MyError> {
+    check_password(&user_create_req.password, &user_create_req.user_name)?;
+    let password_hash = auth::crypto::hash_password(Some(user_create_req.password))?;
     let gender = user_create_req
         .user_gender
         .parse::<Gender>()
-        .map_err(|_| DbErr::Custom("Invalid gender".to_string()))?;
+        .map_err(|_| MyError::ValidationError("Invalid gender".to_string()))?;
+    let dept_id = check_dept(db, user_create_req.dept_id).await?;
     let user = sys_user::ActiveModel {
         user_name: Set(user_create_req.user_name),
         password: Set(password_hash),
@@ -31,10 +38,28 @@ pub async fn create_user(
         mobile: Set(Some(user_create_req.user_phone)),
         status: Set(user_create_req.status.parse().unwrap()),
         gender: Set(gender),
+        dept_id: Set(dept_id),
         create_user: Set(create_user),
+        create_time: Set(Some(Utc::now())),
         ..Default::default()
     };
-    user.insert(db).await
+    Ok(user.insert(db).await?)
+}
+
+// 校验部门存在，为空或 0 时表示不属于任何部门
+async fn check_dept(db: &DatabaseConnection, dept_id: Option<i32>) -> Result<Option<i32>, MyError> {
+    match dept_id {
+        Some(dept_id) if dept_id != 0 => {
+            sys_dept_services::check_dept_exists(db, dept_id, "Department not found").await?;
+            Ok(Some(dept_id))
+        }
+        _ => Ok(None),
+    }
+}
+
+// 按配置的密码策略检查密码
+fn check_password(password: &str, user_name: &str) -> Result<(), MyError> {
+    password_policy::check_password(password, user_name, &globals::APP_CONFIG.password)
 }
 
 #[derive(Debug, FromQueryResult)]
@@ -50,16 +75,28 @@ pub struct UserWithRoles {
     pub create_time: Option<DateTime<Utc>>,
     pub update_user: Option<String>,
     pub update_time: Option<DateTime<Utc>>,
+    pub dept_id: Option<i32>,
     pub role_codes: Option<String>,
 }
 
+//get_users_with_roles 分页获取数据权限范围内的用户及其角色，dept_ids 不为空时只返回这些部门的用户
 pub async fn get_users_with_roles(
     db: &DatabaseConnection,
     current: usize,
     page_size: usize,
+    dept_ids: Option<Vec<i32>>,
+    data_scope: &DataScopeFilter,
 ) -> Result<Vec<UserWithRolesDto>, DbErr> {
     let offset = (current.saturating_sub(1)) * page_size;
-    let users_with_roles: Vec<UserWithRolesDto> = sys_user::Entity::find()
+    let mut query = sys_user::Entity::find().filter_data_scope(
+        data_scope,
+        sys_user::Column::DeptId,
+        sys_user::Column::Id,
+    );
+    if let Some(dept_ids) = dept_ids {
+        query = query.filter(sys_user::Column::DeptId.is_in(dept_ids));
+    }
+    let users_with_roles: Vec<UserWithRolesDto> = query
         .select_only()
         .column(sys_user::Column::Id)
         .column(sys_user::Column::UserName)
@@ -72,6 +109,7 @@ pub async fn get_users_with_roles(
         .column(sys_user::Column::CreateTime)
         .column(sys_user::Column::UpdateUser)
         .column(sys_user::Column::UpdateTime)
+        .column(sys_user::Column::DeptId)
         .join(JoinType::LeftJoin, sys_user::Relation::SysUserRole.def())
         .join(JoinType::LeftJoin, sys_user_role::Relation::SysRole.def())
         .column_as(
@@ -111,6 +149,8 @@ pub async fn get_users_with_roles(
                     .filter_map(|code| code.trim().parse::<i32>().ok())
                     .collect()
             }),
+            dept_id: user.dept_id,
+            locked_for: None,
         })
         .collect();
     Ok(users_with_roles)
@@ -136,11 +176,18 @@ pub async fn update_user(
     update_dto: UserUpdateDto,
     update_user: String,
 ) -> Result<Option<sys_user::Model>, MyError> {
-    let mut user: sys_user::ActiveModel = SysUser::find_by_id(user_id)
+    let existing_user = SysUser::find_by_id(user_id)
         .one(db)
         .await?
-        .ok_or(MyError::NotFound("User not found".to_string()))?
-        .into();
+        .ok_or(MyError::NotFound("User not found".to_string()))?;
+    if let Some(ref pwd) = update_dto.password {
+        let user_name = update_dto
+            .user_name
+            .as_deref()
+            .unwrap_or(&existing_user.user_name);
+        check_password(pwd, user_name)?;
+    }
+    let mut user: sys_user::ActiveModel = existing_user.into();
 
     if let Some(un) = update_dto.user_name {
         user.user_name = Set(un);
@@ -165,6 +212,9 @@ pub async fn update_user(
     if let Some(mb) = update_dto.user_phone {
         user.mobile = Set(Some(mb));
     }
+    if let Some(dept_id) = update_dto.dept_id {
+        user.dept_id = Set(check_dept(db, Some(dept_id)).await?);
+    }
     if let Some(st) = update_dto
         .status
         .unwrap_or("1".to_string())
@@ -175,10 +225,54 @@ pub async fn update_user(
     }
 
     user.update_user = Set(Some(update_user));
+    user.update_time = Set(Some(Utc::now()));
 
     Ok(user.update(db).await.map(Some)?)
 }
 
+//update_password 更新用户密码
+pub async fn update_password(
+    db: &DatabaseConnection,
+    user_id: i32,
+    password: String,
+    update_user: String,
+) -> Result<sys_user::Model, MyError> {
+    let existing_user = SysUser::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or(MyError::NotFound("User not found".to_string()))?;
+    check_password(&password, &existing_user.user_name)?;
+    let mut user: sys_user::ActiveModel = existing_user.into();
+    user.password = Set(auth::crypto::hash_password(Some(password))?);
+    user.update_user = Set(Some(update_user));
+    user.update_time = Set(Some(Utc::now()));
+    Ok(user.update(db).await?)
+}
+
+//update_password_hash 只替换密码哈希，用于登录时升级哈希算法，不修改更新人和更新时间
+pub async fn update_password_hash(
+    db: &DatabaseConnection,
+    user_id: i32,
+    password_hash: String,
+) -> Result<(), DbErr> {
+    SysUser::update_many()
+        .col_expr(sys_user::Column::Password, Expr::value(password_hash))
+        .filter(sys_user::Column::Id.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+//update_last_login 登录成功后更新最后登录时间
+pub async fn update_last_login(db: &DatabaseConnection, user_id: i32) -> Result<(), DbErr> {
+    SysUser::update_many()
+        .col_expr(sys_user::Column::LastLogin, Expr::value(Utc::now()))
+        .filter(sys_user::Column::Id.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
 //delete_user 删除用户
 pub async fn delete_user(db: &DatabaseConnection, user_id: i32) -> Result<u64, DbErr> {
     let user = sys_user::ActiveModel {
@@ -212,6 +306,17 @@ pub async fn find_user_by_email_or_mobile(
     query.one(db).await
 }
 
+//is_user_enabled 用户是否存在且处于启用状态
+pub async fn is_user_enabled(db: &DatabaseConnection, user_id: i32) -> Result<bool, DbErr> {
+    let status: Option<i8> = SysUser::find_by_id(user_id)
+        .select_only()
+        .column(sys_user::Column::Status)
+        .into_tuple()
+        .one(db)
+        .await?;
+    Ok(status == Some(Status::Enable as i8))
+}
+
 //find_user_by_username 根据用户名查找用户
 pub async fn find_user_by_username(
     db: &DatabaseConnection,
@@ -234,8 +339,20 @@ pub async fn batch_delete_users(db: &DatabaseConnection, user_ids: Vec<i32>) ->
     Ok(result.rows_affected)
 }
 
-pub async fn get_total_users_count(db: &DatabaseConnection) -> Result<i32, DbErr> {
-    let total = SysUser::find()
+pub async fn get_total_users_count(
+    db: &DatabaseConnection,
+    dept_ids: Option<Vec<i32>>,
+    data_scope: &DataScopeFilter,
+) -> Result<i32, DbErr> {
+    let mut query = SysUser::find().filter_data_scope(
+        data_scope,
+        sys_user::Column::DeptId,
+        sys_user::Column::Id,
+    );
+    if let Some(dept_ids) = dept_ids {
+        query = query.filter(sys_user::Column::DeptId.is_in(dept_ids));
+    }
+    let total = query
         .select_only()
         .column_as(sys_user::Column::Id.count(), "total_count")
         .into_model::<TotalCount>()
@@ -246,3 +363,19 @@ pub async fn get_total_users_count(db: &DatabaseConnection) -> Result<i32, DbErr
 
     Ok(total)
 }
+
+//get_user_info 获取用户信息、已启用的角色编码和按钮权限编码
+pub async fn get_user_info(db: &DatabaseConnection, user_id: i32) -> Result<UserInfo, MyError> {
+    let user = SysUser::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or(MyError::NotFound("User not found".to_string()))?;
+    let roles = sys_user_role_services::get_role_codes_by_user_id(db, user.id).await?;
+    let buttons = sys_menu_services::get_button_codes_by_user_id(db, user.id).await?;
+    Ok(UserInfo {
+        user_id: user.id.to_string(),
+        user_name: user.user_name,
+        buttons,
+        roles,
+    })
+}
@@ -0,0 +1,155 @@
+// tests/access_explain_test.rs
+
+use rust_web::common::auth::role_hierarchy::{RoleHierarchy, RoleNode};
+use rust_web::schemas::admin::sea_orm_active_enums::{ActionCode, PermissionEffect};
+use rust_web::services::admin::sys_access_services::{
+    evaluate_grant_chains, AccessSnapshot, GrantChain, GrantSource, PermissionInfo,
+};
+use std::collections::HashMap;
+
+fn permission(code: &str, enabled: bool, effect: PermissionEffect) -> PermissionInfo {
+    PermissionInfo {
+        code: code.to_string(),
+        enabled,
+        effect,
+        actions: vec![ActionCode::Read],
+    }
+}
+
+// 用户分配了 R_MANAGER(2) 和已禁用的 R_AUDIT(3)，R_MANAGER 继承 R_STAFF(1)
+fn snapshot() -> AccessSnapshot {
+    AccessSnapshot {
+        user_enabled: true,
+        is_super: false,
+        user_role_ids: vec![2, 3],
+        hierarchy: RoleHierarchy::new(vec![
+            RoleNode {
+                id: 1,
+                parent_role_id: None,
+                enabled: true,
+            },
+            RoleNode {
+                id: 2,
+                parent_role_id: Some(1),
+                enabled: true,
+            },
+            RoleNode {
+                id: 3,
+                parent_role_id: None,
+                enabled: false,
+            },
+        ]),
+        role_codes: HashMap::from([
+            (1, "R_STAFF".to_string()),
+            (2, "R_MANAGER".to_string()),
+            (3, "R_AUDIT".to_string()),
+        ]),
+        role_permissions: HashMap::from([(1, vec![10]), (2, vec![11]), (3, vec![12])]),
+        user_permissions: vec![(10, PermissionEffect::Deny), (13, PermissionEffect::Allow)],
+        permissions: HashMap::from([
+            (10, permission("users", true, PermissionEffect::Allow)),
+            (11, permission("roles", false, PermissionEffect::Allow)),
+            (12, permission("logs", true, PermissionEffect::Allow)),
+            (13, permission("depts", true, PermissionEffect::Allow)),
+        ]),
+    }
+}
+
+fn find<'a>(chains: &'a [GrantChain], permission_id: i32, source: GrantSource) -> &'a GrantChain {
+    chains
+        .iter()
+        .find(|chain| chain.permission_id == permission_id && chain.source == source)
+        .unwrap()
+}
+
+#[test]
+fn test_grant_chains_follow_roles_and_direct_grants() {
+    let chains = snapshot().grant_chains();
+    assert_eq!(chains.len(), 5);
+
+    // 继承的权限记录完整的角色链路
+    let inherited = find(&chains, 10, GrantSource::Role);
+    assert_eq!(inherited.roles, vec!["R_MANAGER", "R_STAFF"]);
+    assert_eq!(inherited.blocked_by, None);
+
+    // 禁用的权限和禁用的角色都会截断链路
+    assert_eq!(
+        find(&chains, 11, GrantSource::Role).blocked_by.as_deref(),
+        Some("Permission roles is disabled")
+    );
+    assert_eq!(
+        find(&chains, 12, GrantSource::Role).blocked_by.as_deref(),
+        Some("Role R_AUDIT is disabled")
+    );
+
+    // 用户直接拒绝一个允许的权限，链路效果为拒绝
+    assert_eq!(
+        find(&chains, 10, GrantSource::User).effect,
+        PermissionEffect::Deny
+    );
+    assert!(find(&chains, 13, GrantSource::User).roles.is_empty());
+}
+
+#[test]
+fn test_evaluate_grant_chains() {
+    let chains = snapshot().grant_chains();
+    let for_permission = |permission_id: i32| -> Vec<GrantChain> {
+        chains
+            .iter()
+            .filter(|chain| chain.permission_id == permission_id)
+            .cloned()
+            .collect()
+    };
+
+    // 角色授予但用户直接拒绝，拒绝优先，超级管理员也不例外
+    let verdict = evaluate_grant_chains(for_permission(10), None, false);
+    assert!(!verdict.allowed);
+    assert_eq!(verdict.granted_by.len(), 1);
+    assert_eq!(verdict.denied_by.len(), 1);
+    assert!(!evaluate_grant_chains(for_permission(10), None, true).allowed);
+
+    // 只有被截断的链路时不允许访问，并保留原因
+    let verdict = evaluate_grant_chains(for_permission(12), None, false);
+    assert!(!verdict.allowed);
+    assert_eq!(verdict.blocked.len(), 1);
+
+    // 接口还要校验操作
+    assert!(evaluate_grant_chains(for_permission(13), Some(&ActionCode::Read), false).allowed);
+    let verdict = evaluate_grant_chains(for_permission(13), Some(&ActionCode::Delete), false);
+    assert!(!verdict.allowed);
+    assert_eq!(
+        verdict.blocked[0].blocked_by.as_deref(),
+        Some("Permission depts does not include action DELETE")
+    );
+
+    // 没有任何链路时只有超级管理员可以访问接口
+    assert!(!evaluate_grant_chains(Vec::new(), Some(&ActionCode::Read), false).allowed);
+    assert!(evaluate_grant_chains(Vec::new(), Some(&ActionCode::Read), true).allowed);
+}
+
+#[test]
+fn test_token_grant_chains_use_token_roles() {
+    let snapshot = snapshot();
+    // 令牌中有 R_MANAGER，继承的 R_STAFF 权限同样生效
+    let chains = snapshot.token_grant_chains(&["R_MANAGER".to_string()]);
+    let blocked = |chains: &[GrantChain]| -> Vec<Option<String>> {
+        chains
+            .iter()
+            .map(|chain| chain.blocked_by.clone())
+            .collect()
+    };
+    assert_eq!(blocked(&chains), blocked(&snapshot.grant_chains()));
+
+    // 令牌签发后才分配的角色在重新登录前不生效，鉴权同样不会采用
+    let chains = snapshot.token_grant_chains(&[]);
+    assert_eq!(
+        find(&chains, 10, GrantSource::Role).blocked_by.as_deref(),
+        Some("Role R_MANAGER is not in the user's token")
+    );
+    // 已有的截断原因保持不变，直接授权不受影响
+    assert_eq!(
+        find(&chains, 12, GrantSource::Role).blocked_by.as_deref(),
+        Some("Role R_AUDIT is disabled")
+    );
+    assert_eq!(find(&chains, 13, GrantSource::User).blocked_by, None);
+}
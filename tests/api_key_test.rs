@@ -0,0 +1,82 @@
+// tests/api_key_test.rs
+
+use rust_web::common::auth::api_key::{
+    generate_api_key, hash_api_key, is_ip_allowed, parse_ip_allowlist, split_key_prefix,
+    verify_api_key, IpRule,
+};
+use std::net::IpAddr;
+
+fn ip(value: &str) -> IpAddr {
+    value.parse().unwrap()
+}
+
+#[test]
+fn test_generate_and_split_api_key() {
+    let (key, key_prefix) = generate_api_key("rwk_");
+    assert!(key.starts_with(&format!("{}_", key_prefix)));
+    assert_eq!(split_key_prefix(&key, "rwk_"), Some(key_prefix.as_str()));
+
+    let (other_key, other_prefix) = generate_api_key("rwk_");
+    assert_ne!(key, other_key);
+    assert_ne!(key_prefix, other_prefix);
+
+    assert_eq!(split_key_prefix(&key, "abc_"), None);
+    assert_eq!(split_key_prefix("rwk_abcd1234", "rwk_"), None);
+    assert_eq!(split_key_prefix("rwk_abcd1234_short", "rwk_"), None);
+    assert_eq!(split_key_prefix(&format!("{}-", key), "rwk_"), None);
+}
+
+#[test]
+fn test_verify_api_key() {
+    let (key, _) = generate_api_key("rwk_");
+    let key_hash = hash_api_key(&key);
+    assert_eq!(key_hash.len(), 64);
+    assert!(verify_api_key(&key, &key_hash));
+
+    let (other_key, _) = generate_api_key("rwk_");
+    assert!(!verify_api_key(&other_key, &key_hash));
+    assert!(!verify_api_key(&key, ""));
+}
+
+#[test]
+fn test_ip_rules() {
+    let rule = IpRule::parse("10.1.0.0/16").unwrap();
+    assert!(rule.matches(ip("10.1.200.3")));
+    assert!(!rule.matches(ip("10.2.0.1")));
+    assert!(rule.matches(ip("::ffff:10.1.0.9")));
+
+    let rule = IpRule::parse("192.168.1.7").unwrap();
+    assert!(rule.matches(ip("192.168.1.7")));
+    assert!(!rule.matches(ip("192.168.1.8")));
+
+    let rule = IpRule::parse("172.16.0.0/12").unwrap();
+    assert!(rule.matches(ip("172.31.255.255")));
+    assert!(!rule.matches(ip("172.32.0.0")));
+
+    let rule = IpRule::parse("2001:db8::/32").unwrap();
+    assert!(rule.matches(ip("2001:db8:1::1")));
+    assert!(!rule.matches(ip("2001:db9::1")));
+    assert!(!rule.matches(ip("10.0.0.1")));
+
+    assert!(IpRule::parse("0.0.0.0/0").unwrap().matches(ip("8.8.8.8")));
+    assert!(IpRule::parse("10.0.0.0/33").is_none());
+    assert!(IpRule::parse("not-an-ip").is_none());
+}
+
+#[test]
+fn test_ip_allowlist() {
+    assert!(is_ip_allowed(None, None));
+    assert!(is_ip_allowed(Some(""), Some(ip("1.2.3.4"))));
+
+    let allowlist = Some("10.0.0.0/8, 192.168.1.7");
+    assert!(is_ip_allowed(allowlist, Some(ip("10.3.4.5"))));
+    assert!(is_ip_allowed(allowlist, Some(ip("192.168.1.7"))));
+    assert!(!is_ip_allowed(allowlist, Some(ip("192.168.1.8"))));
+    assert!(!is_ip_allowed(allowlist, None));
+
+    assert_eq!(
+        parse_ip_allowlist("10.0.0.1,bad").unwrap_err(),
+        "bad".to_string()
+    );
+    assert!(!is_ip_allowed(Some("10.0.0.1,bad"), Some(ip("10.0.0.1"))));
+}
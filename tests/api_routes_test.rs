@@ -0,0 +1,144 @@
+// tests/api_routes_test.rs
+
+use actix_web::dev::Service;
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::http::{Method, StatusCode};
+use actix_web::test::{call_service, init_service, TestRequest};
+use actix_web::{web, App};
+use rust_web::common::api_route::ApiRoute;
+use rust_web::handlers::admin::{system_manage_config, system_manage_routes, SYSTEM_MANAGE_SCOPE};
+use rust_web::schemas::admin::sea_orm_active_enums::ApiMethod;
+use rust_web::schemas::admin::sys_api;
+use rust_web::services::admin::sys_api_services::diff_api_routes;
+
+fn api_model(id: i32, api_group: &str, api_path: &str, api_method: ApiMethod) -> sys_api::Model {
+    sys_api::Model {
+        id,
+        api_name: format!("api_{}", id),
+        api_group: api_group.to_string(),
+        api_path: api_path.to_string(),
+        api_method,
+        description: None,
+        create_user: "admin".to_string(),
+        create_time: None,
+        update_user: None,
+        update_time: None,
+        deprecated: 0,
+    }
+}
+
+#[test]
+fn test_api_route_group() {
+    let route = ApiRoute::new(
+        "rust_web::handlers::admin::sys_user_handler",
+        "create_user",
+        "POST",
+        "/users",
+    )
+    .with_prefix(SYSTEM_MANAGE_SCOPE);
+    assert_eq!(route.api_group, "sys_user");
+    assert_eq!(route.api_path, "/system-manage/users");
+}
+
+// 清单中的每个路由都必须真实注册，请求不会落到 404/405
+#[actix_web::test]
+async fn test_listed_routes_are_registered() {
+    let app = init_service(
+        App::new().service(web::scope(SYSTEM_MANAGE_SCOPE).configure(system_manage_config)),
+    )
+    .await;
+
+    let routes = system_manage_routes();
+    assert!(!routes.is_empty());
+    for route in routes {
+        let path = route
+            .api_path
+            .split('/')
+            .map(|segment| if segment.starts_with('{') { "1" } else { segment })
+            .collect::<Vec<_>>()
+            .join("/");
+        let method = Method::from_bytes(route.api_method.as_bytes()).unwrap();
+        let req = TestRequest::default()
+            .method(method)
+            .uri(&path)
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_ne!(resp.status(), StatusCode::NOT_FOUND, "{:?}", route);
+        assert_ne!(resp.status(), StatusCode::METHOD_NOT_ALLOWED, "{:?}", route);
+    }
+}
+
+// 清单中的每个路由都由按该路径注册的资源处理，不会被同前缀的其他路由抢先匹配
+#[actix_web::test]
+async fn test_listed_routes_match_their_own_resource() {
+    let app = init_service(
+        App::new()
+            .wrap_fn(|req, srv| {
+                let fut = srv.call(req);
+                async move {
+                    let mut res = fut.await?;
+                    let pattern = res.request().match_pattern().unwrap_or_default();
+                    res.headers_mut().insert(
+                        HeaderName::from_static("x-match-pattern"),
+                        HeaderValue::from_str(&pattern).unwrap(),
+                    );
+                    Ok(res)
+                }
+            })
+            .service(web::scope(SYSTEM_MANAGE_SCOPE).configure(system_manage_config)),
+    )
+    .await;
+
+    for route in system_manage_routes() {
+        let path = route
+            .api_path
+            .split('/')
+            .map(|segment| if segment.starts_with('{') { "1" } else { segment })
+            .collect::<Vec<_>>()
+            .join("/");
+        let method = Method::from_bytes(route.api_method.as_bytes()).unwrap();
+        let req = TestRequest::default()
+            .method(method)
+            .uri(&path)
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(
+            resp.headers().get("x-match-pattern").unwrap(),
+            route.api_path.as_str(),
+            "{:?}",
+            route
+        );
+    }
+}
+
+#[test]
+fn test_diff_api_routes() {
+    let mut deprecated = api_model(3, "sys_role", "/system-manage/roles", ApiMethod::Get);
+    deprecated.deprecated = 1;
+    let existing = vec![
+        api_model(1, "sys_user", "/system-manage/users", ApiMethod::Get),
+        api_model(2, "users", "/system-manage/users", ApiMethod::Post),
+        deprecated,
+        api_model(4, "sys_menu", "/system-manage/menus/{id}", ApiMethod::Delete),
+        // scope 之外手工维护的接口不会被标记为废弃
+        api_model(5, "auth", "/auth/login", ApiMethod::Post),
+    ];
+    let routes = vec![
+        ApiRoute::new("sys_user_handler", "get_users", "GET", "/system-manage/users"),
+        ApiRoute::new("sys_user_handler", "create_user", "POST", "/system-manage/users"),
+        ApiRoute::new("sys_role_handler", "get_roles", "GET", "/system-manage/roles"),
+        ApiRoute::new("sys_api_handler", "get_apis", "GET", "/system-manage/apis"),
+    ];
+
+    let diff = diff_api_routes(&existing, &routes, SYSTEM_MANAGE_SCOPE);
+    assert_eq!(diff.created.len(), 1);
+    assert_eq!(diff.created[0].api_path, "/system-manage/apis");
+    assert_eq!(diff.regrouped.len(), 1);
+    assert_eq!(diff.regrouped[0].api_method, "POST");
+    assert_eq!(diff.restored.len(), 1);
+    assert_eq!(diff.restored[0].api_path, "/system-manage/roles");
+    assert_eq!(
+        diff.deprecated.iter().map(|api| api.id).collect::<Vec<_>>(),
+        vec![4]
+    );
+}
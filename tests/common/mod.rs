@@ -0,0 +1,12 @@
+// tests/common/mod.rs
+
+// 部门树 (id, parent_id)：1 -> (2 -> 4, 3)，5 独立
+pub fn depts() -> Vec<(i32, Option<i32>)> {
+    vec![
+        (1, None),
+        (2, Some(1)),
+        (3, Some(1)),
+        (4, Some(2)),
+        (5, None),
+    ]
+}
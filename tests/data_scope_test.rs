@@ -0,0 +1,179 @@
+// tests/data_scope_test.rs
+
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+use rust_web::common::resp::ApiError;
+use rust_web::schemas::admin::sea_orm_active_enums::DataScope;
+use rust_web::schemas::admin::sys_user;
+use rust_web::services::admin::sys_data_scope_services::{
+    check_dept_in_scope, resolve_data_scope, DataScopeFilter, DataScopeQuery, RoleDataScope,
+};
+use sea_orm::{DbBackend, EntityTrait, QueryTrait};
+
+fn scope(data_scope: DataScope, dept_ids: Vec<i32>) -> RoleDataScope {
+    RoleDataScope {
+        data_scope,
+        dept_ids,
+    }
+}
+
+fn restricted(dept_ids: Vec<i32>, user_id: Option<i32>) -> DataScopeFilter {
+    DataScopeFilter::Restricted { dept_ids, user_id }
+}
+
+#[test]
+fn test_single_role_scopes() {
+    let depts = common::depts();
+    assert_eq!(
+        resolve_data_scope(&[scope(DataScope::All, vec![])], 7, Some(2), &depts),
+        DataScopeFilter::All
+    );
+    assert_eq!(
+        resolve_data_scope(&[scope(DataScope::Dept, vec![])], 7, Some(2), &depts),
+        restricted(vec![2], None)
+    );
+    assert_eq!(
+        resolve_data_scope(
+            &[scope(DataScope::DeptAndChild, vec![])],
+            7,
+            Some(2),
+            &depts
+        ),
+        restricted(vec![2, 4], None)
+    );
+    assert_eq!(
+        resolve_data_scope(&[scope(DataScope::Custom, vec![5, 3])], 7, Some(2), &depts),
+        restricted(vec![3, 5], None)
+    );
+    assert_eq!(
+        resolve_data_scope(&[scope(DataScope::SelfOnly, vec![])], 7, Some(2), &depts),
+        restricted(vec![], Some(7))
+    );
+    // 没有部门的用户，本部门范围看不到任何部门的数据
+    assert_eq!(
+        resolve_data_scope(&[scope(DataScope::DeptAndChild, vec![])], 7, None, &depts),
+        restricted(vec![], None)
+    );
+    assert_eq!(
+        resolve_data_scope(&[], 7, Some(2), &depts),
+        restricted(vec![], None)
+    );
+}
+
+#[test]
+fn test_most_permissive_scope_wins() {
+    let depts = common::depts();
+    assert_eq!(
+        resolve_data_scope(
+            &[
+                scope(DataScope::SelfOnly, vec![]),
+                scope(DataScope::All, vec![]),
+            ],
+            7,
+            Some(2),
+            &depts
+        ),
+        DataScopeFilter::All
+    );
+    assert_eq!(
+        resolve_data_scope(
+            &[
+                scope(DataScope::Dept, vec![]),
+                scope(DataScope::DeptAndChild, vec![]),
+                scope(DataScope::Custom, vec![5, 2]),
+                scope(DataScope::SelfOnly, vec![]),
+            ],
+            7,
+            Some(2),
+            &depts
+        ),
+        restricted(vec![2, 4, 5], Some(7))
+    );
+}
+
+#[test]
+fn test_data_scope_condition() {
+    let all = sys_user::Entity::find()
+        .filter_data_scope(
+            &DataScopeFilter::All,
+            sys_user::Column::DeptId,
+            sys_user::Column::Id,
+        )
+        .build(DbBackend::MySql)
+        .to_string();
+    assert!(!all.contains("WHERE"), "{}", all);
+
+    let sql = sys_user::Entity::find()
+        .filter_data_scope(
+            &restricted(vec![2, 4], Some(7)),
+            sys_user::Column::DeptId,
+            sys_user::Column::Id,
+        )
+        .build(DbBackend::MySql)
+        .to_string();
+    assert!(
+        sql.ends_with("WHERE `sys_user`.`dept_id` IN (2, 4) OR `sys_user`.`id` = 7"),
+        "{}",
+        sql
+    );
+
+    // 没有可见范围时不返回任何数据
+    let none = sys_user::Entity::find()
+        .filter_data_scope(
+            &restricted(vec![], None),
+            sys_user::Column::DeptId,
+            sys_user::Column::Id,
+        )
+        .build(DbBackend::MySql)
+        .to_string();
+    assert!(none.ends_with("WHERE 1 = 2"), "{}", none);
+}
+
+#[test]
+fn test_data_scope_contains() {
+    assert!(DataScopeFilter::All.contains(None, 1));
+
+    // 与查询条件一致：所属部门在范围内，或者是本人
+    let scope = restricted(vec![2, 4], Some(7));
+    assert!(scope.contains(Some(2), 1));
+    assert!(scope.contains(Some(4), 1));
+    assert!(scope.contains(None, 7));
+    assert!(scope.contains(Some(5), 7));
+    assert!(!scope.contains(Some(1), 1));
+    assert!(!scope.contains(None, 1));
+
+    assert!(!restricted(vec![], None).contains(Some(2), 7));
+    // 超出范围的用户返回 403
+    assert_eq!(
+        ApiError::Forbidden("User is out of your data scope".to_string()).status_code(),
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[test]
+fn test_create_user_in_sibling_dept() {
+    // 部门 2 的管理员只能管理 2 及其下级 4
+    let depts = common::depts();
+    let dept_scope = resolve_data_scope(
+        &[scope(DataScope::DeptAndChild, vec![])],
+        7,
+        Some(2),
+        &depts,
+    );
+    assert!(check_dept_in_scope(&dept_scope, 2, None).is_ok());
+    assert!(check_dept_in_scope(&dept_scope, 4, None).is_ok());
+    // 同级部门 3、其它部门树和不属于任何部门都超出范围
+    assert!(check_dept_in_scope(&dept_scope, 3, None).is_err());
+    assert!(check_dept_in_scope(&dept_scope, 5, None).is_err());
+    assert!(check_dept_in_scope(&dept_scope, 0, None).is_err());
+
+    // 只能看本人数据时，可以调整自己的部门，不能新建用户
+    let self_only = resolve_data_scope(&[scope(DataScope::SelfOnly, vec![])], 7, Some(2), &depts);
+    assert!(check_dept_in_scope(&self_only, 3, Some(7)).is_ok());
+    assert!(check_dept_in_scope(&self_only, 3, Some(8)).is_err());
+    assert!(check_dept_in_scope(&self_only, 2, None).is_err());
+
+    assert!(check_dept_in_scope(&DataScopeFilter::All, 0, None).is_ok());
+}
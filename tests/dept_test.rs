@@ -0,0 +1,72 @@
+// tests/dept_test.rs
+
+mod common;
+
+use rust_web::dto::admin::sys_dept_dto::DeptTreeDto;
+use rust_web::schemas::admin::sys_dept;
+use rust_web::services::admin::sys_dept_services::{build_dept_tree, collect_subtree_ids};
+
+fn dept(id: i32, parent_id: Option<i32>, order: i32) -> sys_dept::Model {
+    sys_dept::Model {
+        id,
+        parent_id,
+        dept_name: format!("dept_{}", id),
+        leader_id: None,
+        order,
+        create_user: "admin".to_string(),
+        create_time: None,
+        update_user: None,
+        update_time: None,
+        status: 1,
+    }
+}
+
+fn ids(nodes: &[DeptTreeDto]) -> Vec<i32> {
+    nodes.iter().map(|node| node.base.id).collect()
+}
+
+#[test]
+fn test_collect_subtree_ids() {
+    let depts = common::depts();
+    let mut subtree = collect_subtree_ids(&depts, 1);
+    subtree.sort_unstable();
+    assert_eq!(subtree, vec![1, 2, 3, 4]);
+    assert_eq!(collect_subtree_ids(&depts, 2), vec![2, 4]);
+    assert_eq!(collect_subtree_ids(&depts, 5), vec![5]);
+    assert!(collect_subtree_ids(&depts, 99).is_empty());
+
+    // 数据中已有的环不会导致死循环
+    let cyclic = vec![(1, Some(2)), (2, Some(1))];
+    let mut subtree = collect_subtree_ids(&cyclic, 1);
+    subtree.sort_unstable();
+    assert_eq!(subtree, vec![1, 2]);
+}
+
+#[test]
+fn test_build_dept_tree_sorts_by_order() {
+    let tree = build_dept_tree(vec![
+        dept(1, None, 2),
+        dept(2, Some(1), 1),
+        dept(3, Some(1), 0),
+        dept(4, Some(2), 0),
+        dept(5, None, 1),
+    ]);
+    assert_eq!(ids(&tree), vec![5, 1]);
+    assert_eq!(ids(&tree[1].children), vec![3, 2]);
+    assert_eq!(ids(&tree[1].children[1].children), vec![4]);
+    assert_eq!(tree[1].children[1].base.parent_id, 1);
+    assert!(tree[0].children.is_empty());
+}
+
+#[test]
+fn test_build_dept_tree_keeps_orphans_and_cycles() {
+    let tree = build_dept_tree(vec![
+        dept(1, Some(99), 0),
+        dept(2, Some(3), 0),
+        dept(3, Some(2), 0),
+    ]);
+    // 上级部门不存在的部门作为顶级部门，环中的部门也不会丢失
+    assert_eq!(ids(&tree), vec![1, 2]);
+    assert_eq!(ids(&tree[1].children), vec![3]);
+    assert!(tree[1].children[0].children.is_empty());
+}
@@ -1,13 +1,17 @@
 // tests/jwt_integration_test.rs
 
+use actix_web::test::TestRequest;
+use actix_web::{FromRequest, HttpMessage};
+use jsonwebtoken::{Header, TokenData};
 use rust_web::common::auth;
+use rust_web::common::auth::current_user::CurrentUser;
 use rust_web::common::auth::jwt;
 
 #[test]
 fn test_jwt_integration() {
     let user_name = "integration_test_user";
     let role = "user".to_string();
-    let jwt = jwt::generate_jwt(user_name.to_string(), vec![role]).expect("Failed to generate JWT");
+    let jwt = jwt::generate_jwt(1, user_name.to_string(), vec![role]).expect("Failed to generate JWT");
 
     assert!(!jwt.is_empty(), "JWT should not be empty");
 }
@@ -17,6 +21,7 @@ fn test_encryption_decryption() {
     let original_data = "Hello, Rust!";
     // 假设 generate_jwt 函数接受 Claims 结构体作为参数
     let claims = jwt::Claims {
+        user_id: 1,
         user_name: original_data.to_owned(),
         exp: 0, // 示例过期时间
         role_codes: vec![
@@ -24,11 +29,15 @@ fn test_encryption_decryption() {
             "admin1".to_string(),
             "admin2".to_string(),
         ],
+        jti: "".to_string(),
+        iat: 0,
+        iat_ms: 0,
     };
 
     // 加密原始数据
     let encrypted_data =
-        jwt::generate_jwt(claims.user_name.clone(), claims.role_codes.clone()).unwrap(); // 确保generate_jwt返回Result<String, Error>
+        jwt::generate_jwt(claims.user_id, claims.user_name.clone(), claims.role_codes.clone())
+            .unwrap(); // 确保generate_jwt返回Result<String, Error>
 
     // 尝试解密
     let token_data = jwt::decode_jwt(&encrypted_data).expect("Decryption failed"); // 确保decode_jwt正确处理并返回TokenData<Claims>
@@ -43,6 +52,35 @@ fn test_encryption_decryption() {
         claims.role_codes, decrypted_claims.role_codes,
         "Decrypted data does not match original"
     );
+    assert_eq!(
+        claims.user_id, decrypted_claims.user_id,
+        "Decrypted data does not match original"
+    );
+}
+
+#[test]
+fn test_jwt_unique_id() {
+    let first = jwt::generate_jwt(1, "jti_test_user".to_string(), vec![]).unwrap();
+    let second = jwt::generate_jwt(1, "jti_test_user".to_string(), vec![]).unwrap();
+
+    let first_claims = jwt::decode_jwt(&first).expect("Decryption failed").claims;
+    let second_claims = jwt::decode_jwt(&second).expect("Decryption failed").claims;
+
+    // 每个 token 都有独立的 jti，吊销一个不会影响另一个
+    assert!(!first_claims.jti.is_empty());
+    assert_ne!(first_claims.jti, second_claims.jti);
+    assert!(first_claims.iat <= first_claims.exp);
+}
+
+#[test]
+fn test_jwt_issued_at_millis() {
+    let before = jwt::now_timestamp_millis();
+    let token = jwt::generate_jwt(1, "iat_ms_test_user".to_string(), vec![]).unwrap();
+    let claims = jwt::decode_jwt(&token).expect("Decryption failed").claims;
+
+    // 吊销按毫秒比较，同一秒内吊销之后签发的 token 仍然有效
+    assert!(claims.iat_ms >= before);
+    assert_eq!(claims.iat_ms / 1000, claims.iat as u64);
 }
 
 #[test]
@@ -50,3 +88,26 @@ fn test_password_encryption() {
     let password_hash = auth::crypto::hash_password(Some("12345678".to_string())).unwrap(); // 假设这是一个外部函数，用于安全地散列密码
     println!("{}", password_hash)
 }
+
+#[actix_web::test]
+async fn test_current_user_extractor() {
+    let claims = jwt::Claims {
+        user_id: 7,
+        user_name: "current_user".to_string(),
+        role_codes: vec!["admin".to_string()],
+        ..jwt::Claims::new()
+    };
+    let req = TestRequest::default().to_http_request();
+    req.extensions_mut().insert(TokenData {
+        header: Header::default(),
+        claims,
+    });
+
+    let current_user = CurrentUser::extract(&req).await.expect("CurrentUser missing");
+    assert_eq!(current_user.user_id, 7);
+    assert_eq!(current_user.user_name, "current_user");
+
+    // 没有经过 JWTAuth 的请求取不到当前用户
+    let req = TestRequest::default().to_http_request();
+    assert!(CurrentUser::extract(&req).await.is_err());
+}
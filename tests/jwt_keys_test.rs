@@ -0,0 +1,222 @@
+// tests/jwt_keys_test.rs
+
+use jsonwebtoken::jwk::AlgorithmParameters;
+use rust_web::common::auth::jwt::Claims;
+use rust_web::common::auth::jwt_keys::JwtKeyStore;
+use rust_web::config::cfg::{JwtConfig, JwtKeyConfig};
+
+const ISSUER: &str = "rust-web";
+const AUDIENCE: &str = "rust-web";
+
+fn key_path(name: &str) -> Option<String> {
+    Some(format!(
+        "{}/tests/data/jwt/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    ))
+}
+
+fn key_config(
+    kid: &str,
+    algorithm: &str,
+    private_key: Option<&str>,
+    public_key: &str,
+) -> JwtKeyConfig {
+    JwtKeyConfig {
+        kid: kid.to_string(),
+        algorithm: algorithm.to_string(),
+        private_key_path: private_key.and_then(key_path),
+        public_key_path: key_path(public_key),
+        secret: None,
+    }
+}
+
+fn jwt_config(signing_kid: Option<&str>, keys: Vec<JwtKeyConfig>) -> JwtConfig {
+    JwtConfig {
+        secret: "test-secret".to_string(),
+        expire_time: 3600,
+        refresh_expire_time: 7200,
+        issuer: ISSUER.to_string(),
+        audience: AUDIENCE.to_string(),
+        signing_kid: signing_kid.map(str::to_string),
+        keys,
+    }
+}
+
+fn claims() -> Claims {
+    Claims {
+        user_id: 1,
+        user_name: "jwt_keys_user".to_string(),
+        exp: jsonwebtoken::get_current_timestamp() as usize + 600,
+        role_codes: vec!["R_ADMIN".to_string()],
+        ..Claims::new()
+    }
+}
+
+#[test]
+fn test_legacy_hs256_without_keys() {
+    let store = JwtKeyStore::from_config(&jwt_config(None, vec![])).unwrap();
+    assert!(store.jwks().keys.is_empty());
+
+    let token = store.encode(&claims(), ISSUER, AUDIENCE).unwrap();
+    let header = jsonwebtoken::decode_header(&token).unwrap();
+    assert_eq!(header.alg, jsonwebtoken::Algorithm::HS256);
+    assert!(header.kid.is_none());
+
+    let decoded = store.decode::<Claims>(&token, ISSUER, AUDIENCE).unwrap();
+    assert_eq!(decoded.claims.user_name, "jwt_keys_user");
+}
+
+#[test]
+fn test_asymmetric_keys_and_jwks() {
+    let config = jwt_config(
+        Some("ed"),
+        vec![
+            key_config("rsa", "RS256", Some("rsa_private.pem"), "rsa_public.pem"),
+            key_config("ec", "ES256", Some("ec_private.pem"), "ec_public.pem"),
+            key_config(
+                "ed",
+                "EdDSA",
+                Some("ed25519_private.pem"),
+                "ed25519_public.pem",
+            ),
+        ],
+    );
+    let store = JwtKeyStore::from_config(&config).unwrap();
+
+    let jwks = store.jwks();
+    assert_eq!(jwks.keys.len(), 3);
+    match &jwks.find("rsa").unwrap().algorithm {
+        AlgorithmParameters::RSA(rsa) => {
+            assert_eq!(rsa.e, "AQAB");
+            // 2048 位模数 base64url 编码后为 342 个字符
+            assert_eq!(rsa.n.len(), 342);
+        }
+        other => panic!("unexpected rsa jwk {:?}", other),
+    }
+    assert!(matches!(
+        jwks.find("ec").unwrap().algorithm,
+        AlgorithmParameters::EllipticCurve(_)
+    ));
+    assert!(matches!(
+        jwks.find("ed").unwrap().algorithm,
+        AlgorithmParameters::OctetKeyPair(_)
+    ));
+
+    let token = store.encode(&claims(), ISSUER, AUDIENCE).unwrap();
+    let header = jsonwebtoken::decode_header(&token).unwrap();
+    assert_eq!(header.kid.as_deref(), Some("ed"));
+    assert_eq!(header.alg, jsonwebtoken::Algorithm::EdDSA);
+    assert!(store.decode::<Claims>(&token, ISSUER, AUDIENCE).is_ok());
+
+    // 发布的 JWK 可以直接用于验签
+    let jwk = jwks.find("ed").unwrap();
+    let decoding_key = jsonwebtoken::DecodingKey::from_jwk(jwk).unwrap();
+    let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::EdDSA);
+    validation.set_audience(&[AUDIENCE]);
+    assert!(jsonwebtoken::decode::<Claims>(&token, &decoding_key, &validation).is_ok());
+}
+
+#[test]
+fn test_rotation_keeps_old_key_verifying() {
+    let old_config = jwt_config(
+        Some("2026-01"),
+        vec![key_config(
+            "2026-01",
+            "RS256",
+            Some("rsa_private.pem"),
+            "rsa_public.pem",
+        )],
+    );
+    let old_token = JwtKeyStore::from_config(&old_config)
+        .unwrap()
+        .encode(&claims(), ISSUER, AUDIENCE)
+        .unwrap();
+
+    // 轮换后旧 key 只保留公钥
+    let new_config = jwt_config(
+        Some("2026-07"),
+        vec![
+            key_config("2026-01", "RS256", None, "rsa_public.pem"),
+            key_config(
+                "2026-07",
+                "EdDSA",
+                Some("ed25519_private.pem"),
+                "ed25519_public.pem",
+            ),
+        ],
+    );
+    let store = JwtKeyStore::from_config(&new_config).unwrap();
+    assert!(store.decode::<Claims>(&old_token, ISSUER, AUDIENCE).is_ok());
+
+    let new_token = store.encode(&claims(), ISSUER, AUDIENCE).unwrap();
+    assert_eq!(
+        jsonwebtoken::decode_header(&new_token)
+            .unwrap()
+            .kid
+            .as_deref(),
+        Some("2026-07")
+    );
+
+    // 没有私钥的 key 不能作为签名 key
+    let invalid = jwt_config(
+        Some("2026-01"),
+        vec![key_config("2026-01", "RS256", None, "rsa_public.pem")],
+    );
+    assert!(JwtKeyStore::from_config(&invalid).is_err());
+}
+
+#[test]
+fn test_rejects_wrong_issuer_audience_and_unknown_kid() {
+    let config = jwt_config(
+        Some("rsa"),
+        vec![key_config(
+            "rsa",
+            "RS256",
+            Some("rsa_private.pem"),
+            "rsa_public.pem",
+        )],
+    );
+    let store = JwtKeyStore::from_config(&config).unwrap();
+    let token = store.encode(&claims(), ISSUER, AUDIENCE).unwrap();
+
+    assert!(store
+        .decode::<Claims>(&token, "other-issuer", AUDIENCE)
+        .is_err());
+    assert!(store
+        .decode::<Claims>(&token, ISSUER, "other-audience")
+        .is_err());
+
+    // 不带 kid 的旧 HS256 token 在配置 keys 后不再被接受
+    let legacy_token = JwtKeyStore::from_config(&jwt_config(None, vec![]))
+        .unwrap()
+        .encode(&claims(), ISSUER, AUDIENCE)
+        .unwrap();
+    assert!(store
+        .decode::<Claims>(&legacy_token, ISSUER, AUDIENCE)
+        .is_err());
+
+    // 公钥与算法不匹配时加载失败
+    let mismatched = jwt_config(
+        Some("rsa"),
+        vec![key_config(
+            "rsa",
+            "ES256",
+            Some("rsa_private.pem"),
+            "rsa_public.pem",
+        )],
+    );
+    assert!(JwtKeyStore::from_config(&mismatched).is_err());
+
+    // 私钥和公钥不是一对时加载失败
+    let mismatched_pair = jwt_config(
+        Some("ec"),
+        vec![key_config(
+            "ec",
+            "ES256",
+            Some("ec_private.pem"),
+            "ec_other_public.pem",
+        )],
+    );
+    assert!(JwtKeyStore::from_config(&mismatched_pair).is_err());
+}
@@ -0,0 +1,47 @@
+// tests/login_guard_test.rs
+
+use actix_web::test::TestRequest;
+use rust_web::common::client_ip::client_ip;
+use rust_web::config::cfg::SecurityConfig;
+use rust_web::services::admin::sys_auth_services::login_delay_ms;
+
+fn security_config() -> SecurityConfig {
+    SecurityConfig {
+        login_failure_window: 900,
+        login_delay_after: 3,
+        login_delay_step_ms: 500,
+        login_max_delay_ms: 2000,
+        login_max_failures: 10,
+        login_ip_max_failures: 20,
+        login_lockout_duration: 900,
+        password_reset_expire_time: 1800,
+        password_reset_url: "http://localhost/reset-password?token=".to_string(),
+        password_reset_cooldown: 60,
+        trust_proxy_headers: false,
+    }
+}
+
+#[test]
+fn test_login_delay_is_progressive() {
+    let config = security_config();
+    assert_eq!(login_delay_ms(1, &config), 0);
+    assert_eq!(login_delay_ms(2, &config), 0);
+    assert_eq!(login_delay_ms(3, &config), 500);
+    assert_eq!(login_delay_ms(4, &config), 1000);
+    // 延迟不会超过配置的上限
+    assert_eq!(login_delay_ms(9, &config), 2000);
+}
+
+#[test]
+fn test_client_ip_ignores_proxy_headers_unless_trusted() {
+    let req = TestRequest::default()
+        .peer_addr("10.0.0.1:5000".parse().unwrap())
+        .insert_header(("X-Forwarded-For", "203.0.113.7"))
+        .to_http_request();
+
+    // 未配置信任代理时伪造的请求头不影响限流使用的IP
+    let ip = client_ip(req.peer_addr(), &req.connection_info(), false);
+    assert_eq!(ip, Some("10.0.0.1".parse().unwrap()));
+    let ip = client_ip(req.peer_addr(), &req.connection_info(), true);
+    assert_eq!(ip, Some("203.0.113.7".parse().unwrap()));
+}
@@ -0,0 +1,135 @@
+// tests/mail_test.rs
+
+use rust_web::common::error::MyError;
+use rust_web::common::mail::log_mail::LogMailSender;
+use rust_web::common::mail::smtp::SmtpMailSender;
+use rust_web::common::mail::{Mail, MailSender};
+use rust_web::config::cfg::{MailConfig, MailSenderKind, SmtpSecurity};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+fn test_mail() -> Mail {
+    Mail {
+        to: "user@example.com".to_string(),
+        subject: "重置密码".to_string(),
+        body: "reset link".to_string(),
+    }
+}
+
+#[actix_web::test]
+async fn test_log_mail_sender_appends_to_file() {
+    let file_path = std::env::temp_dir().join(format!("mail_test_{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&file_path);
+    let sender = LogMailSender::new(Some(file_path.to_string_lossy().to_string()));
+
+    sender.send(test_mail()).await.unwrap();
+    sender.send(test_mail()).await.unwrap();
+
+    let content = std::fs::read_to_string(&file_path).unwrap();
+    assert_eq!(content.matches("To: user@example.com").count(), 2);
+    assert!(content.contains("reset link"));
+    let _ = std::fs::remove_file(&file_path);
+}
+
+// 模拟一个只接受明文连接的 SMTP 服务器，记录收到的命令
+#[actix_web::test]
+async fn test_smtp_mail_sender_conversation() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut commands = Vec::new();
+        writer.write_all(b"220 test ready\r\n").unwrap();
+        let mut in_data = false;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                break;
+            }
+            let line = line.trim_end().to_string();
+            if in_data {
+                if line == "." {
+                    in_data = false;
+                    writer.write_all(b"250 queued\r\n").unwrap();
+                }
+                continue;
+            }
+            commands.push(line.clone());
+            let reply: &[u8] = match line.as_str() {
+                l if l.starts_with("EHLO") => b"250-test\r\n250 AUTH LOGIN\r\n",
+                "AUTH LOGIN" => b"334 VXNlcm5hbWU6\r\n",
+                "dXNlcg==" => b"334 UGFzc3dvcmQ6\r\n",
+                "c2VjcmV0" => b"235 ok\r\n",
+                "DATA" => {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                }
+                "QUIT" => {
+                    writer.write_all(b"221 bye\r\n").unwrap();
+                    break;
+                }
+                _ => b"250 ok\r\n",
+            };
+            writer.write_all(reply).unwrap();
+        }
+        commands
+    });
+
+    let sender = SmtpMailSender::new(&MailConfig {
+        sender: MailSenderKind::Smtp,
+        from: "noreply@example.com".to_string(),
+        smtp_host: "127.0.0.1".to_string(),
+        smtp_port: port,
+        smtp_security: SmtpSecurity::None,
+        smtp_user: Some("user".to_string()),
+        smtp_password: Some("secret".to_string()),
+        smtp_timeout: 5,
+        file_path: None,
+    })
+    .unwrap();
+    sender.send(test_mail()).await.unwrap();
+
+    let commands = server.join().unwrap();
+    assert_eq!(
+        commands,
+        vec![
+            "EHLO localhost",
+            "AUTH LOGIN",
+            "dXNlcg==",
+            "c2VjcmV0",
+            "MAIL FROM:<noreply@example.com>",
+            "RCPT TO:<user@example.com>",
+            "DATA",
+            "QUIT",
+        ]
+    );
+}
+
+// 收件人地址中夹带换行时拒绝发送，不会连接 SMTP 服务器
+#[actix_web::test]
+async fn test_smtp_mail_sender_rejects_header_injection() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let sender = SmtpMailSender::new(&MailConfig {
+        sender: MailSenderKind::Smtp,
+        from: "noreply@example.com".to_string(),
+        smtp_host: "127.0.0.1".to_string(),
+        smtp_port: listener.local_addr().unwrap().port(),
+        smtp_security: SmtpSecurity::None,
+        smtp_user: None,
+        smtp_password: None,
+        smtp_timeout: 1,
+        file_path: None,
+    })
+    .unwrap();
+    let mail = Mail {
+        to: "user@example.com>\r\nRCPT TO:<attacker@example.com".to_string(),
+        ..test_mail()
+    };
+    let err = sender.send(mail).await.unwrap_err();
+    assert!(matches!(err, MyError::MailError(_)));
+    assert!(listener.accept().is_err());
+}
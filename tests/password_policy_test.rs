@@ -0,0 +1,71 @@
+// tests/password_policy_test.rs
+
+use rust_web::common::auth::crypto::{hash_password_with, needs_rehash, verify_password};
+use rust_web::common::auth::password_policy::check_password;
+use rust_web::config::cfg::PasswordConfig;
+
+fn password_config() -> PasswordConfig {
+    PasswordConfig {
+        min_length: 8,
+        max_length: 64,
+        require_uppercase: true,
+        require_lowercase: true,
+        require_digit: true,
+        require_special: false,
+        banned_passwords: vec!["Password1".to_string()],
+        disallow_user_name: true,
+        // 测试中使用较小的参数，加快哈希速度
+        argon2_memory_kib: 1024,
+        argon2_iterations: 1,
+        argon2_parallelism: 1,
+    }
+}
+
+#[test]
+fn test_password_policy() {
+    let config = password_config();
+    assert!(check_password("Secure123", "alice", &config).is_ok());
+    assert!(check_password("Sec123", "alice", &config).is_err());
+    assert!(check_password("secure123", "alice", &config).is_err());
+    assert!(check_password("SECURE123", "alice", &config).is_err());
+    assert!(check_password("SecurePass", "alice", &config).is_err());
+    // 禁用列表不区分大小写
+    assert!(check_password("PASSWORD1", "alice", &config).is_err());
+    assert!(check_password("MyAlice123", "alice", &config).is_err());
+
+    let config = PasswordConfig {
+        require_special: true,
+        disallow_user_name: false,
+        ..password_config()
+    };
+    assert!(check_password("Secure123", "alice", &config).is_err());
+    assert!(check_password("Secure123!", "alice", &config).is_ok());
+    assert!(check_password("MyAlice123!", "alice", &config).is_ok());
+}
+
+#[test]
+fn test_argon2_hash_and_rehash() {
+    let config = password_config();
+    let hash = hash_password_with("Secure123", &config).unwrap();
+    assert!(hash.starts_with("$argon2id$"));
+    assert!(verify_password("Secure123", &hash).unwrap());
+    assert!(!verify_password("Secure124", &hash).unwrap());
+    assert!(!needs_rehash(&hash, &config));
+
+    // 参数调整后，旧哈希依然可以验证，但需要重新生成
+    let stronger = PasswordConfig {
+        argon2_iterations: 2,
+        ..password_config()
+    };
+    assert!(verify_password("Secure123", &hash).unwrap());
+    assert!(needs_rehash(&hash, &stronger));
+}
+
+#[test]
+fn test_legacy_bcrypt_hash() {
+    let config = password_config();
+    let hash = bcrypt::hash("Secure123", 4).unwrap();
+    assert!(verify_password("Secure123", &hash).unwrap());
+    assert!(!verify_password("Secure124", &hash).unwrap());
+    assert!(needs_rehash(&hash, &config));
+}
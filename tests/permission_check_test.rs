@@ -0,0 +1,304 @@
+// tests/permission_check_test.rs
+
+use actix_redis::RedisActor;
+use actix_web::dev::Service;
+use actix_web::http::{Method, StatusCode};
+use actix_web::test::{call_service, init_service, try_call_service, TestRequest};
+use actix_web::{web, App, HttpMessage, HttpResponse};
+use jsonwebtoken::{Header, TokenData};
+use rust_web::common::auth::jwt::Claims;
+use rust_web::common::mail::log_mail::LogMailSender;
+use rust_web::config::globals::AppState;
+use rust_web::middleware::permission_check_middleware::PermissionCheck;
+use rust_web::schemas::admin::sea_orm_active_enums::{ActionCode, ApiMethod, PermissionEffect};
+use rust_web::services::admin::sys_authorization_services::{
+    action_code_of, evaluate_api_access, evaluate_subject_grants, is_super_role, match_api_path,
+    resolve_permission_effects, ApiGrant, ApiGrants, SubjectGrants,
+};
+use sea_orm::DatabaseConnection;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+
+// 只支持 GET 的 Redis 模拟服务：values 之外的键返回 nil，其它命令一律返回 OK
+fn start_mock_redis(values: HashMap<String, String>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let values = values.clone();
+            thread::spawn(move || {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    // 请求是由 bulk string 组成的数组：*N\r\n 后跟 N 个 $len\r\nvalue\r\n
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                        return;
+                    }
+                    let count: usize = line.trim_end()[1..].parse().unwrap();
+                    let mut args = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        let mut len_line = String::new();
+                        reader.read_line(&mut len_line).unwrap();
+                        let len: usize = len_line.trim_end()[1..].parse().unwrap();
+                        let mut arg = vec![0; len + 2];
+                        reader.read_exact(&mut arg).unwrap();
+                        args.push(String::from_utf8_lossy(&arg[..len]).to_string());
+                    }
+                    let reply = if args[0].eq_ignore_ascii_case("GET") {
+                        match values.get(&args[1]) {
+                            Some(value) => format!("${}\r\n{}\r\n", value.len(), value),
+                            None => "$-1\r\n".to_string(),
+                        }
+                    } else {
+                        "+OK\r\n".to_string()
+                    };
+                    stream.write_all(reply.as_bytes()).unwrap();
+                }
+            });
+        }
+    });
+    addr
+}
+
+#[test]
+fn test_match_api_path() {
+    assert!(match_api_path(
+        "/system-manage/users",
+        "/system-manage/users"
+    ));
+    assert!(match_api_path(
+        "/system-manage/users",
+        "/system-manage/users/"
+    ));
+    assert!(match_api_path(
+        "/system-manage/users/{user_id}",
+        "/system-manage/users/12"
+    ));
+    assert!(match_api_path(
+        "/system-manage/users/{user_id}/revoke-tokens",
+        "/system-manage/users/12/revoke-tokens"
+    ));
+    assert!(!match_api_path(
+        "/system-manage/users/{user_id}",
+        "/system-manage/users"
+    ));
+    assert!(!match_api_path(
+        "/system-manage/users",
+        "/system-manage/users/12"
+    ));
+    assert!(!match_api_path(
+        "/system-manage/users",
+        "/system-manage/roles"
+    ));
+}
+
+#[test]
+fn test_action_code_of() {
+    assert_eq!(action_code_of(&Method::GET), Some(ActionCode::Read));
+    assert_eq!(action_code_of(&Method::POST), Some(ActionCode::Create));
+    assert_eq!(action_code_of(&Method::PUT), Some(ActionCode::Update));
+    assert_eq!(action_code_of(&Method::DELETE), Some(ActionCode::Delete));
+    assert_eq!(action_code_of(&Method::PATCH), None);
+}
+
+#[test]
+fn test_api_grant_allows() {
+    let grant = ApiGrant {
+        api_path: "/system-manage/users/{user_id}".to_string(),
+        api_method: ApiMethod::Put,
+        actions: vec![ActionCode::Read, ActionCode::Update],
+    };
+    assert!(grant.allows(&Method::PUT, "/system-manage/users/1"));
+    // 请求方法与接口不一致
+    assert!(!grant.allows(&Method::DELETE, "/system-manage/users/1"));
+    assert!(!grant.allows(&Method::PUT, "/system-manage/roles/1"));
+
+    // 权限没有对应的操作
+    let read_only = ApiGrant {
+        actions: vec![ActionCode::Read],
+        ..grant
+    };
+    assert!(!read_only.allows(&Method::PUT, "/system-manage/users/1"));
+}
+
+#[test]
+fn test_is_super_role() {
+    let super_roles = vec!["R_SUPER".to_string()];
+    assert!(is_super_role(
+        &["R_ADMIN".to_string(), "R_SUPER".to_string()],
+        &super_roles
+    ));
+    assert!(!is_super_role(&["R_ADMIN".to_string()], &super_roles));
+}
+
+#[test]
+fn test_resolve_permission_effects() {
+    // 权限 2 同时被允许和拒绝，以拒绝为准
+    let permission_set = resolve_permission_effects(&[
+        (1, PermissionEffect::Allow),
+        (2, PermissionEffect::Allow),
+        (2, PermissionEffect::Deny),
+        (3, PermissionEffect::Deny),
+        (1, PermissionEffect::Allow),
+    ]);
+    assert_eq!(permission_set.allow_ids, vec![1]);
+    assert_eq!(permission_set.deny_ids, vec![2, 3]);
+}
+
+#[test]
+fn test_evaluate_api_access_deny_overrides() {
+    let users_grant = ApiGrant {
+        api_path: "/system-manage/users/{user_id}".to_string(),
+        api_method: ApiMethod::Delete,
+        actions: vec![ActionCode::Delete],
+    };
+    let role_grants = ApiGrants {
+        allow: vec![users_grant.clone()],
+        deny: Vec::new(),
+    };
+    let user_grants = ApiGrants {
+        allow: Vec::new(),
+        deny: vec![users_grant],
+    };
+    let method = Method::DELETE;
+    let path = "/system-manage/users/1";
+
+    assert!(evaluate_api_access(&[&role_grants], false, &method, path));
+    // 用户直接拒绝优先于角色授权，超级管理员同样受约束
+    assert!(!evaluate_api_access(
+        &[&role_grants, &user_grants],
+        false,
+        &method,
+        path
+    ));
+    assert!(!evaluate_api_access(&[&user_grants], true, &method, path));
+    // 未覆盖的请求只有超级管理员可以访问
+    let empty = ApiGrants::default();
+    assert!(!evaluate_api_access(&[&empty], false, &method, path));
+    assert!(evaluate_api_access(&[&empty], true, &method, path));
+    // 拒绝只作用于其覆盖的接口
+    assert!(evaluate_api_access(
+        &[&role_grants, &user_grants],
+        true,
+        &Method::GET,
+        path
+    ));
+}
+
+#[actix_web::test]
+async fn test_middleware_allows_direct_grant_without_roles() {
+    let user_id = 90_001;
+    let user_grants = SubjectGrants {
+        grants: ApiGrants {
+            allow: vec![ApiGrant {
+                api_path: "/system-manage/users".to_string(),
+                api_method: ApiMethod::Get,
+                actions: vec![ActionCode::Read],
+            }],
+            deny: Vec::new(),
+        },
+        super_role: false,
+    };
+    // 权限缓存的版本号不存在时为 0，用户直接授权从 Redis 缓存读取，不会访问数据库
+    let redis_addr = start_mock_redis(HashMap::from([(
+        format!("perm_cache:0:user:{}", user_id),
+        serde_json::to_string(&user_grants).unwrap(),
+    )]));
+    let app_state = web::Data::new(AppState {
+        redis_conn: RedisActor::start(redis_addr),
+        mysql_conn: Arc::new(DatabaseConnection::Disconnected),
+        mail_sender: Arc::new(LogMailSender::new(None)),
+    });
+
+    let app = init_service(
+        App::new()
+            .app_data(app_state)
+            .wrap(PermissionCheck)
+            .wrap_fn(move |req, srv| {
+                // 没有任何角色的用户
+                req.extensions_mut().insert(TokenData {
+                    header: Header::default(),
+                    claims: Claims {
+                        user_id,
+                        user_name: "direct_grant_user".to_string(),
+                        ..Claims::new()
+                    },
+                });
+                srv.call(req)
+            })
+            .route("/system-manage/users", web::get().to(HttpResponse::Ok))
+            .route("/system-manage/roles", web::get().to(HttpResponse::Ok)),
+    )
+    .await;
+
+    let resp = call_service(
+        &app,
+        TestRequest::get().uri("/system-manage/users").to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // 直接授权之外的接口仍然被拒绝
+    let err = try_call_service(
+        &app,
+        TestRequest::get().uri("/system-manage/roles").to_request(),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.as_response_error().status_code(), StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn test_evaluate_subject_grants() {
+    let grant = ApiGrant {
+        api_path: "/system-manage/users".to_string(),
+        api_method: ApiMethod::Get,
+        actions: vec![ActionCode::Read],
+    };
+    let allow = Arc::new(SubjectGrants {
+        grants: ApiGrants {
+            allow: vec![grant.clone()],
+            deny: Vec::new(),
+        },
+        super_role: false,
+    });
+    let deny = Arc::new(SubjectGrants {
+        grants: ApiGrants {
+            allow: Vec::new(),
+            deny: vec![grant],
+        },
+        super_role: false,
+    });
+    let super_role = Arc::new(SubjectGrants {
+        super_role: true,
+        ..SubjectGrants::default()
+    });
+    let path = "/system-manage/users";
+
+    // 只有用户直接授权也能访问
+    assert!(evaluate_subject_grants(
+        &[allow.clone()],
+        &Method::GET,
+        path
+    ));
+    assert!(!evaluate_subject_grants(
+        &[SubjectGrants::default().into()],
+        &Method::GET,
+        path
+    ));
+    // 超级管理员角色放行未授权的接口，但仍受拒绝约束
+    assert!(evaluate_subject_grants(
+        &[super_role.clone()],
+        &Method::GET,
+        path
+    ));
+    assert!(!evaluate_subject_grants(
+        &[super_role, allow, deny],
+        &Method::GET,
+        path
+    ));
+}
@@ -0,0 +1,87 @@
+// tests/role_hierarchy_test.rs
+
+use rust_web::common::auth::role_hierarchy::{RoleHierarchy, RoleNode};
+
+fn node(id: i32, parent_role_id: Option<i32>, enabled: bool) -> RoleNode {
+    RoleNode {
+        id,
+        parent_role_id,
+        enabled,
+    }
+}
+
+// 1(staff) <- 2(manager) <- 3(director)，4 独立，5(disabled) <- 6
+fn hierarchy() -> RoleHierarchy {
+    RoleHierarchy::new(vec![
+        node(1, None, true),
+        node(2, Some(1), true),
+        node(3, Some(2), true),
+        node(4, None, true),
+        node(5, Some(1), false),
+        node(6, Some(5), true),
+    ])
+}
+
+#[test]
+fn test_effective_role_ids_are_transitive() {
+    let hierarchy = hierarchy();
+    assert_eq!(hierarchy.inheritance_chain(3), vec![3, 2, 1]);
+    assert_eq!(hierarchy.inheritance_chain(1), vec![1]);
+    assert_eq!(hierarchy.effective_role_ids(&[3, 4, 2]), vec![3, 2, 1, 4]);
+    assert!(hierarchy.effective_role_ids(&[99]).is_empty());
+}
+
+#[test]
+fn test_disabled_role_stops_inheritance() {
+    let hierarchy = hierarchy();
+    assert!(hierarchy.inheritance_chain(5).is_empty());
+    // 6 继承的 5 已禁用，5 及其祖先的权限都不再传递
+    assert_eq!(hierarchy.inheritance_chain(6), vec![6]);
+}
+
+#[test]
+fn test_lineage_includes_disabled_roles() {
+    let hierarchy = hierarchy();
+    // 解释授权时需要看到被禁用角色截断的继承链
+    assert_eq!(hierarchy.lineage(6), vec![6, 5, 1]);
+    assert_eq!(hierarchy.lineage(3), vec![3, 2, 1]);
+    assert!(hierarchy.lineage(99).is_empty());
+}
+
+#[test]
+fn test_cycle_detection() {
+    let hierarchy = hierarchy();
+    assert!(hierarchy.would_create_cycle(1, 3));
+    assert!(hierarchy.would_create_cycle(2, 2));
+    assert!(hierarchy.would_create_cycle(1, 6));
+    assert!(!hierarchy.would_create_cycle(3, 4));
+    assert!(!hierarchy.would_create_cycle(4, 3));
+    assert!(!hierarchy.would_create_cycle(1, 99));
+
+    // 数据中已有的环不会导致死循环
+    let cyclic = RoleHierarchy::new(vec![
+        node(1, Some(2), true),
+        node(2, Some(1), true),
+        node(3, None, true),
+    ]);
+    assert_eq!(cyclic.inheritance_chain(1), vec![1, 2]);
+    assert!(!cyclic.would_create_cycle(3, 1));
+    assert!(cyclic.would_create_cycle(1, 2));
+}
+
+#[test]
+fn test_tree_roots_and_children() {
+    let hierarchy = hierarchy();
+    assert_eq!(hierarchy.roots(), vec![1, 4]);
+    assert_eq!(hierarchy.children(1), vec![2, 5]);
+    assert_eq!(hierarchy.children(3), Vec::<i32>::new());
+
+    // 父角色不存在的角色作为根，环中 id 最小的角色作为根
+    let broken = RoleHierarchy::new(vec![
+        node(1, Some(99), true),
+        node(2, Some(3), true),
+        node(3, Some(2), true),
+        node(4, Some(3), true),
+    ]);
+    assert_eq!(broken.roots(), vec![1, 2]);
+}
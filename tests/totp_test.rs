@@ -0,0 +1,71 @@
+// tests/totp_test.rs
+
+use rust_web::common::auth::totp::{
+    base32_decode, base32_encode, generate_secret, hotp, otpauth_uri, verify_code,
+};
+use rust_web::config::cfg::TwoFactorConfig;
+
+// RFC 6238 附录 B 中 SHA1 的测试密钥
+const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+#[test]
+fn test_base32_round_trip() {
+    assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+    assert_eq!(base32_decode("MZXW6YTBOI").unwrap(), b"foobar");
+    assert_eq!(base32_decode("mzxw 6ytb oi======").unwrap(), b"foobar");
+    assert!(base32_decode("MZXW1").is_none());
+
+    let secret = generate_secret();
+    assert_eq!(base32_decode(&secret).unwrap().len(), 20);
+}
+
+#[test]
+fn test_rfc6238_vectors() {
+    assert_eq!(hotp(RFC_SECRET, 59 / 30, 8).unwrap(), "94287082");
+    assert_eq!(hotp(RFC_SECRET, 1111111109 / 30, 8).unwrap(), "07081804");
+    assert_eq!(hotp(RFC_SECRET, 1234567890 / 30, 8).unwrap(), "89005924");
+    assert_eq!(hotp(RFC_SECRET, 20000000000 / 30, 8).unwrap(), "65353130");
+}
+
+#[test]
+fn test_verify_code_with_skew() {
+    let secret = base32_encode(RFC_SECRET);
+    let timestamp = 1111111109;
+    let code = hotp(RFC_SECRET, timestamp / 30, 6).unwrap();
+
+    assert_eq!(
+        verify_code(&secret, &code, timestamp, 30, 6, 1),
+        Some(timestamp / 30)
+    );
+    // 允许前后一个时间步的时钟误差
+    assert!(verify_code(&secret, &code, timestamp + 30, 30, 6, 1).is_some());
+    assert!(verify_code(&secret, &code, timestamp + 90, 30, 6, 1).is_none());
+    assert!(verify_code(&secret, "12345", timestamp, 30, 6, 1).is_none());
+    assert!(verify_code(&secret, "abcdef", timestamp, 30, 6, 1).is_none());
+}
+
+#[test]
+fn test_otpauth_uri() {
+    let uri = otpauth_uri("rust web", "admin", "MZXW6YTBOI", 6, 30);
+    assert_eq!(
+        uri,
+        "otpauth://totp/rust%20web:admin?secret=MZXW6YTBOI&issuer=rust%20web&algorithm=SHA1&digits=6&period=30"
+    );
+}
+
+#[test]
+fn test_unsupported_digits() {
+    let secret = base32_encode(RFC_SECRET);
+    // 超出 6 到 8 位时不计算验证码，而不是溢出
+    assert!(hotp(RFC_SECRET, 1, 10).is_none());
+    assert!(verify_code(&secret, "1234567890", 1111111109, 30, 10, 1).is_none());
+
+    let config = |digits: u32| {
+        format!(
+            "issuer = \"rust-web\"\nperiod = 30\ndigits = {}\nskew = 1\nchallenge_expire_time = 300\nmax_failures = 5\nfailure_window = 900\nlockout_duration = 900\nenroll_expire_time = 600\nrecovery_code_count = 10\n",
+            digits
+        )
+    };
+    assert!(toml::from_str::<TwoFactorConfig>(&config(6)).is_ok());
+    assert!(toml::from_str::<TwoFactorConfig>(&config(10)).is_err());
+}